@@ -0,0 +1,131 @@
+// 认证/授权提取器
+// AuthUser：校验 Bearer 访问令牌，并确认其会话族未被吊销（登出/重用检测后立即失效）；
+//   角色不信任令牌内的 role 声明，而是经短 TTL 缓存读取 users.role，链上撤销授权后很快生效。
+// Authorized<R>：在 AuthUser 基础上要求持有角色 R（admin 视为拥有全部角色）。
+use axum::{async_trait, extract::FromRequestParts, http::header::AUTHORIZATION, http::request::Parts};
+use shared::db::pool::Db;
+use shared::domain::user::Role;
+use shared::error::ApiError;
+use shared::repo::user_repo::UserRepo;
+use shared::security::jwt::{self, Claims};
+use std::collections::{BTreeSet, HashMap};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::state::AppState;
+
+#[derive(Clone)]
+pub struct RoleCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, (Instant, BTreeSet<Role>)>>>,
+}
+
+impl RoleCache {
+    pub fn new(ttl_secs: u64) -> Self {
+        Self {
+            ttl: Duration::from_secs(ttl_secs),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn get(&self, db: &Db, wallet: &str) -> anyhow::Result<BTreeSet<Role>> {
+        if let Some((at, roles)) = self.entries.lock().ok().and_then(|m| m.get(wallet).cloned()) {
+            if at.elapsed() < self.ttl {
+                return Ok(roles);
+            }
+        }
+        let roles = UserRepo::new(db)
+            .find(wallet)
+            .await?
+            .map(|u| Role::parse_set(&u.role))
+            .unwrap_or_default();
+        if let Ok(mut m) = self.entries.lock() {
+            m.insert(wallet.to_string(), (Instant::now(), roles.clone()));
+        }
+        Ok(roles)
+    }
+}
+
+pub struct AuthUser {
+    pub claims: Claims,
+    pub roles: BTreeSet<Role>,
+}
+
+impl AuthUser {
+    pub fn wallet(&self) -> &str {
+        &self.claims.sub
+    }
+
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&Role::Admin) || self.roles.contains(&role)
+    }
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "auth lookup failed");
+    ApiError::Internal
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for AuthUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, ApiError> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or(ApiError::Unauthorized)?;
+        let claims = jwt::decode(&state.cfg.jwt_secret, token).map_err(|_| ApiError::Unauthorized)?;
+        if !state.sessions.is_active(&claims.sid).await.map_err(internal)? {
+            return Err(ApiError::Unauthorized);
+        }
+        let roles = state.roles.get(&state.db, &claims.sub).await.map_err(internal)?;
+        Ok(AuthUser { claims, roles })
+    }
+}
+
+pub trait RoleGuard: Send + Sync {
+    const ROLE: Role;
+}
+
+pub struct Organizer;
+pub struct Verifier;
+pub struct Minter;
+pub struct Admin;
+
+impl RoleGuard for Organizer {
+    const ROLE: Role = Role::Organizer;
+}
+impl RoleGuard for Verifier {
+    const ROLE: Role = Role::Verifier;
+}
+impl RoleGuard for Minter {
+    const ROLE: Role = Role::Minter;
+}
+impl RoleGuard for Admin {
+    const ROLE: Role = Role::Admin;
+}
+
+pub struct Authorized<R: RoleGuard> {
+    pub user: AuthUser,
+    _role: PhantomData<R>,
+}
+
+#[async_trait]
+impl<R: RoleGuard> FromRequestParts<Arc<AppState>> for Authorized<R> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, ApiError> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if !user.has_role(R::ROLE) {
+            return Err(ApiError::Forbidden);
+        }
+        Ok(Authorized {
+            user,
+            _role: PhantomData,
+        })
+    }
+}
@@ -1,23 +1,22 @@
-use alloy::primitives::U256;
+use alloy::providers::Provider;
 use axum::{
     extract::State,
-    routing::{get, post},
+    routing::get,
     Json, Router,
 };
-use chrono::{Duration, Utc};
 use shared::contracts::provider::build_provider;
 use shared::contracts::registry::{resolve_addresses, spawn_registry_watcher, AddressCache};
+use shared::security::session::SessionStore;
 use shared::seed;
-use shared::{db::pool::Db, domain::event::NewEvent, repo::event_repo::EventRepo, AppConfig};
+use shared::{db::pool::Db, AppConfig};
 use std::sync::Arc;
 use tracing_subscriber::{fmt, EnvFilter}; // 示例保留
 
-struct AppState {
-    db: Db,
-    cache: AddressCache,
-    cfg: AppConfig,
-    chain_id: i64,
-}
+mod extract;
+mod routes;
+mod state;
+
+use state::AppState;
 
 #[tokio::main]
 async fn main() {
@@ -55,45 +54,54 @@ async fn main() {
     )
     .await;
 
+    let redis = redis::Client::open(cfg.redis_url.as_str())
+        .expect("redis url")
+        .get_connection_manager()
+        .await
+        .expect("redis connect");
+    let sessions = SessionStore::new(redis.clone(), cfg.jwt_refresh_ttl_secs);
+
     let state = Arc::new(AppState {
         db,
         cache,
         cfg: cfg.clone(),
         chain_id,
+        provider,
+        sessions,
+        roles: extract::RoleCache::new(cfg.role_cache_ttl_secs),
+        redis,
     });
 
     let app = Router::new()
         .route("/health", get(health))
-        .route("/events/demo-create", post(demo_create_event))
         .route("/contracts/addresses", get(get_addresses))
+        .merge(routes::events::router())
+        .merge(routes::drafts::router())
+        .merge(routes::tickets::router())
+        .merge(routes::metadata::router())
+        .merge(routes::market::router())
+        .merge(routes::auctions::router())
+        .merge(routes::resale::router())
+        .merge(routes::whitelist::router())
+        .merge(routes::limits::router())
+        .merge(routes::swap::router())
+        .merge(routes::tx::router())
+        .merge(routes::permit::router())
+        .merge(routes::auth::router())
+        .merge(routes::admin::router())
         .with_state(state);
 
-    let addr = cfg.listen_addr.parse().expect("listen addr");
-    tracing::info!(?addr, chain_id, "api listening");
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+    let listener = tokio::net::TcpListener::bind(&cfg.listen_addr)
         .await
-        .unwrap();
+        .expect("listen addr");
+    tracing::info!(addr = %cfg.listen_addr, chain_id, "api listening");
+    axum::serve(listener, app).await.unwrap();
 }
 
 async fn health() -> &'static str {
     "ok"
 }
 
-async fn demo_create_event(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
-    let repo = EventRepo::new(&state.db);
-    let now = Utc::now();
-    let new = NewEvent {
-        organizer_wallet: "0xDemo",
-        start_time: now + Duration::hours(24),
-        end_time: now + Duration::hours(30),
-        venue: Some("Demo Venue"),
-        status: "draft",
-    };
-    let ev = repo.insert(&new).await.expect("insert");
-    Json(serde_json::json!({"id": ev.id, "status": ev.status}))
-}
-
 async fn get_addresses(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     if let Some(a) = state.cache.get() {
         Json(serde_json::json!({
@@ -0,0 +1,76 @@
+// 平台管理接口（需 admin 角色）
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use shared::domain::swap::flash::FlashLoan;
+use shared::error::ApiError;
+use shared::repo::flash_repo::FlashRepo;
+use std::sync::Arc;
+
+use crate::extract::{Admin, Authorized};
+use crate::state::AppState;
+
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Serialize)]
+struct RevokeResp {
+    wallet: String,
+    revoked_sessions: usize,
+}
+
+#[derive(Deserialize)]
+struct FlashLoanQuery {
+    // true 时只返回触发告警的闪电贷
+    alerted: Option<bool>,
+    // confirmed | suspected
+    status: Option<String>,
+    limit: Option<i64>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/admin/users/:wallet/revoke-sessions", post(revoke_sessions))
+        .route("/admin/flash-loans", get(flash_loans))
+}
+
+async fn revoke_sessions(
+    auth: Authorized<Admin>,
+    State(state): State<Arc<AppState>>,
+    Path(wallet): Path<String>,
+) -> Result<Json<RevokeResp>, ApiError> {
+    let wallet = wallet.to_lowercase();
+    let revoked_sessions = state.sessions.revoke_wallet(&wallet).await.map_err(|e| {
+        tracing::error!(?e, "revoke sessions failed");
+        ApiError::Internal
+    })?;
+    tracing::warn!(%wallet, revoked_sessions, by = %auth.user.wallet(), "sessions revoked by admin");
+    Ok(Json(RevokeResp {
+        wallet,
+        revoked_sessions,
+    }))
+}
+
+async fn flash_loans(
+    _auth: Authorized<Admin>,
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<FlashLoanQuery>,
+) -> Result<Json<Vec<FlashLoan>>, ApiError> {
+    let limit = q.limit.unwrap_or(50);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    if let Some(s) = q.status.as_deref().filter(|s| !matches!(*s, "confirmed" | "suspected")) {
+        return Err(ApiError::BadRequest(format!("unknown status {s}")));
+    }
+    FlashRepo::new(&state.db)
+        .list(state.chain_id, q.alerted.unwrap_or(false), q.status.as_deref(), limit)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!(?e, "list flash loans failed");
+            ApiError::Internal
+        })
+}
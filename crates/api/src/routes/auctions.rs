@@ -0,0 +1,186 @@
+// 拍卖浏览与出价辅助：列表/出价历史取索引数据，详情叠加链上 auctions(id) 实时状态
+// 注：当前 Marketplace 合约未暴露出价入口，这里只提供查询与规则说明，不构建出价交易
+use alloy::primitives::{Address, U256};
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::contracts::bindings::Marketplace;
+use shared::contracts::registry::ContractAddresses;
+use shared::contracts::revert;
+use shared::domain::market::auction::{self, AntiSniping, Auction, AuctionBid, AuctionStatus, MinBid};
+use shared::error::ApiError;
+use shared::repo::market_repo::MarketRepo;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+struct LiveQuery {
+    event_id: Option<i64>,
+    // 仅返回该秒数内结束的拍卖
+    ending_within_secs: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct AuctionDetail {
+    #[serde(flatten)]
+    auction: Auction,
+    accepts_eth: bool,
+    bids: Vec<AuctionBid>,
+    bidding: Option<BidGuide>,
+}
+
+// 仅进行中的拍卖给出
+#[derive(Serialize)]
+struct BidGuide {
+    min_bids: Vec<MinBid>,
+    reserve_met: bool,
+    anti_sniping: AntiSniping,
+}
+
+#[derive(Serialize)]
+struct BidBalance {
+    wallet: String,
+    token_wei: U256,
+    eth_wei: U256,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/market/auctions", get(live_auctions))
+        .route("/market/auctions/:id", get(get_auction))
+        .route("/market/auctions/:id/bids", get(auction_bids))
+        .route("/wallets/:address/bid-balance", get(bid_balance))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "auctions request failed");
+    ApiError::Internal
+}
+
+fn addresses(state: &AppState) -> Result<ContractAddresses, ApiError> {
+    state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })
+}
+
+fn chain_error(e: alloy::contract::Error) -> ApiError {
+    match revert::from_contract_error(&e) {
+        Some(r) => r.into(),
+        None => internal(e.into()),
+    }
+}
+
+fn auction_id(id: &str) -> Result<U256, ApiError> {
+    id.parse()
+        .map_err(|_| ApiError::BadRequest("auction id must be a decimal integer".into()))
+}
+
+async fn live_auctions(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<LiveQuery>,
+) -> Result<Json<Vec<Auction>>, ApiError> {
+    let limit = q.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    let ends_before = match q.ending_within_secs {
+        Some(s) if s <= 0 => return Err(ApiError::BadRequest("ending_within_secs must be positive".into())),
+        Some(s) => Some(Utc::now() + Duration::seconds(s)),
+        None => None,
+    };
+    MarketRepo::new(&state.db)
+        .live_auctions(state.chain_id, q.event_id, ends_before, limit)
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+async fn get_auction(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<AuctionDetail>, ApiError> {
+    let aid = auction_id(&id)?;
+    let repo = MarketRepo::new(&state.db);
+    let mut a = repo
+        .find_auction(state.chain_id, &aid.to_string())
+        .await
+        .map_err(internal)?
+        .ok_or(ApiError::NotFound)?;
+    let bids = repo
+        .auction_bids(state.chain_id, &a.auction_id)
+        .await
+        .map_err(internal)?;
+
+    // 结束时间可能已被防狙击规则延长，状态与最高价以链上为准
+    let addrs = addresses(&state)?;
+    let mk = Marketplace::new(addrs.marketplace, state.provider.clone());
+    let live = mk.auctions(aid).call().await.map_err(chain_error)?;
+    let extension = mk.auctionExtensionTime().call().await.map_err(chain_error)?._0;
+    a.end_time = DateTime::from_timestamp(live.endTime.saturating_to::<i64>(), 0).unwrap_or(a.end_time);
+    if let Some(s) = AuctionStatus::from_chain(live.status) {
+        a.status = s.as_str().to_string();
+    }
+    let current = (live.currentBidder != Address::ZERO).then_some((live.currentBid, live.isEthBid));
+    if let Some((bid, is_eth)) = current {
+        a.current_bid_wei = Some(bid.to_string());
+        a.current_bidder = Some(format!("{:#x}", live.currentBidder));
+        a.current_bid_is_eth = Some(is_eth);
+    }
+
+    let now = Utc::now();
+    let bidding = (a.status == AuctionStatus::Active.as_str() && a.end_time > now).then(|| BidGuide {
+        min_bids: auction::min_bids(live.startingPrice, live.ethStartingPrice, live.acceptsEth, current),
+        reserve_met: current.is_some_and(|(bid, _)| bid >= live.reservePrice),
+        anti_sniping: auction::anti_sniping(a.end_time, extension.saturating_to(), now),
+    });
+    Ok(Json(AuctionDetail {
+        auction: a,
+        accepts_eth: live.acceptsEth,
+        bids,
+        bidding,
+    }))
+}
+
+async fn auction_bids(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<AuctionBid>>, ApiError> {
+    let id = auction_id(&id)?.to_string();
+    let repo = MarketRepo::new(&state.db);
+    if repo.find_auction(state.chain_id, &id).await.map_err(internal)?.is_none() {
+        return Err(ApiError::NotFound);
+    }
+    repo.auction_bids(state.chain_id, &id)
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+// 出价保证金余额（链上实时读取）
+async fn bid_balance(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+) -> Result<Json<BidBalance>, ApiError> {
+    let wallet: Address = address
+        .parse()
+        .map_err(|_| ApiError::BadRequest("invalid wallet address".into()))?;
+    let addrs = addresses(&state)?;
+    let mk = Marketplace::new(addrs.marketplace, state.provider.clone());
+    let token_wei = mk.userBidBalance(wallet).call().await.map_err(chain_error)?._0;
+    let eth_wei = mk.userEthBidBalance(wallet).call().await.map_err(chain_error)?._0;
+    Ok(Json(BidBalance {
+        wallet: format!("{wallet:#x}"),
+        token_wei,
+        eth_wei,
+    }))
+}
@@ -0,0 +1,203 @@
+// SIWE 登录：challenge 签发一次性 nonce 与待签名消息，verify 校验签名后签发访问/刷新令牌
+// refresh 轮换刷新令牌，logout 吊销当前会话族
+use alloy::primitives::{hex, Address};
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::error::ApiError;
+use shared::repo::user_repo::UserRepo;
+use shared::security::jwt;
+use shared::security::session::{Rotation, Session};
+use shared::security::siwe::{generate_nonce, verify_signature, SiweMessage};
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+// 挑战有效期
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+// 单钱包并存的未使用挑战上限（challenge 无需登录，防止无限堆积）
+const MAX_OUTSTANDING_CHALLENGES: i64 = 5;
+const STATEMENT: &str = "Sign in to OnlineTicket";
+
+#[derive(Deserialize)]
+struct ChallengeReq {
+    address: String,
+}
+
+#[derive(Serialize)]
+struct ChallengeResp {
+    message: String,
+    nonce: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct VerifyReq {
+    message: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct RefreshReq {
+    refresh_token: String,
+}
+
+#[derive(Serialize)]
+struct TokenResp {
+    access_token: String,
+    token_type: &'static str,
+    expires_at: DateTime<Utc>,
+    refresh_token: String,
+    refresh_expires_at: DateTime<Utc>,
+    wallet: String,
+    role: String,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/auth/siwe/challenge", post(challenge))
+        .route("/auth/siwe/verify", post(verify))
+        .route("/auth/refresh", post(refresh))
+        .route("/auth/logout", post(logout))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "auth request failed");
+    ApiError::Internal
+}
+
+// 数据库中统一使用小写地址
+fn wallet_key(a: &Address) -> String {
+    format!("{a:#x}")
+}
+
+async fn challenge(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ChallengeReq>,
+) -> Result<Json<ChallengeResp>, ApiError> {
+    let address: Address = req
+        .address
+        .parse()
+        .map_err(|_| ApiError::BadRequest("invalid address".into()))?;
+    let now = Utc::now();
+    let expires_at = now + Duration::minutes(CHALLENGE_TTL_MINUTES);
+    let msg = SiweMessage {
+        domain: state.cfg.siwe_domain.clone(),
+        address,
+        statement: Some(STATEMENT.into()),
+        uri: state.cfg.siwe_uri.clone(),
+        version: "1".into(),
+        chain_id: state.chain_id as u64,
+        nonce: generate_nonce(),
+        issued_at: now,
+        expiration_time: Some(expires_at),
+        not_before: None,
+        request_id: None,
+        resources: vec![],
+    };
+    UserRepo::new(&state.db)
+        .put_login_nonce(&wallet_key(&address), &msg.nonce, expires_at, MAX_OUTSTANDING_CHALLENGES)
+        .await
+        .map_err(internal)?;
+    Ok(Json(ChallengeResp {
+        message: msg.to_string(),
+        nonce: msg.nonce,
+        expires_at,
+    }))
+}
+
+async fn verify(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyReq>,
+) -> Result<Json<TokenResp>, ApiError> {
+    let msg = SiweMessage::parse(&req.message).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if msg.uri != state.cfg.siwe_uri {
+        return Err(ApiError::BadRequest("uri mismatch".into()));
+    }
+    msg.validate(&state.cfg.siwe_domain, state.chain_id as u64, Utc::now())
+        .map_err(|e| {
+            tracing::info!(%e, address = %msg.address, "siwe message rejected");
+            ApiError::Unauthorized
+        })?;
+    let signature = hex::decode(req.signature.trim())
+        .map_err(|_| ApiError::BadRequest("signature must be hex".into()))?;
+
+    let wallet = wallet_key(&msg.address);
+    let users = UserRepo::new(&state.db);
+    // 先验签再消费 nonce：未持有私钥的请求无法作废他人的挑战；签名有效的同一挑战只能用一次
+    let ok = verify_signature(&state.provider, msg.address, &req.message, &signature)
+        .await
+        .map_err(internal)?;
+    if !ok {
+        tracing::info!(%wallet, "siwe signature invalid");
+        return Err(ApiError::Unauthorized);
+    }
+    if !users.consume_login_nonce(&wallet, &msg.nonce).await.map_err(internal)? {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let user = users
+        .upsert_on_login(&wallet, &generate_nonce())
+        .await
+        .map_err(internal)?;
+    let (session, refresh_token) = state.sessions.create(&user.wallet).await.map_err(internal)?;
+    tracing::info!(%wallet, role = %user.role, "siwe login");
+    token_pair(&state, &session, &user.role, refresh_token)
+}
+
+// 角色每次刷新时重新读取，链上角色变更最迟在下一次刷新生效
+async fn refresh(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RefreshReq>,
+) -> Result<Json<TokenResp>, ApiError> {
+    match state.sessions.rotate(&req.refresh_token).await.map_err(internal)? {
+        Rotation::Rotated(session, next) => {
+            let role = UserRepo::new(&state.db)
+                .find(&session.wallet)
+                .await
+                .map_err(internal)?
+                .map(|u| u.role)
+                .ok_or(ApiError::Unauthorized)?;
+            token_pair(&state, &session, &role, next)
+        }
+        Rotation::Reused(session) => {
+            metrics::counter!("auth_refresh_reuse_total").increment(1);
+            tracing::warn!(wallet = %session.wallet, family = %session.family, "refresh token reuse, session revoked");
+            Err(ApiError::Unauthorized)
+        }
+        Rotation::Invalid => Err(ApiError::Unauthorized),
+    }
+}
+
+async fn logout(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<RefreshReq>,
+) -> Result<StatusCode, ApiError> {
+    state.sessions.logout(&req.refresh_token).await.map_err(internal)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn token_pair(
+    state: &AppState,
+    session: &Session,
+    role: &str,
+    refresh_token: String,
+) -> Result<Json<TokenResp>, ApiError> {
+    let (access_token, claims) = jwt::issue(
+        &state.cfg.jwt_secret,
+        &session.wallet,
+        role,
+        &session.family,
+        state.cfg.jwt_access_ttl_secs,
+    )
+    .map_err(internal)?;
+    Ok(Json(TokenResp {
+        access_token,
+        token_type: "Bearer",
+        expires_at: DateTime::from_timestamp(claims.exp, 0).unwrap_or_default(),
+        refresh_token,
+        refresh_expires_at: Utc::now() + Duration::seconds(state.sessions.ttl_secs()),
+        wallet: session.wallet.clone(),
+        role: role.to_string(),
+    }))
+}
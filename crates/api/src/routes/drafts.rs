@@ -0,0 +1,193 @@
+// 组织者活动草稿：CRUD 与发布
+// 发布分两步：先签名 createEvent；索引器关联 chain_event_id 后再次调用 publish 取得各票种的 addTicketType。
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::Serialize;
+use shared::contracts::calldata::{self, PreparedCall};
+use shared::domain::event::draft::{EventDraft, STATUS_DRAFT, STATUS_PUBLISHING};
+use shared::domain::event::{EventFilter, EventSort, EventWithTypes};
+use shared::error::ApiError;
+use shared::repo::event_repo::EventRepo;
+use std::sync::Arc;
+
+use crate::extract::{Authorized, Organizer};
+use crate::state::AppState;
+
+// 单个组织者列表上限（草稿量小，不分页）
+const MAX_DRAFTS: i64 = 100;
+
+#[derive(Serialize)]
+struct PublishResp {
+    event_id: i64,
+    status: String,
+    chain_event_id: Option<i64>,
+    calls: Vec<PreparedCall>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/organizer/drafts", get(list_drafts).post(create_draft))
+        .route(
+            "/organizer/drafts/:id",
+            get(get_draft).put(update_draft).delete(delete_draft),
+        )
+        .route("/organizer/drafts/:id/publish", post(publish))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "draft request failed");
+    ApiError::Internal
+}
+
+// 只能访问自己的活动；他人的按不存在处理
+async fn load_own(state: &AppState, id: i64, wallet: &str) -> Result<EventWithTypes, ApiError> {
+    EventRepo::new(&state.db)
+        .find_with_types(id)
+        .await
+        .map_err(internal)?
+        .filter(|e| e.event.organizer_wallet == wallet)
+        .ok_or(ApiError::NotFound)
+}
+
+async fn list_drafts(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<EventWithTypes>>, ApiError> {
+    let filter = EventFilter {
+        organizer: Some(auth.user.wallet().to_string()),
+        ..Default::default()
+    };
+    let (items, _) = EventRepo::new(&state.db)
+        .list(&filter, EventSort::CreatedDesc, None, MAX_DRAFTS)
+        .await
+        .map_err(internal)?;
+    Ok(Json(items))
+}
+
+async fn create_draft(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Json(d): Json<EventDraft>,
+) -> Result<(StatusCode, Json<EventWithTypes>), ApiError> {
+    d.validate(Utc::now(), false).map_err(ApiError::BadRequest)?;
+    let wallet = auth.user.wallet();
+    let id = EventRepo::new(&state.db)
+        .create_draft(wallet, &d)
+        .await
+        .map_err(internal)?;
+    tracing::info!(id, %wallet, "event draft created");
+    Ok((StatusCode::CREATED, Json(load_own(&state, id, wallet).await?)))
+}
+
+async fn get_draft(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<EventWithTypes>, ApiError> {
+    Ok(Json(load_own(&state, id, auth.user.wallet()).await?))
+}
+
+async fn update_draft(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Json(d): Json<EventDraft>,
+) -> Result<Json<EventWithTypes>, ApiError> {
+    d.validate(Utc::now(), false).map_err(ApiError::BadRequest)?;
+    let wallet = auth.user.wallet();
+    let current = load_own(&state, id, wallet).await?;
+    if current.event.status != STATUS_DRAFT {
+        return Err(ApiError::Conflict("only drafts can be edited".into()));
+    }
+    if !EventRepo::new(&state.db)
+        .replace_draft(id, wallet, &d)
+        .await
+        .map_err(internal)?
+    {
+        return Err(ApiError::Conflict("draft is being published".into()));
+    }
+    Ok(Json(load_own(&state, id, wallet).await?))
+}
+
+async fn delete_draft(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let wallet = auth.user.wallet();
+    let current = load_own(&state, id, wallet).await?;
+    if current.event.status != STATUS_DRAFT {
+        return Err(ApiError::Conflict("only drafts can be deleted".into()));
+    }
+    if !EventRepo::new(&state.db)
+        .delete_draft(id, wallet)
+        .await
+        .map_err(internal)?
+    {
+        return Err(ApiError::Conflict("draft is being published".into()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 未上链：返回 createEvent（可重复获取，用于交易失败后重签）；
+// 已关联 chain_event_id：返回尚未上链票种的 addTicketType。
+async fn publish(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<PublishResp>, ApiError> {
+    let wallet = auth.user.wallet();
+    let current = load_own(&state, id, wallet).await?;
+    let draft = EventDraft::from_stored(&current).ok_or_else(|| {
+        tracing::error!(id, "stored draft is unreadable");
+        ApiError::Internal
+    })?;
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+
+    let (status, calls) = match current.event.chain_event_id {
+        None => {
+            if current.event.status != STATUS_DRAFT && current.event.status != STATUS_PUBLISHING {
+                return Err(ApiError::Conflict(format!(
+                    "event in status {} cannot be published",
+                    current.event.status
+                )));
+            }
+            draft
+                .validate(Utc::now(), true)
+                .map_err(ApiError::BadRequest)?;
+            EventRepo::new(&state.db)
+                .mark_publishing(id)
+                .await
+                .map_err(internal)?;
+            tracing::info!(id, %wallet, "event publish requested");
+            (
+                STATUS_PUBLISHING.to_string(),
+                vec![calldata::create_event(addrs.event_manager, &draft)],
+            )
+        }
+        Some(chain_id) => {
+            let calls = current
+                .ticket_types
+                .iter()
+                .zip(draft.ticket_types.iter())
+                .filter(|(stored, _)| stored.chain_type_id.is_none())
+                .map(|(_, t)| calldata::add_ticket_type(addrs.event_manager, chain_id as u64, t))
+                .collect();
+            (current.event.status.clone(), calls)
+        }
+    };
+    Ok(Json(PublishResp {
+        event_id: id,
+        status,
+        chain_event_id: current.event.chain_event_id,
+        calls,
+    }))
+}
@@ -0,0 +1,112 @@
+// 公开活动目录：列表（过滤/排序/keyset 分页）与详情，均内嵌票种
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use shared::domain::event::draft::{STATUS_DRAFT, STATUS_PUBLISHING};
+use shared::domain::event::{EventCursor, EventFilter, EventPage, EventSort, EventWithTypes, NewEvent};
+use shared::error::ApiError;
+use shared::repo::event_repo::EventRepo;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+struct ListQuery {
+    status: Option<String>,
+    organizer: Option<String>,
+    starts_after: Option<DateTime<Utc>>,
+    starts_before: Option<DateTime<Utc>>,
+    venue: Option<String>,
+    // start_time | -start_time | -created_at
+    sort: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/events", get(list_events))
+        .route("/events/:id", get(get_event))
+        .route("/events/demo-create", post(demo_create_event))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "events request failed");
+    ApiError::Internal
+}
+
+async fn list_events(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<ListQuery>,
+) -> Result<Json<EventPage>, ApiError> {
+    let sort = match q.sort.as_deref() {
+        None => EventSort::default(),
+        Some(s) => s
+            .parse()
+            .map_err(|_| ApiError::BadRequest("sort must be start_time, -start_time or -created_at".into()))?,
+    };
+    let after = q
+        .cursor
+        .as_deref()
+        .map(|c| EventCursor::decode(c).ok_or_else(|| ApiError::BadRequest("invalid cursor".into())))
+        .transpose()?;
+    let limit = q.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    if let (Some(a), Some(b)) = (q.starts_after, q.starts_before) {
+        if a >= b {
+            return Err(ApiError::BadRequest("starts_after must be before starts_before".into()));
+        }
+    }
+    let filter = EventFilter {
+        status: q.status,
+        organizer: q.organizer,
+        starts_after: q.starts_after,
+        starts_before: q.starts_before,
+        venue: q.venue.filter(|v| !v.is_empty()),
+        exclude_drafts: true,
+    };
+    let (items, next) = EventRepo::new(&state.db)
+        .list(&filter, sort, after, limit)
+        .await
+        .map_err(internal)?;
+    Ok(Json(EventPage {
+        items,
+        next_cursor: next.map(|c| c.encode()),
+    }))
+}
+
+async fn get_event(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<EventWithTypes>, ApiError> {
+    EventRepo::new(&state.db)
+        .find_with_types(id)
+        .await
+        .map_err(internal)?
+        .filter(|e| e.event.status != STATUS_DRAFT && e.event.status != STATUS_PUBLISHING)
+        .map(Json)
+        .ok_or(ApiError::NotFound)
+}
+
+async fn demo_create_event(State(state): State<Arc<AppState>>) -> Result<Json<serde_json::Value>, ApiError> {
+    let repo = EventRepo::new(&state.db);
+    let now = Utc::now();
+    let new = NewEvent {
+        organizer_wallet: "0xDemo",
+        start_time: now + Duration::hours(24),
+        end_time: now + Duration::hours(30),
+        venue: Some("Demo Venue"),
+        status: "draft",
+    };
+    let ev = repo.insert(&new).await.map_err(internal)?;
+    Ok(Json(serde_json::json!({"id": ev.id, "status": ev.status})))
+}
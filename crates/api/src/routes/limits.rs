@@ -0,0 +1,258 @@
+// 购买限额：按钱包给出各票种剩余可购数量；构建购票交易前据此拒绝超额请求，避免用户白付 gas
+// 活动级限额链上实时读取，已购数量取索引数据（TicketPurchased）
+// 单钱包票种限额先记为待确认，组织者提交交易哈希、校验回执与调用数据后才参与限购
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::sol_types::SolCall;
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use shared::contracts::bindings::{EventManager, TicketManager};
+use shared::contracts::calldata::{self, PreparedCall};
+use shared::contracts::revert;
+use shared::domain::event::limits::{self, PurchaseAllowance, TypeAllowance};
+use shared::domain::event::EventWithTypes;
+use shared::error::ApiError;
+use shared::repo::event_repo::EventRepo;
+use shared::repo::purchase_repo::PurchaseRepo;
+use std::sync::Arc;
+
+use crate::extract::{Authorized, Organizer};
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+struct TypeLimitReq {
+    wallet: String,
+    // 0 表示取消限制
+    limit: u64,
+}
+
+#[derive(Deserialize)]
+struct ConfirmLimitReq {
+    tx_hash: String,
+}
+
+#[derive(Serialize)]
+struct ConfirmedLimit {
+    wallet: String,
+    chain_event_id: i64,
+    chain_type_id: i64,
+    limit: Option<i64>,
+    block: i64,
+    tx_hash: String,
+    // 已有更晚区块确认的限额时为 false，本次交易不覆盖
+    applied: bool,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/events/:id/purchase-allowance/:wallet", get(get_allowance))
+        .route("/organizer/events/:id/ticket-types/:type_id/purchase-limits", post(set_type_limit))
+        .route("/organizer/events/:id/ticket-types/:type_id/purchase-limits/confirm", post(confirm_type_limit))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "purchase limit request failed");
+    ApiError::Internal
+}
+
+fn wallet_param(s: &str) -> Result<Address, ApiError> {
+    s.parse().map_err(|_| ApiError::BadRequest("invalid wallet address".into()))
+}
+
+// 仅统计已上链的票种；活动未上链时返回 None
+pub async fn allowance(state: &AppState, ev: &EventWithTypes, wallet: Address) -> Result<Option<PurchaseAllowance>, ApiError> {
+    let Some(chain_event_id) = ev.event.chain_event_id else {
+        return Ok(None);
+    };
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let event_limit = TicketManager::new(addrs.ticket_manager, state.provider.clone())
+        .eventPurchaseLimit(U256::from(chain_event_id))
+        .call()
+        .await
+        .map_err(|e| match revert::from_contract_error(&e) {
+            Some(r) => r.into(),
+            None => internal(e.into()),
+        })?
+        ._0;
+    let event_limit = limits::limit(event_limit.saturating_to());
+
+    let wallet = format!("{wallet:#x}");
+    let repo = PurchaseRepo::new(&state.db);
+    let counts = repo.counts(state.chain_id, chain_event_id, &wallet).await.map_err(internal)?;
+    let type_limits = repo.type_limits(state.chain_id, chain_event_id, &wallet).await.map_err(internal)?;
+    let purchased: i64 = counts.by_wallet.values().sum();
+
+    let ticket_types = ev
+        .ticket_types
+        .iter()
+        .filter_map(|t| {
+            let chain_type_id = t.chain_type_id?;
+            let bought = counts.by_wallet.get(&chain_type_id).copied().unwrap_or(0);
+            let sold = counts.total.get(&chain_type_id).copied().unwrap_or(0);
+            let tl = type_limits.get(&chain_type_id).copied().unwrap_or_default();
+            let type_limit = tl.confirmed.and_then(limits::limit);
+            let supply_remaining = (t.supply_total - sold).max(0);
+            Some(TypeAllowance {
+                type_id: t.id,
+                chain_type_id,
+                type_limit,
+                pending_type_limit: tl.pending,
+                purchased: bought,
+                supply_remaining,
+                remaining: limits::remaining(event_limit, purchased, type_limit, bought, supply_remaining),
+            })
+        })
+        .collect();
+    Ok(Some(PurchaseAllowance {
+        wallet,
+        event_id: ev.event.id,
+        chain_event_id,
+        event_limit,
+        purchased,
+        ticket_types,
+    }))
+}
+
+// 活动或票种尚未索引时放行，交由合约校验
+pub async fn enforce_purchase_limit(state: &AppState, from: Address, chain_event_id: u64, chain_type_id: u64, quantity: u64) -> Result<(), ApiError> {
+    let repo = EventRepo::new(&state.db);
+    let Some(id) = repo.find_id_by_chain_event(chain_event_id as i64).await.map_err(internal)? else {
+        return Ok(());
+    };
+    let Some(ev) = repo.find_with_types(id).await.map_err(internal)? else {
+        return Ok(());
+    };
+    let Some(a) = allowance(state, &ev, from).await? else {
+        return Ok(());
+    };
+    match a.for_type(chain_type_id as i64) {
+        Some(t) if quantity as i64 > t.remaining => Err(ApiError::Contract {
+            code: "PURCHASE_LIMIT_EXCEEDED",
+            message: format!("quantity {quantity} exceeds the remaining purchasable amount of {}", t.remaining),
+        }),
+        _ => Ok(()),
+    }
+}
+
+async fn get_allowance(
+    State(state): State<Arc<AppState>>,
+    Path((id, wallet)): Path<(i64, String)>,
+) -> Result<Json<PurchaseAllowance>, ApiError> {
+    let wallet = wallet_param(&wallet)?;
+    let ev = EventRepo::new(&state.db)
+        .find_with_types(id)
+        .await
+        .map_err(internal)?
+        .ok_or(ApiError::NotFound)?;
+    allowance(&state, &ev, wallet).await?.map(Json).ok_or(ApiError::NotFound)
+}
+
+// 组织者自己的活动中已上链的票种，返回 (链上活动 id, 链上票种 id)
+async fn organizer_type(state: &AppState, organizer: &str, id: i64, type_id: i64) -> Result<(i64, i64), ApiError> {
+    let ev = EventRepo::new(&state.db)
+        .find_with_types(id)
+        .await
+        .map_err(internal)?
+        .filter(|e| e.event.organizer_wallet == organizer)
+        .ok_or(ApiError::NotFound)?;
+    let tt = ev.ticket_types.iter().find(|t| t.id == type_id).ok_or(ApiError::NotFound)?;
+    match (ev.event.chain_event_id, tt.chain_type_id) {
+        (Some(e), Some(t)) => Ok((e, t)),
+        _ => Err(ApiError::Conflict("ticket type is not published on chain yet".into())),
+    }
+}
+
+// 合约不提供读取接口也不发事件，下发的限额先记为待确认，交易上链后经 confirm 接口生效
+async fn set_type_limit(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path((id, type_id)): Path<(i64, i64)>,
+    Json(req): Json<TypeLimitReq>,
+) -> Result<Json<PreparedCall>, ApiError> {
+    let user = wallet_param(&req.wallet)?;
+    let organizer = auth.user.wallet();
+    let (chain_event_id, chain_type_id) = organizer_type(&state, organizer, id, type_id).await?;
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let limit = i64::try_from(req.limit).map_err(|_| ApiError::BadRequest("limit is too large".into()))?;
+    PurchaseRepo::new(&state.db)
+        .request_type_limit(state.chain_id, chain_event_id, chain_type_id, &format!("{user:#x}"), limit, organizer)
+        .await
+        .map_err(internal)?;
+    tracing::info!(event_id = id, type_id, wallet = %user, limit, "ticket type purchase limit requested");
+    Ok(Json(calldata::set_ticket_type_purchase_limit(
+        addrs.event_manager,
+        chain_event_id as u64,
+        chain_type_id as u64,
+        user,
+        req.limit,
+    )))
+}
+
+// 只接受组织者直接发往 EventManager 且执行成功的 setTicketTypePurchaseLimit 交易，限额以调用数据为准
+async fn confirm_type_limit(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path((id, type_id)): Path<(i64, i64)>,
+    Json(req): Json<ConfirmLimitReq>,
+) -> Result<Json<ConfirmedLimit>, ApiError> {
+    let hash: B256 = req.tx_hash.parse().map_err(|_| ApiError::BadRequest("invalid tx_hash".into()))?;
+    let organizer = auth.user.wallet();
+    let (chain_event_id, chain_type_id) = organizer_type(&state, organizer, id, type_id).await?;
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let receipt = state
+        .provider
+        .get_transaction_receipt(hash)
+        .await
+        .map_err(|e| internal(e.into()))?
+        .ok_or_else(|| ApiError::Conflict("transaction is not mined yet".into()))?;
+    if !receipt.status() {
+        return Err(ApiError::Conflict("transaction reverted".into()));
+    }
+    let block = receipt
+        .block_number
+        .ok_or_else(|| ApiError::Conflict("transaction is not mined yet".into()))? as i64;
+    let tx = state
+        .provider
+        .get_transaction_by_hash(hash)
+        .await
+        .map_err(|e| internal(e.into()))?
+        .ok_or(ApiError::NotFound)?;
+    if format!("{:#x}", tx.from) != organizer || tx.to != Some(addrs.event_manager) {
+        return Err(ApiError::BadRequest("transaction was not sent by the organizer to EventManager".into()));
+    }
+    let call = EventManager::setTicketTypePurchaseLimitCall::abi_decode(&tx.input, true)
+        .map_err(|_| ApiError::BadRequest("transaction is not a setTicketTypePurchaseLimit call".into()))?;
+    if call.eventId != U256::from(chain_event_id as u64) || call.typeId != U256::from(chain_type_id as u64) {
+        return Err(ApiError::BadRequest("transaction targets a different ticket type".into()));
+    }
+    let limit = i64::try_from(call.limit).map_err(|_| ApiError::BadRequest("limit is too large".into()))?;
+    let wallet = format!("{:#x}", call.user);
+    let tx_hash = format!("{hash:#x}");
+    let applied = PurchaseRepo::new(&state.db)
+        .confirm_type_limit(state.chain_id, chain_event_id, chain_type_id, &wallet, limit, organizer, block, &tx_hash)
+        .await
+        .map_err(internal)?;
+    tracing::info!(event_id = id, type_id, %wallet, limit, block, applied, "ticket type purchase limit confirmed");
+    Ok(Json(ConfirmedLimit {
+        wallet,
+        chain_event_id,
+        chain_type_id,
+        limit: limits::limit(limit),
+        block,
+        tx_hash,
+        applied,
+    }))
+}
@@ -0,0 +1,193 @@
+// 二级市场：挂单列表/详情（链上 + 链下合并）与订单簿式聚合视图
+use alloy::primitives::{Address, U256};
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use shared::domain::market::{
+    DepthBucket, FloorPrice, ListingCursor, ListingFilter, ListingPage, ListingSort, ListingStatus, MarketListing, Sale,
+};
+use shared::error::ApiError;
+use shared::repo::market_repo::MarketRepo;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+// 深度视图最多返回的价格桶数
+const MAX_DEPTH_BUCKETS: usize = 100;
+
+#[derive(Deserialize)]
+struct ListQuery {
+    event_id: Option<i64>,
+    type_id: Option<i64>,
+    seller: Option<String>,
+    min_price: Option<String>,
+    max_price: Option<String>,
+    // active | sold | cancelled | expired；默认 active
+    status: Option<String>,
+    side: Option<String>,
+    // price | -price | -created_at
+    sort: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct FloorQuery {
+    event_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct DepthQuery {
+    type_id: i64,
+    bucket_wei: String,
+}
+
+#[derive(Deserialize)]
+struct SalesQuery {
+    event_id: Option<i64>,
+    type_id: Option<i64>,
+    limit: Option<i64>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/market/listings", get(list_listings))
+        .route("/market/listings/:id", get(get_listing))
+        .route("/market/floor", get(floor_prices))
+        .route("/market/depth", get(depth))
+        .route("/market/sales/recent", get(recent_sales))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "market request failed");
+    ApiError::Internal
+}
+
+// wei 金额统一按 uint256 十进制校验，避免 NUMERIC 转换报错落到 500
+fn wei(name: &str, v: Option<String>) -> Result<Option<String>, ApiError> {
+    v.map(|s| {
+        s.parse::<U256>()
+            .map(|p| p.to_string())
+            .map_err(|_| ApiError::BadRequest(format!("{name} must be a decimal wei amount")))
+    })
+    .transpose()
+}
+
+fn limit(v: Option<i64>) -> Result<i64, ApiError> {
+    let limit = v.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    Ok(limit)
+}
+
+async fn list_listings(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<ListQuery>,
+) -> Result<Json<ListingPage>, ApiError> {
+    let sort: ListingSort = match q.sort.as_deref() {
+        None => Default::default(),
+        Some(s) => s
+            .parse()
+            .map_err(|_| ApiError::BadRequest("sort must be price, -price or -created_at".into()))?,
+    };
+    let status = match q.status.as_deref() {
+        None => Some(ListingStatus::Active),
+        Some("all") => None,
+        Some(s) => Some(
+            s.parse()
+                .map_err(|_| ApiError::BadRequest("status must be active, sold, cancelled, expired or all".into()))?,
+        ),
+    };
+    let seller = q
+        .seller
+        .map(|s| {
+            s.parse::<Address>()
+                .map(|a| format!("{a:#x}"))
+                .map_err(|_| ApiError::BadRequest("invalid seller address".into()))
+        })
+        .transpose()?;
+    let filter = ListingFilter {
+        event_id: q.event_id,
+        type_id: q.type_id,
+        seller,
+        min_price: wei("min_price", q.min_price)?,
+        max_price: wei("max_price", q.max_price)?,
+        status,
+        side: q.side.filter(|s| !s.is_empty()),
+    };
+    let after = q
+        .cursor
+        .as_deref()
+        .map(|c| ListingCursor::decode(c).ok_or_else(|| ApiError::BadRequest("invalid cursor".into())))
+        .transpose()?;
+    // 按创建时间排序时游标键为微秒时间戳，须在 BIGINT 范围内
+    if let Some(c) = &after {
+        if sort == ListingSort::CreatedDesc && c.key.parse::<i64>().is_err() {
+            return Err(ApiError::BadRequest("invalid cursor".into()));
+        }
+    }
+    let (items, next) = MarketRepo::new(&state.db)
+        .list(state.chain_id, &filter, sort, after, limit(q.limit)?)
+        .await
+        .map_err(internal)?;
+    Ok(Json(ListingPage {
+        items,
+        next_cursor: next.map(|c| c.encode()),
+    }))
+}
+
+async fn get_listing(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<MarketListing>, ApiError> {
+    MarketRepo::new(&state.db)
+        .find(state.chain_id, &id)
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or(ApiError::NotFound)
+}
+
+async fn floor_prices(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<FloorQuery>,
+) -> Result<Json<Vec<FloorPrice>>, ApiError> {
+    MarketRepo::new(&state.db)
+        .floor_prices(state.chain_id, q.event_id)
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+async fn depth(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<DepthQuery>,
+) -> Result<Json<Vec<DepthBucket>>, ApiError> {
+    let bucket = wei("bucket_wei", Some(q.bucket_wei))?.unwrap_or_default();
+    if bucket == "0" {
+        return Err(ApiError::BadRequest("bucket_wei must be positive".into()));
+    }
+    let mut buckets = MarketRepo::new(&state.db)
+        .depth(state.chain_id, q.type_id, &bucket)
+        .await
+        .map_err(internal)?;
+    buckets.truncate(MAX_DEPTH_BUCKETS);
+    Ok(Json(buckets))
+}
+
+async fn recent_sales(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<SalesQuery>,
+) -> Result<Json<Vec<Sale>>, ApiError> {
+    MarketRepo::new(&state.db)
+        .recent_sales(state.chain_id, q.event_id, q.type_id, limit(q.limit)?)
+        .await
+        .map(Json)
+        .map_err(internal)
+}
@@ -0,0 +1,153 @@
+// ERC-721 tokenURI 元数据与票面图像：按数据库实时生成，ETag 含索引器维护的 metadata_version
+use alloy::primitives::keccak256;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use shared::domain::ticket::artwork;
+use shared::domain::ticket::metadata::TokenMetadata;
+use shared::domain::ticket::TicketDetail;
+use shared::error::ApiError;
+use shared::repo::ticket_repo::TicketRepo;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/metadata/tickets/:token_id", get(ticket_metadata))
+        .route("/metadata/tickets/:token_id/image.svg", get(ticket_svg))
+        .merge(png_router())
+}
+
+#[cfg(feature = "png")]
+fn png_router() -> Router<Arc<AppState>> {
+    Router::new().route("/metadata/tickets/:token_id/image.png", get(ticket_png))
+}
+
+#[cfg(not(feature = "png"))]
+fn png_router() -> Router<Arc<AppState>> {
+    Router::new()
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "metadata request failed");
+    ApiError::Internal
+}
+
+async fn load(state: &AppState, token_id: i64) -> Result<(TicketDetail, i64, Option<DateTime<Utc>>), ApiError> {
+    let repo = TicketRepo::new(&state.db);
+    let ticket = repo
+        .find(state.chain_id, token_id)
+        .await
+        .map_err(internal)?
+        .ok_or(ApiError::NotFound)?;
+    let (version, updated_at) = repo
+        .metadata_version(state.chain_id, token_id)
+        .await
+        .map_err(internal)?;
+    Ok((ticket, version, updated_at))
+}
+
+// 统一缓存头：链上刚标记过更新的 token 在一个缓存周期内要求每次回源校验
+fn cached(
+    state: &AppState,
+    headers: &HeaderMap,
+    version: i64,
+    updated_at: Option<DateTime<Utc>>,
+    content_type: &'static str,
+    body: Vec<u8>,
+) -> Result<Response, ApiError> {
+    let etag = format!("\"{}-{}\"", version, &keccak256(&body).to_string()[2..18]);
+    let max_age = state.cfg.metadata_max_age_secs;
+    let recently_updated = updated_at
+        .is_some_and(|t| (Utc::now() - t).num_seconds() < max_age as i64);
+    let cache_control = if recently_updated {
+        "no-cache".to_string()
+    } else {
+        format!("public, max-age={max_age}")
+    };
+    let mut resp = if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|t| t.trim() == etag))
+    {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+    };
+    let h = resp.headers_mut();
+    h.insert(header::ETAG, HeaderValue::from_str(&etag).map_err(|e| internal(e.into()))?);
+    h.insert(header::CACHE_CONTROL, HeaderValue::from_str(&cache_control).map_err(|e| internal(e.into()))?);
+    Ok(resp)
+}
+
+async fn ticket_metadata(
+    State(state): State<Arc<AppState>>,
+    Path(token_id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (ticket, version, updated_at) = load(&state, token_id).await?;
+    // 票面图像由本服务渲染，随状态变化（水印）自动更新
+    let image = format!(
+        "{}/metadata/tickets/{}/image.svg",
+        state.cfg.public_base_url.trim_end_matches('/'),
+        token_id
+    );
+    let meta = TokenMetadata::from_ticket(&ticket, Some(image), &state.cfg.public_base_url);
+    let body = serde_json::to_vec(&meta).map_err(|e| internal(e.into()))?;
+    cached(&state, &headers, version, updated_at, "application/json", body)
+}
+
+async fn ticket_svg(
+    State(state): State<Arc<AppState>>,
+    Path(token_id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (ticket, version, updated_at) = load(&state, token_id).await?;
+    let svg = artwork::render_svg(&ticket);
+    cached(&state, &headers, version, updated_at, "image/svg+xml", svg.into_bytes())
+}
+
+// 本地栅格化（resvg），供不支持 SVG 的钱包/平台使用
+#[cfg(feature = "png")]
+async fn ticket_png(
+    State(state): State<Arc<AppState>>,
+    Path(token_id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (ticket, version, updated_at) = load(&state, token_id).await?;
+    let svg = artwork::render_svg(&ticket);
+    let png = tokio::task::spawn_blocking(move || rasterize(&svg))
+        .await
+        .map_err(|e| internal(e.into()))?
+        .map_err(internal)?;
+    cached(&state, &headers, version, updated_at, "image/png", png)
+}
+
+#[cfg(feature = "png")]
+fn rasterize(svg: &str) -> anyhow::Result<Vec<u8>> {
+    use resvg::{tiny_skia, usvg};
+    use std::sync::OnceLock;
+
+    // 系统字体只加载一次
+    static FONTS: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    let fonts = FONTS.get_or_init(|| {
+        let mut db = usvg::fontdb::Database::new();
+        db.load_system_fonts();
+        Arc::new(db)
+    });
+    let opt = usvg::Options {
+        fontdb: fonts.clone(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &opt)?;
+    let mut pixmap = tiny_skia::Pixmap::new(artwork::WIDTH, artwork::HEIGHT)
+        .ok_or_else(|| anyhow::anyhow!("invalid pixmap size"))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    Ok(pixmap.encode_png()?)
+}
@@ -0,0 +1,14 @@
+pub mod admin;
+pub mod auctions;
+pub mod auth;
+pub mod drafts;
+pub mod events;
+pub mod limits;
+pub mod market;
+pub mod metadata;
+pub mod permit;
+pub mod resale;
+pub mod swap;
+pub mod tickets;
+pub mod tx;
+pub mod whitelist;
@@ -0,0 +1,198 @@
+// 代币购票的 EIP-2612 permit 流程
+//   1. POST /tx/permit/typed-data：读取链上 domain/nonce，返回待签名的 typed data（额度已足够时无需 permit）
+//   2. POST /tx/permit/purchase：校验签名并预执行 permit，返回两笔未签名交易（permit、购票），由用户依次发送
+// 省去的是单独的 approve 授权步骤；没有中继，permit 仍需用户自己提交，共两笔交易
+use alloy::primitives::{hex, Address, Signature, U256};
+use axum::{extract::State, routing::post, Json, Router};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::contracts::bindings::EventManager;
+use shared::contracts::permit::{self, PermitContext};
+use shared::contracts::registry::ContractAddresses;
+use shared::contracts::revert;
+use shared::contracts::txbuilder::{TxAction, TxBuilder, UnsignedTx};
+use shared::error::ApiError;
+use std::sync::Arc;
+
+use crate::extract::AuthUser;
+use crate::routes::limits;
+use crate::routes::tx::{sender, tx_error};
+use crate::state::AppState;
+
+// permit 有效期
+const PERMIT_TTL_SECS: i64 = 20 * 60;
+// permit 未上链前购票无法预执行，按张数给出保守 gas 上限
+const PURCHASE_GAS_BASE: u64 = 200_000;
+const PURCHASE_GAS_PER_TICKET: u64 = 180_000;
+// 单笔购票张数上限，超出时 gas 上限已无意义
+const MAX_QUANTITY: u64 = 100;
+
+#[derive(Deserialize)]
+struct PurchaseTarget {
+    event_id: u64,
+    type_id: u64,
+    quantity: u64,
+}
+
+#[derive(Serialize)]
+struct TypedDataResp {
+    permit_required: bool,
+    token: Address,
+    spender: Address,
+    value: U256,
+    allowance: U256,
+    deadline: u64,
+    typed_data: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct PermitPurchaseReq {
+    event_id: u64,
+    type_id: u64,
+    quantity: u64,
+    #[serde(default)]
+    seat_numbers: Vec<u64>,
+    deadline: u64,
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct PlanResp {
+    // 两笔交易均由用户签名，按顺序发送：permit 使用 nonce n，purchase 使用 nonce n + 1
+    permit: UnsignedTx,
+    purchase: UnsignedTx,
+    deadline: u64,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/tx/permit/typed-data", post(typed_data))
+        .route("/tx/permit/purchase", post(purchase))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "permit request failed");
+    ApiError::Internal
+}
+
+fn addresses(state: &AppState) -> Result<ContractAddresses, ApiError> {
+    state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })
+}
+
+// 应付代币总额 + permit 上下文（spender 为 EventManager）
+async fn context(
+    state: &AppState,
+    addrs: &ContractAddresses,
+    owner: Address,
+    event_id: u64,
+    type_id: u64,
+    quantity: u64,
+) -> Result<(U256, PermitContext), ApiError> {
+    if !(1..=MAX_QUANTITY).contains(&quantity) {
+        return Err(ApiError::BadRequest(format!("quantity must be within [1, {MAX_QUANTITY}]")));
+    }
+    let builder = TxBuilder::new(&state.provider, addrs, state.chain_id as u64);
+    let (price, _) = builder.ticket_prices(event_id, type_id).await.map_err(tx_error)?;
+    if price.is_zero() {
+        return Err(ApiError::BadRequest("ticket type is not sold for tokens".into()));
+    }
+    let em = EventManager::new(addrs.event_manager, state.provider.clone());
+    let token = em
+        .platformToken()
+        .call()
+        .await
+        .map_err(|e| revert::from_contract_error(&e).map(ApiError::from).unwrap_or_else(|| internal(e.into())))?
+        ._0;
+    let ctx = permit::load(&state.provider, token, owner, addrs.event_manager)
+        .await
+        .map_err(internal)?;
+    Ok((price.saturating_mul(U256::from(quantity)), ctx))
+}
+
+async fn typed_data(
+    auth: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PurchaseTarget>,
+) -> Result<Json<TypedDataResp>, ApiError> {
+    let owner = sender(&auth)?;
+    let addrs = addresses(&state)?;
+    let (value, ctx) = context(&state, &addrs, owner, req.event_id, req.type_id, req.quantity).await?;
+    if ctx.balance < value {
+        return Err(ApiError::Contract {
+            code: "INSUFFICIENT_BALANCE",
+            message: format!("token balance {} is below required {}", ctx.balance, value),
+        });
+    }
+    let deadline = (Utc::now().timestamp() + PERMIT_TTL_SECS) as u64;
+    let required = ctx.allowance < value;
+    let typed = required.then(|| ctx.typed_data(&ctx.permit(owner, addrs.event_manager, value, deadline)));
+    Ok(Json(TypedDataResp {
+        permit_required: required,
+        token: ctx.token,
+        spender: addrs.event_manager,
+        value,
+        allowance: ctx.allowance,
+        deadline,
+        typed_data: typed,
+    }))
+}
+
+async fn purchase(
+    auth: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PermitPurchaseReq>,
+) -> Result<Json<PlanResp>, ApiError> {
+    let owner = sender(&auth)?;
+    let addrs = addresses(&state)?;
+    if (req.deadline as i64) <= Utc::now().timestamp() {
+        return Err(ApiError::Contract {
+            code: "PERMIT_EXPIRED",
+            message: "permit deadline has passed".into(),
+        });
+    }
+    let raw = hex::decode(req.signature.trim())
+        .map_err(|_| ApiError::BadRequest("signature must be hex".into()))?;
+    let sig = Signature::try_from(raw.as_slice())
+        .map_err(|_| ApiError::BadRequest("signature must be 65 bytes".into()))?;
+
+    // 金额与 nonce 以链上当前值重算，签名必须与之匹配
+    let (value, ctx) = context(&state, &addrs, owner, req.event_id, req.type_id, req.quantity).await?;
+    let p = ctx.permit(owner, addrs.event_manager, value, req.deadline);
+    if !ctx.verify(&p, &sig) {
+        return Err(ApiError::Contract {
+            code: "PERMIT_INVALID_SIGNATURE",
+            message: "signature does not match the permit for this purchase".into(),
+        });
+    }
+
+    limits::enforce_purchase_limit(&state, owner, req.event_id, req.type_id, req.quantity).await?;
+
+    let builder = TxBuilder::new(&state.provider, &addrs, state.chain_id as u64);
+    let permit = builder.finalize(owner, ctx.call(&p, &sig)).await.map_err(tx_error)?;
+
+    let action = TxAction::PurchaseTickets {
+        event_id: req.event_id,
+        type_id: req.type_id,
+        quantity: req.quantity,
+        seat_numbers: req.seat_numbers,
+        pay_with_eth: false,
+    };
+    let call = builder.prepare(&action).await.map_err(tx_error)?;
+    // quantity 已由 context 限定在 MAX_QUANTITY 以内
+    let gas = PURCHASE_GAS_BASE + PURCHASE_GAS_PER_TICKET * req.quantity;
+    let mut purchase = builder
+        .finalize_with_gas(owner, call, gas)
+        .await
+        .map_err(tx_error)?;
+    // 两笔交易同时构建，待处理 nonce 相同；购票排在 permit 之后
+    purchase.nonce = permit.nonce + 1;
+    tracing::info!(wallet = %auth.wallet(), event_id = req.event_id, quantity = req.quantity, "permit purchase plan built");
+    Ok(Json(PlanResp {
+        permit,
+        purchase,
+        deadline: req.deadline,
+    }))
+}
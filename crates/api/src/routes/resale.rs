@@ -0,0 +1,123 @@
+// 转售政策：组织者按活动配置限价/挂单数上限，公开只读；索引器标记的违规挂单仅组织者可见
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use shared::domain::market::policy::{ListingViolation, ResalePolicy, ResalePolicyInput};
+use shared::error::ApiError;
+use shared::repo::event_repo::EventRepo;
+use shared::repo::market_repo::MarketRepo;
+use std::sync::Arc;
+
+use crate::extract::{Authorized, Organizer};
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Deserialize)]
+struct ViolationQuery {
+    limit: Option<i64>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/events/:id/resale-policy", get(get_policy))
+        .route(
+            "/organizer/events/:id/resale-policy",
+            get(get_own_policy).put(put_policy).delete(delete_policy),
+        )
+        .route("/organizer/events/:id/resale-violations", get(violations))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "resale policy request failed");
+    ApiError::Internal
+}
+
+// 只能管理自己的活动；他人的按不存在处理
+async fn ensure_own(state: &AppState, id: i64, wallet: &str) -> Result<(), ApiError> {
+    EventRepo::new(&state.db)
+        .find_by_id(id)
+        .await
+        .map_err(internal)?
+        .filter(|e| e.organizer_wallet == wallet)
+        .map(|_| ())
+        .ok_or(ApiError::NotFound)
+}
+
+async fn get_policy(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<ResalePolicy>, ApiError> {
+    MarketRepo::new(&state.db)
+        .resale_policy(id)
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or(ApiError::NotFound)
+}
+
+async fn get_own_policy(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<ResalePolicy>, ApiError> {
+    ensure_own(&state, id, auth.user.wallet()).await?;
+    get_policy(State(state), Path(id)).await
+}
+
+async fn put_policy(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Json(input): Json<ResalePolicyInput>,
+) -> Result<Json<ResalePolicy>, ApiError> {
+    input.validate().map_err(ApiError::BadRequest)?;
+    let wallet = auth.user.wallet();
+    ensure_own(&state, id, wallet).await?;
+    let policy = MarketRepo::new(&state.db)
+        .upsert_resale_policy(id, &input, wallet)
+        .await
+        .map_err(internal)?;
+    tracing::info!(event_id = id, %wallet, ?input, "resale policy updated");
+    Ok(Json(policy))
+}
+
+async fn delete_policy(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    ensure_own(&state, id, auth.user.wallet()).await?;
+    let deleted = MarketRepo::new(&state.db)
+        .delete_resale_policy(id)
+        .await
+        .map_err(internal)?;
+    if !deleted {
+        return Err(ApiError::NotFound);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// 已有违规标记不会因政策修改而回溯重算，直到挂单再次更新
+async fn violations(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(q): Query<ViolationQuery>,
+) -> Result<Json<Vec<ListingViolation>>, ApiError> {
+    let limit = q.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    ensure_own(&state, id, auth.user.wallet()).await?;
+    MarketRepo::new(&state.db)
+        .policy_violations(state.chain_id, id, limit)
+        .await
+        .map(Json)
+        .map_err(internal)
+}
@@ -0,0 +1,303 @@
+// Swap 报价：同一区块读取池子储备与费率，本地按恒定乘积计算，再用 get*AmountOut 校正
+// 结果按 (方向, 数量, 滑点) 在 Redis 短暂缓存；缓存不可用时直接计算
+// 价格历史：TWAP 取 PriceUpdated 观测值，K 线取 Swap 成交
+// LP 头寸：余额读链上 balanceOf，存取记录取索引数据，当前价值读 calculateRemoveLiquidity；手续费按每笔成交前的持仓份额累加，年化按窗口内成交估算
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use redis::AsyncCommands;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::contracts::bindings::TokenSwap;
+use shared::contracts::revert;
+use shared::domain::swap::history::{self, Candle, CandleInterval, Twap, MAX_CANDLES};
+use shared::domain::swap::lp::{self, LpPosition, MAX_WINDOW_DAYS};
+use shared::domain::swap::{self, Direction, PoolState, SwapQuote, MAX_SLIPPAGE_BPS};
+use shared::error::ApiError;
+use shared::repo::swap_repo::SwapRepo;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+const DEFAULT_SLIPPAGE_BPS: u32 = 50;
+// 未指定起点时返回的 K 线根数
+const DEFAULT_CANDLES: i64 = 100;
+const DEFAULT_LP_WINDOW_DAYS: i64 = 7;
+
+#[derive(Deserialize)]
+struct QuoteQuery {
+    // eth | token
+    in_token: String,
+    out_token: String,
+    // 输入数量（wei）
+    amount: String,
+    slippage_bps: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct TwapQuery {
+    // 任取其一：[from, to) 或截至 to 的 window_secs；to 缺省为当前时间
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    window_secs: Option<i64>,
+    // chain：截至当前的窗口额外返回合约 getTWAP 结果
+    verify: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TwapResp {
+    #[serde(flatten)]
+    twap: Twap,
+    onchain: Option<OnchainTwap>,
+}
+
+#[derive(Serialize)]
+struct OnchainTwap {
+    avg_token_price: String,
+    avg_eth_price: String,
+}
+
+#[derive(Deserialize)]
+struct CandleQuery {
+    // 1m | 1h | 1d
+    interval: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct LpQuery {
+    window_days: Option<i64>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/swap/quote", get(quote))
+        .route("/swap/twap", get(twap))
+        .route("/swap/candles", get(candles))
+        .route("/wallets/:address/lp-position", get(lp_position))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "swap request failed");
+    ApiError::Internal
+}
+
+fn chain_error(e: alloy::contract::Error) -> ApiError {
+    match revert::from_contract_error(&e) {
+        Some(r) => r.into(),
+        None => internal(e.into()),
+    }
+}
+
+fn cache_key(d: Direction, amount: U256, slippage_bps: u32) -> String {
+    format!("swap:quote:{}:{amount}:{slippage_bps}", d.as_str())
+}
+
+async fn quote(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<QuoteQuery>,
+) -> Result<Json<SwapQuote>, ApiError> {
+    let d = Direction::from_pair(&q.in_token.to_lowercase(), &q.out_token.to_lowercase())
+        .ok_or_else(|| ApiError::BadRequest("in_token/out_token must be eth/token or token/eth".into()))?;
+    let amount: U256 = q
+        .amount
+        .parse()
+        .map_err(|_| ApiError::BadRequest("amount must be a decimal wei amount".into()))?;
+    if amount.is_zero() {
+        return Err(ApiError::BadRequest("amount must be positive".into()));
+    }
+    let slippage_bps = q.slippage_bps.unwrap_or(DEFAULT_SLIPPAGE_BPS);
+    if slippage_bps > MAX_SLIPPAGE_BPS {
+        return Err(ApiError::BadRequest(format!("slippage_bps must be within [0, {MAX_SLIPPAGE_BPS}]")));
+    }
+
+    let key = cache_key(d, amount, slippage_bps);
+    let mut conn = state.redis.clone();
+    match conn.get::<_, Option<String>>(&key).await {
+        Ok(Some(cached)) => {
+            if let Ok(hit) = serde_json::from_str::<SwapQuote>(&cached) {
+                return Ok(Json(hit));
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!(?e, "swap quote cache read failed"),
+    }
+
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let block = state.provider.get_block_number().await.map_err(|e| internal(e.into()))?;
+    let at = BlockId::number(block);
+    let ts = TokenSwap::new(addrs.token_swap, state.provider.clone());
+    let pool = PoolState {
+        reserve_eth: ts.reserveETH().block(at).call().await.map_err(chain_error)?._0,
+        reserve_token: ts.reserveToken().block(at).call().await.map_err(chain_error)?._0,
+        swap_fee_rate: ts.swapFeeRate().block(at).call().await.map_err(chain_error)?._0,
+        protocol_fee_rate: ts.protocolFeeRate().block(at).call().await.map_err(chain_error)?._0,
+    };
+    let mut quote = swap::quote(&pool, d, amount, slippage_bps, block)
+        .ok_or_else(|| ApiError::Conflict("pool has no liquidity".into()))?;
+
+    let onchain = match d {
+        Direction::EthToToken => ts.getTokenAmountOut(amount).block(at).call().await.map_err(chain_error)?._0,
+        Direction::TokenToEth => ts.getETHAmountOut(amount).block(at).call().await.map_err(chain_error)?._0,
+    };
+    let local = quote.amount_out_wei.clone();
+    quote.reconcile(onchain);
+    if !quote.reconciled {
+        tracing::warn!(direction = d.as_str(), %amount, %local, %onchain, block, "local swap quote differs from chain");
+    }
+
+    if let Ok(body) = serde_json::to_string(&quote) {
+        if let Err(e) = conn.set_ex::<_, _, ()>(&key, body, state.cfg.swap_quote_cache_secs).await {
+            tracing::warn!(?e, "swap quote cache write failed");
+        }
+    }
+    Ok(Json(quote))
+}
+
+async fn twap(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<TwapQuery>,
+) -> Result<Json<TwapResp>, ApiError> {
+    let ends_now = q.to.is_none();
+    let to = q.to.unwrap_or_else(Utc::now);
+    let from = match (q.from, q.window_secs) {
+        (Some(f), None) => f,
+        (None, Some(w)) if w > 0 => to - Duration::seconds(w),
+        (None, Some(_)) => return Err(ApiError::BadRequest("window_secs must be positive".into())),
+        _ => return Err(ApiError::BadRequest("exactly one of from or window_secs is required".into())),
+    };
+    if from >= to {
+        return Err(ApiError::BadRequest("from must be before to".into()));
+    }
+    let obs = SwapRepo::new(&state.db)
+        .observations(state.chain_id, from, to)
+        .await
+        .map_err(internal)?;
+    let twap = history::twap(&obs, from, to).ok_or(ApiError::NotFound)?;
+
+    let onchain = match q.verify.as_deref() {
+        Some("chain") if ends_now => {
+            let addrs = state.cache.get().ok_or_else(|| {
+                tracing::error!("contract addresses not loaded");
+                ApiError::Internal
+            })?;
+            let window = U256::from((to - from).num_seconds().max(0) as u64);
+            let r = TokenSwap::new(addrs.token_swap, state.provider.clone())
+                .getTWAP(window)
+                .call()
+                .await
+                .map_err(chain_error)?;
+            Some(OnchainTwap {
+                avg_token_price: r.avgTokenPrice.to_string(),
+                avg_eth_price: r.avgETHPrice.to_string(),
+            })
+        }
+        Some("chain") => return Err(ApiError::BadRequest("verify=chain requires a window ending now".into())),
+        Some(_) => return Err(ApiError::BadRequest("verify must be chain".into())),
+        None => None,
+    };
+    Ok(Json(TwapResp { twap, onchain }))
+}
+
+async fn candles(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<CandleQuery>,
+) -> Result<Json<Vec<Candle>>, ApiError> {
+    let interval: CandleInterval = q
+        .interval
+        .parse()
+        .map_err(|_| ApiError::BadRequest("interval must be 1m, 1h or 1d".into()))?;
+    let step = interval.seconds();
+    let to = q.to.unwrap_or_else(Utc::now);
+    let from = q.from.unwrap_or(to - Duration::seconds(step * DEFAULT_CANDLES));
+    if from >= to {
+        return Err(ApiError::BadRequest("from must be before to".into()));
+    }
+    if (to - from).num_seconds() / step > MAX_CANDLES {
+        return Err(ApiError::BadRequest(format!("range covers more than {MAX_CANDLES} candles")));
+    }
+    SwapRepo::new(&state.db)
+        .candles(state.chain_id, interval, from, to)
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+async fn lp_position(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    Query(q): Query<LpQuery>,
+) -> Result<Json<LpPosition>, ApiError> {
+    let wallet: Address = address
+        .parse()
+        .map_err(|_| ApiError::BadRequest("invalid wallet address".into()))?;
+    let window_days = q.window_days.unwrap_or(DEFAULT_LP_WINDOW_DAYS);
+    if !(1..=MAX_WINDOW_DAYS).contains(&window_days) {
+        return Err(ApiError::BadRequest(format!("window_days must be within [1, {MAX_WINDOW_DAYS}]")));
+    }
+    let wallet_hex = format!("{wallet:#x}");
+    let repo = SwapRepo::new(&state.db);
+    let indexed_balance = repo.lp_balance(state.chain_id, &wallet_hex).await.map_err(internal)?;
+    let flows = repo.lp_flows(state.chain_id, &wallet_hex).await.map_err(internal)?;
+
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let block = state.provider.get_block_number().await.map_err(|e| internal(e.into()))?;
+    let at = BlockId::number(block);
+    let ts = TokenSwap::new(addrs.token_swap, state.provider.clone());
+    let balance = ts.balanceOf(wallet).block(at).call().await.map_err(chain_error)?._0;
+    if balance.is_zero() && flows.adds == 0 && flows.removes == 0 {
+        return Err(ApiError::NotFound);
+    }
+    let total_supply = ts.totalSupply().block(at).call().await.map_err(chain_error)?._0;
+    let reserve_eth = ts.reserveETH().block(at).call().await.map_err(chain_error)?._0;
+    let reserve_token = ts.reserveToken().block(at).call().await.map_err(chain_error)?._0;
+    let swap_fee_rate = ts.swapFeeRate().block(at).call().await.map_err(chain_error)?._0;
+    let protocol_fee_rate = ts.protocolFeeRate().block(at).call().await.map_err(chain_error)?._0;
+    let (value_token, value_eth) = if balance.is_zero() {
+        (U256::ZERO, U256::ZERO)
+    } else {
+        let r = ts.calculateRemoveLiquidity(balance).block(at).call().await.map_err(chain_error)?;
+        (r.tokenAmount, r.ethAmount)
+    };
+
+    let window = Duration::days(window_days);
+    let buckets = repo
+        .fee_buckets(state.chain_id, &wallet_hex, Utc::now() - window, swap_fee_rate, protocol_fee_rate)
+        .await
+        .map_err(internal)?;
+    let fees = lp::pool_fees(&buckets);
+    let fees_eth = fees.eth.saturating_add(lp::token_to_eth(fees.token, reserve_eth, reserve_token));
+    let earned = lp::wallet_fees(&buckets);
+
+    Ok(Json(LpPosition {
+        wallet: wallet_hex,
+        block,
+        lp_balance: balance.to_string(),
+        total_supply: total_supply.to_string(),
+        history_complete: indexed_balance == Some(balance),
+        share_bps: lp::share_bps(balance, total_supply),
+        value_eth_wei: value_eth.to_string(),
+        value_token_wei: value_token.to_string(),
+        deposited_eth_wei: flows.deposited_eth.to_string(),
+        deposited_token_wei: flows.deposited_token.to_string(),
+        withdrawn_eth_wei: flows.withdrawn_eth.to_string(),
+        withdrawn_token_wei: flows.withdrawn_token.to_string(),
+        window_days,
+        fees_earned_eth_wei: earned.eth.to_string(),
+        fees_earned_token_wei: earned.token.to_string(),
+        fee_rate_assumed: buckets.iter().any(|b| b.assumed_rate),
+        apr_bps: lp::apr_bps(fees_eth, reserve_eth, window.num_seconds() as u64),
+    }))
+}
@@ -0,0 +1,145 @@
+// 门票详情与钱包持仓（索引数据），?verify=chain 时实时调用 ownerOf 交叉校验
+use alloy::primitives::{Address, U256};
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use shared::contracts::bindings::TicketManager;
+use shared::contracts::revert;
+use shared::domain::ticket::{TicketDetail, TicketFilter};
+use shared::error::ApiError;
+use shared::repo::ticket_repo::TicketRepo;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+struct VerifyQuery {
+    verify: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PortfolioQuery {
+    event_id: Option<i64>,
+    status: Option<String>,
+    cursor: Option<i64>,
+    limit: Option<i64>,
+    verify: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TicketResp {
+    #[serde(flatten)]
+    ticket: TicketDetail,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain: Option<ChainCheck>,
+}
+
+// owner 为空表示 ownerOf 回退（已销毁或尚未铸造）
+#[derive(Serialize)]
+struct ChainCheck {
+    owner: Option<String>,
+    owner_matches: bool,
+}
+
+#[derive(Serialize)]
+struct PortfolioResp {
+    wallet: String,
+    items: Vec<TicketResp>,
+    next_cursor: Option<i64>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/tickets/:token_id", get(get_ticket))
+        .route("/wallets/:address/tickets", get(wallet_tickets))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "tickets request failed");
+    ApiError::Internal
+}
+
+fn wants_chain(verify: Option<&str>) -> Result<bool, ApiError> {
+    match verify {
+        None => Ok(false),
+        Some("chain") => Ok(true),
+        Some(_) => Err(ApiError::BadRequest("verify must be chain".into())),
+    }
+}
+
+async fn with_chain(state: &AppState, tickets: Vec<TicketDetail>, verify: bool) -> Result<Vec<TicketResp>, ApiError> {
+    if !verify {
+        return Ok(tickets.into_iter().map(|ticket| TicketResp { ticket, chain: None }).collect());
+    }
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let tm = TicketManager::new(addrs.ticket_manager, state.provider.clone());
+    let mut out = Vec::with_capacity(tickets.len());
+    for ticket in tickets {
+        let owner = match tm.ownerOf(U256::from(ticket.token_id as u64)).call().await {
+            Ok(v) => Some(format!("0x{:x}", v._0)),
+            // 回退（ERC721NonexistentToken 等）视为链上无持有人，其余 RPC 错误直接失败
+            Err(e) if revert::from_contract_error(&e).is_some() => None,
+            Err(e) => return Err(internal(e.into())),
+        };
+        let owner_matches = owner.as_deref() == Some(ticket.owner.as_str());
+        if !owner_matches {
+            tracing::warn!(token_id = ticket.token_id, db = %ticket.owner, chain = ?owner, "ticket owner drift");
+        }
+        out.push(TicketResp { ticket, chain: Some(ChainCheck { owner, owner_matches }) });
+    }
+    Ok(out)
+}
+
+async fn get_ticket(
+    State(state): State<Arc<AppState>>,
+    Path(token_id): Path<i64>,
+    Query(q): Query<VerifyQuery>,
+) -> Result<Json<TicketResp>, ApiError> {
+    let verify = wants_chain(q.verify.as_deref())?;
+    let ticket = TicketRepo::new(&state.db)
+        .find(state.chain_id, token_id)
+        .await
+        .map_err(internal)?
+        .ok_or(ApiError::NotFound)?;
+    let mut items = with_chain(&state, vec![ticket], verify).await?;
+    items.pop().map(Json).ok_or(ApiError::NotFound)
+}
+
+async fn wallet_tickets(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    Query(q): Query<PortfolioQuery>,
+) -> Result<Json<PortfolioResp>, ApiError> {
+    let wallet: Address = address
+        .parse()
+        .map_err(|_| ApiError::BadRequest("invalid wallet address".into()))?;
+    let verify = wants_chain(q.verify.as_deref())?;
+    let limit = q.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    let wallet = format!("{wallet:#x}");
+    let filter = TicketFilter {
+        event_id: q.event_id,
+        status: q.status,
+    };
+    let (items, next_cursor) = TicketRepo::new(&state.db)
+        .by_owner(state.chain_id, &wallet, &filter, q.cursor, limit)
+        .await
+        .map_err(internal)?;
+    let items = with_chain(&state, items, verify).await?;
+    Ok(Json(PortfolioResp {
+        wallet,
+        items,
+        next_cursor,
+    }))
+}
@@ -0,0 +1,82 @@
+// 未签名交易构建：发送方为当前登录钱包，前端只需签名并广播
+use alloy::primitives::Address;
+use axum::{extract::State, routing::post, Json, Router};
+use shared::contracts::txbuilder::{TxAction, TxBuildError, TxBuilder, UnsignedTx};
+use shared::error::ApiError;
+use shared::repo::market_repo::MarketRepo;
+use std::sync::Arc;
+
+use crate::extract::AuthUser;
+use crate::routes::limits;
+use crate::state::AppState;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/tx/build", post(build))
+}
+
+pub fn tx_error(e: TxBuildError) -> ApiError {
+    match e {
+        TxBuildError::Invalid(m) => ApiError::BadRequest(m),
+        TxBuildError::Reverted(r) => r.into(),
+        TxBuildError::Rpc(e) => {
+            tracing::error!(?e, "tx build rpc failed");
+            ApiError::Internal
+        }
+    }
+}
+
+// 转售政策：挂单价格超出上限或卖家挂单数已满时拒绝构建
+// 链上未索引的挂单无法定位票所属活动，交由合约自身规则与索引器事后标记
+async fn enforce_resale_policy(state: &AppState, from: Address, action: &TxAction) -> Result<(), ApiError> {
+    let repo = MarketRepo::new(&state.db);
+    let (token_id, price, eth_price, exclude) = match action {
+        TxAction::CreateListing { token_id, price, eth_price, .. } => (token_id.to_string(), *price, *eth_price, None),
+        TxAction::UpdateListing { listing_id, price, eth_price, .. } => {
+            let id = listing_id.to_string();
+            let Some(l) = repo.find(state.chain_id, &id).await.map_err(internal)? else {
+                return Ok(());
+            };
+            (l.token_id.to_string(), *price, *eth_price, Some(id))
+        }
+        _ => return Ok(()),
+    };
+    let checked = repo
+        .check_listing(state.chain_id, &token_id, &format!("{from:#x}"), price, eth_price, exclude.as_deref())
+        .await
+        .map_err(internal)?;
+    match checked.and_then(|(_, v)| v.into_iter().next()) {
+        Some(v) => Err(ApiError::Contract { code: v.rule, message: v.message }),
+        None => Ok(()),
+    }
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "tx build failed");
+    ApiError::Internal
+}
+
+pub fn sender(auth: &AuthUser) -> Result<Address, ApiError> {
+    auth.wallet().parse().map_err(|_| ApiError::Unauthorized)
+}
+
+async fn build(
+    auth: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Json(action): Json<TxAction>,
+) -> Result<Json<UnsignedTx>, ApiError> {
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    let from = sender(&auth)?;
+    enforce_resale_policy(&state, from, &action).await?;
+    if let TxAction::PurchaseTickets { event_id, type_id, quantity, .. } = &action {
+        limits::enforce_purchase_limit(&state, from, *event_id, *type_id, *quantity).await?;
+    }
+    let tx = TxBuilder::new(&state.provider, &addrs, state.chain_id as u64)
+        .build(from, &action)
+        .await
+        .map_err(tx_error)?;
+    tracing::info!(wallet = %auth.wallet(), function = tx.function, "unsigned tx built");
+    Ok(Json(tx))
+}
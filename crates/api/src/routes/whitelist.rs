@@ -0,0 +1,228 @@
+// 预售白名单：组织者上传 CSV，按 isInPresaleWhitelist 与链上求差并生成分批 addToPresaleWhitelist；
+// 公开资格查询只读索引数据，不访问 RPC
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use shared::contracts::bindings::EventManager;
+use shared::contracts::calldata::{self, PreparedCall};
+use shared::domain::event::whitelist::{
+    self, Eligibility, InvalidRow, WhitelistEntry, BATCH_SIZE, MAX_IMPORT_WALLETS, STATUS_CONFIRMED, STATUS_EXPIRED,
+    STATUS_PENDING,
+};
+use shared::domain::event::TicketType;
+use shared::error::ApiError;
+use shared::repo::event_repo::EventRepo;
+use shared::repo::whitelist_repo::WhitelistRepo;
+use std::sync::Arc;
+
+use crate::extract::{Authorized, Organizer};
+use crate::state::AppState;
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+// 导入时并发读取 isInPresaleWhitelist 的请求数
+const CHAIN_CHECK_CONCURRENCY: usize = 16;
+
+#[derive(Deserialize)]
+struct ImportQuery {
+    // 只求差，不记录 pending
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    status: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ImportResp {
+    type_id: i64,
+    chain_event_id: i64,
+    chain_type_id: i64,
+    valid: usize,
+    duplicates: usize,
+    invalid: Vec<InvalidRow>,
+    already_whitelisted: usize,
+    // already_whitelisted 中已在链上、但索引尚未确认的数量（如直接调用合约添加）
+    on_chain_unconfirmed: usize,
+    // 此前已导入但尚未确认上链的数量
+    previously_pending: usize,
+    to_add: usize,
+    calls: Vec<PreparedCall>,
+}
+
+#[derive(Serialize)]
+struct EntryPage {
+    items: Vec<WhitelistEntry>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EligibilityResp {
+    wallet: String,
+    ticket_types: Vec<Eligibility>,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/events/:id/whitelist/:wallet", get(eligibility))
+        .route("/organizer/events/:id/ticket-types/:type_id/whitelist", get(list_entries))
+        .route("/organizer/events/:id/ticket-types/:type_id/whitelist/import", post(import))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "whitelist request failed");
+    ApiError::Internal
+}
+
+// 只能管理自己活动下已上链的票种；他人的按不存在处理
+async fn load_own_type(state: &AppState, id: i64, type_id: i64, wallet: &str) -> Result<(i64, TicketType), ApiError> {
+    let ev = EventRepo::new(&state.db)
+        .find_with_types(id)
+        .await
+        .map_err(internal)?
+        .filter(|e| e.event.organizer_wallet == wallet)
+        .ok_or(ApiError::NotFound)?;
+    let tt = ev
+        .ticket_types
+        .into_iter()
+        .find(|t| t.id == type_id)
+        .ok_or(ApiError::NotFound)?;
+    match ev.event.chain_event_id {
+        Some(chain_event_id) if tt.chain_type_id.is_some() => Ok((chain_event_id, tt)),
+        _ => Err(ApiError::Conflict("ticket type is not published on chain yet".into())),
+    }
+}
+
+async fn import(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path((id, type_id)): Path<(i64, i64)>,
+    Query(q): Query<ImportQuery>,
+    body: String,
+) -> Result<Json<ImportResp>, ApiError> {
+    let wallet = auth.user.wallet();
+    let (chain_event_id, tt) = load_own_type(&state, id, type_id, wallet).await?;
+    let chain_type_id = tt.chain_type_id.unwrap_or_default();
+
+    let parsed = whitelist::parse_csv(&body);
+    if parsed.wallets.len() > MAX_IMPORT_WALLETS {
+        return Err(ApiError::BadRequest(format!("at most {MAX_IMPORT_WALLETS} wallets per import")));
+    }
+    let all: Vec<String> = parsed.wallets.iter().map(|a| format!("{a:#x}")).collect();
+    let repo = WhitelistRepo::new(&state.db);
+    let existing = repo.statuses(state.chain_id, type_id, &all).await.map_err(internal)?;
+    let unconfirmed: Vec<Address> = parsed
+        .wallets
+        .iter()
+        .copied()
+        .filter(|a| existing.get(&format!("{a:#x}")).map(String::as_str) != Some(STATUS_CONFIRMED))
+        .collect();
+    let previously_pending = existing.values().filter(|s| s.as_str() == STATUS_PENDING).count();
+
+    let addrs = state.cache.get().ok_or_else(|| {
+        tracing::error!("contract addresses not loaded");
+        ApiError::Internal
+    })?;
+    // 索引只认识经 API 导入的地址；直接在链上添加的按链头状态剔除，避免重复生成交易
+    let block = state.provider.get_block_number().await.map_err(|e| internal(e.into()))?;
+    let at = BlockId::number(block);
+    let em = EventManager::new(addrs.event_manager, state.provider.clone());
+    let listed: Vec<bool> = stream::iter(unconfirmed.iter().copied())
+        .map(|a| {
+            let call = em.isInPresaleWhitelist(U256::from(chain_event_id), U256::from(chain_type_id), a);
+            async move { call.block(at).call().await.map(|r| r._0) }
+        })
+        .buffered(CHAIN_CHECK_CONCURRENCY)
+        .try_collect()
+        .await
+        .map_err(|e| internal(e.into()))?;
+    let to_add: Vec<Address> = unconfirmed
+        .iter()
+        .zip(&listed)
+        .filter(|(_, on_chain)| !**on_chain)
+        .map(|(a, _)| *a)
+        .collect();
+
+    // 已在链上的也记为 pending，由索引器在确认高度置为 confirmed
+    if !q.dry_run && !unconfirmed.is_empty() {
+        let pending: Vec<String> = unconfirmed.iter().map(|a| format!("{a:#x}")).collect();
+        repo.mark_pending(state.chain_id, type_id, &pending, wallet)
+            .await
+            .map_err(internal)?;
+    }
+    let calls = to_add
+        .chunks(BATCH_SIZE)
+        .map(|c| calldata::add_to_presale_whitelist(addrs.event_manager, chain_event_id as u64, chain_type_id as u64, c))
+        .collect();
+    tracing::info!(event_id = id, type_id, %wallet, to_add = to_add.len(), dry_run = q.dry_run, "whitelist import");
+    Ok(Json(ImportResp {
+        type_id,
+        chain_event_id,
+        chain_type_id,
+        valid: parsed.wallets.len(),
+        duplicates: parsed.duplicates,
+        invalid: parsed.invalid,
+        already_whitelisted: parsed.wallets.len() - to_add.len(),
+        on_chain_unconfirmed: unconfirmed.len() - to_add.len(),
+        previously_pending,
+        to_add: to_add.len(),
+        calls,
+    }))
+}
+
+async fn list_entries(
+    auth: Authorized<Organizer>,
+    State(state): State<Arc<AppState>>,
+    Path((id, type_id)): Path<(i64, i64)>,
+    Query(q): Query<ListQuery>,
+) -> Result<Json<EntryPage>, ApiError> {
+    let limit = q.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::BadRequest(format!("limit must be within [1, {MAX_LIMIT}]")));
+    }
+    if let Some(s) = q.status.as_deref() {
+        if ![STATUS_PENDING, STATUS_CONFIRMED, STATUS_EXPIRED].contains(&s) {
+            return Err(ApiError::BadRequest("status must be pending, confirmed or expired".into()));
+        }
+    }
+    load_own_type(&state, id, type_id, auth.user.wallet()).await?;
+    let items = WhitelistRepo::new(&state.db)
+        .list(state.chain_id, type_id, q.status.as_deref(), q.cursor.as_deref(), limit)
+        .await
+        .map_err(internal)?;
+    // 游标为本页最后一个钱包地址
+    let next_cursor = items
+        .last()
+        .filter(|_| items.len() as i64 == limit)
+        .map(|e| e.wallet.clone());
+    Ok(Json(EntryPage { items, next_cursor }))
+}
+
+async fn eligibility(
+    State(state): State<Arc<AppState>>,
+    Path((id, wallet)): Path<(i64, String)>,
+) -> Result<Json<EligibilityResp>, ApiError> {
+    let wallet: Address = wallet
+        .parse()
+        .map_err(|_| ApiError::BadRequest("invalid wallet address".into()))?;
+    let wallet = format!("{wallet:#x}");
+    let ticket_types = WhitelistRepo::new(&state.db)
+        .eligibility(state.chain_id, id, &wallet)
+        .await
+        .map_err(internal)?;
+    if ticket_types.is_empty() {
+        return Err(ApiError::NotFound);
+    }
+    Ok(Json(EligibilityResp { wallet, ticket_types }))
+}
@@ -0,0 +1,18 @@
+use crate::extract::RoleCache;
+use redis::aio::ConnectionManager;
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::AddressCache;
+use shared::security::session::SessionStore;
+use shared::{db::pool::Db, AppConfig};
+
+pub struct AppState {
+    pub db: Db,
+    pub cache: AddressCache,
+    pub cfg: AppConfig,
+    pub chain_id: i64,
+    pub provider: SharedProvider,
+    pub sessions: SessionStore,
+    pub roles: RoleCache,
+    // 短期热数据缓存（报价等）
+    pub redis: ConnectionManager,
+}
@@ -0,0 +1,206 @@
+// 索引器管理接口（仅内网）：查看游标/链头/滞后/leader/最近错误，暂停恢复、回退游标、区间重索引
+// 所有路由要求 `Authorization: Bearer <OT_INDEXER_ADMIN_TOKEN>`。
+
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use shared::contracts::provider::SharedProvider;
+use shared::db::pool::Db;
+use shared::error::ApiError;
+use std::sync::Arc;
+
+use crate::control::{ErrorEntry, IndexerControl};
+use crate::cursor::{load_cursor, save_cursor};
+use crate::pipeline::CONFIRM_DEPTH;
+
+// 单次重索引最大区块跨度
+pub const MAX_REINDEX_SPAN: i64 = 10_000;
+
+pub struct AdminState {
+    pub db: Db,
+    pub provider: SharedProvider,
+    pub chain_id: i64,
+    pub ctl: IndexerControl,
+    pub token: String,
+}
+
+#[derive(Serialize)]
+struct CursorRow {
+    chain_id: i64,
+    last_block: i64,
+}
+
+#[derive(Serialize)]
+struct StatusBody {
+    chain_id: i64,
+    cursor: i64,
+    head: Option<i64>,
+    finalized: Option<i64>,
+    lag: Option<i64>,
+    paused: bool,
+    leader: bool,
+    pending_reindex: Vec<(i64, i64)>,
+    recent_errors: Vec<ErrorEntry>,
+    cursors: Vec<CursorRow>,
+}
+
+#[derive(Deserialize)]
+struct RewindReq {
+    block: i64,
+}
+
+#[derive(Deserialize)]
+struct ReindexReq {
+    from: i64,
+    to: i64,
+}
+
+pub fn router(state: Arc<AdminState>) -> Router {
+    Router::new()
+        .route("/admin/status", get(status))
+        .route("/admin/pause", post(pause))
+        .route("/admin/resume", post(resume))
+        .route("/admin/rewind", post(rewind))
+        .route("/admin/reindex", post(reindex))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+pub async fn serve(state: Arc<AdminState>, addr: String) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    tracing::info!(addr, "indexer admin listening");
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+async fn require_token(
+    State(state): State<Arc<AdminState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let presented = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match presented {
+        Some(t) if constant_time_eq(t.as_bytes(), state.token.as_bytes()) => next.run(req).await,
+        _ => ApiError::Unauthorized.into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    tracing::error!(?e, "admin request failed");
+    ApiError::Internal
+}
+
+async fn head(state: &AdminState) -> Option<i64> {
+    match state.provider.get_block_number().await {
+        Ok(h) => Some(h as i64),
+        Err(e) => {
+            tracing::warn!(?e, "admin: head unavailable");
+            None
+        }
+    }
+}
+
+async fn build_status(state: &AdminState) -> Result<StatusBody, ApiError> {
+    let cursor = load_cursor(&state.db, state.chain_id).await.map_err(internal)?;
+    let head = head(state).await;
+    let cursors = sqlx::query_as!(
+        CursorRow,
+        "SELECT chain_id, last_block FROM indexer_cursors ORDER BY chain_id"
+    )
+    .fetch_all(&state.db.0)
+    .await
+    .map_err(|e| internal(e.into()))?;
+    Ok(StatusBody {
+        chain_id: state.chain_id,
+        cursor,
+        head,
+        finalized: head.map(|h| (h - CONFIRM_DEPTH).max(0)),
+        lag: head.map(|h| h - cursor),
+        paused: state.ctl.is_paused(),
+        leader: state.ctl.is_leader(),
+        pending_reindex: state.ctl.pending_reindex(),
+        recent_errors: state.ctl.recent_errors(),
+        cursors,
+    })
+}
+
+async fn status(State(state): State<Arc<AdminState>>) -> Result<Json<StatusBody>, ApiError> {
+    Ok(Json(build_status(&state).await?))
+}
+
+async fn pause(State(state): State<Arc<AdminState>>) -> Result<Json<StatusBody>, ApiError> {
+    state.ctl.set_paused(true);
+    tracing::warn!("indexing paused via admin");
+    Ok(Json(build_status(&state).await?))
+}
+
+async fn resume(State(state): State<Arc<AdminState>>) -> Result<Json<StatusBody>, ApiError> {
+    state.ctl.set_paused(false);
+    tracing::info!("indexing resumed via admin");
+    Ok(Json(build_status(&state).await?))
+}
+
+// 回退游标：只允许向后回退，下一轮增量会从该高度重新处理（写入幂等）
+// 须先暂停索引；持有 step 锁后再写游标，进行中的批次不会覆盖回退结果
+async fn rewind(
+    State(state): State<Arc<AdminState>>,
+    Json(req): Json<RewindReq>,
+) -> Result<Json<StatusBody>, ApiError> {
+    if !state.ctl.is_paused() {
+        return Err(ApiError::Conflict("pause indexing before rewinding the cursor".into()));
+    }
+    let step = state.ctl.lock_step().await;
+    let cursor = load_cursor(&state.db, state.chain_id).await.map_err(internal)?;
+    if req.block < 0 || req.block > cursor {
+        return Err(ApiError::BadRequest(format!(
+            "block must be within [0, {cursor}]"
+        )));
+    }
+    save_cursor(&state.db, state.chain_id, req.block)
+        .await
+        .map_err(internal)?;
+    drop(step);
+    tracing::warn!(from = cursor, to = req.block, "cursor rewound via admin");
+    Ok(Json(build_status(&state).await?))
+}
+
+// 区间重索引：入队后由增量循环执行，不改变游标
+async fn reindex(
+    State(state): State<Arc<AdminState>>,
+    Json(req): Json<ReindexReq>,
+) -> Result<impl IntoResponse, ApiError> {
+    if req.from < 1 || req.from > req.to {
+        return Err(ApiError::BadRequest("require 1 <= from <= to".into()));
+    }
+    if req.to - req.from + 1 > MAX_REINDEX_SPAN {
+        return Err(ApiError::BadRequest(format!(
+            "range too large (max {MAX_REINDEX_SPAN} blocks)"
+        )));
+    }
+    let finalized = head(&state)
+        .await
+        .map(|h| h - CONFIRM_DEPTH)
+        .ok_or(ApiError::Internal)?;
+    if req.to > finalized {
+        return Err(ApiError::BadRequest(format!(
+            "to must not exceed finalized block {finalized}"
+        )));
+    }
+    state.ctl.push_reindex(req.from, req.to);
+    tracing::info!(from = req.from, to = req.to, "reindex queued via admin");
+    Ok((StatusCode::ACCEPTED, Json(build_status(&state).await?)))
+}
@@ -0,0 +1,95 @@
+// 索引器运行时控制状态：暂停/恢复、leader 标记、待执行的重索引区间、最近错误
+// 由增量循环与 admin HTTP 接口共享。
+// 回填每批、增量循环每轮持有 step 锁；直接改写游标的管理操作须先取得该锁，避免与进行中的批次互相覆盖。
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard};
+
+const MAX_RECENT_ERRORS: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorEntry {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Inner {
+    paused: AtomicBool,
+    leader: AtomicBool,
+    reindex: Mutex<VecDeque<(i64, i64)>>,
+    errors: Mutex<VecDeque<ErrorEntry>>,
+    step: AsyncMutex<()>,
+}
+
+#[derive(Clone, Default)]
+pub struct IndexerControl(Arc<Inner>);
+
+impl IndexerControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.paused.load(Ordering::SeqCst)
+    }
+    pub fn set_paused(&self, v: bool) {
+        self.0.paused.store(v, Ordering::SeqCst);
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.0.leader.load(Ordering::SeqCst)
+    }
+    pub fn set_leader(&self, v: bool) {
+        self.0.leader.store(v, Ordering::SeqCst);
+    }
+
+    // 回填、增量与各后台同步任务只在 leader 且未暂停时执行
+    pub fn is_active(&self) -> bool {
+        self.is_leader() && !self.is_paused()
+    }
+
+    // 等待进行中的增量/重索引批次结束
+    pub async fn lock_step(&self) -> MutexGuard<'_, ()> {
+        self.0.step.lock().await
+    }
+
+    pub fn push_reindex(&self, from: i64, to: i64) {
+        if let Ok(mut q) = self.0.reindex.lock() {
+            q.push_back((from, to));
+        }
+    }
+    pub fn take_reindex(&self) -> Option<(i64, i64)> {
+        self.0.reindex.lock().ok()?.pop_front()
+    }
+    pub fn pending_reindex(&self) -> Vec<(i64, i64)> {
+        self.0
+            .reindex
+            .lock()
+            .map(|q| q.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn record_error(&self, message: String) {
+        if let Ok(mut q) = self.0.errors.lock() {
+            if q.len() == MAX_RECENT_ERRORS {
+                q.pop_front();
+            }
+            q.push_back(ErrorEntry {
+                at: Utc::now(),
+                message,
+            });
+        }
+    }
+    pub fn recent_errors(&self) -> Vec<ErrorEntry> {
+        self.0
+            .errors
+            .lock()
+            .map(|q| q.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,37 @@
+use shared::db::pool::Db;
+
+// 简易游标表（若尚未建表，可后续迁移添加，这里先用临时表名占位）
+// CREATE TABLE indexer_cursors(chain_id BIGINT PRIMARY KEY, last_block BIGINT NOT NULL);
+
+pub async fn ensure_cursor(db: &Db, chain_id: i64) -> anyhow::Result<()> {
+    sqlx::query!("CREATE TABLE IF NOT EXISTS indexer_cursors(chain_id BIGINT PRIMARY KEY, last_block BIGINT NOT NULL)").execute(&db.0).await?;
+    sqlx::query!(
+        "INSERT INTO indexer_cursors(chain_id,last_block) VALUES($1,$2) ON CONFLICT DO NOTHING",
+        chain_id,
+        0_i64
+    )
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+pub async fn load_cursor(db: &Db, chain_id: i64) -> anyhow::Result<i64> {
+    let v = sqlx::query_scalar!(
+        "SELECT last_block FROM indexer_cursors WHERE chain_id=$1",
+        chain_id
+    )
+    .fetch_one(&db.0)
+    .await?;
+    Ok(v)
+}
+
+pub async fn save_cursor(db: &Db, chain_id: i64, block: i64) -> anyhow::Result<()> {
+    sqlx::query!(
+        "UPDATE indexer_cursors SET last_block=$2 WHERE chain_id=$1",
+        chain_id,
+        block
+    )
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,242 @@
+// 分析数据导出：chain_logs / marketplace_trades / token_swaps / ticket_transfers
+// 按 链 + 天 分区写出 CSV 与 Parquet，每个数据集在 export_cursors 中记录已导出高度，只导出增量。
+// 目录布局：<out>/<dataset>/chain_id=<id>/date=<YYYY-MM-DD>/part-<from>-<to>.{csv,parquet}
+
+use anyhow::{bail, Context};
+use arrow::array::{ArrayRef, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use shared::db::pool::Db;
+use sqlx::Row;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::cursor::load_cursor;
+
+const EXPORT_BATCH: i64 = 50_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Parquet,
+    Both,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "parquet" => Ok(Self::Parquet),
+            "both" => Ok(Self::Both),
+            other => bail!("unknown export format: {other}"),
+        }
+    }
+}
+
+struct Dataset {
+    name: &'static str,
+    // 除 block_number 外其余列统一导出为文本（uint256 超出 i64 范围）
+    columns: &'static [&'static str],
+    // $1 chain_id, $2 起始(不含), $3 结束(含)；前两列固定为 day, block_number
+    sql: &'static str,
+}
+
+const DATASETS: &[Dataset] = &[
+    Dataset {
+        name: "chain_logs",
+        columns: &["tx_hash", "log_index", "primary_topic", "contract_address", "data"],
+        sql: "SELECT COALESCE(to_char(b.block_time AT TIME ZONE 'UTC','YYYY-MM-DD'),'unknown') AS day, t.block_number,
+                     t.tx_hash, t.log_index::TEXT, t.primary_topic, t.contract_address, t.data::TEXT
+              FROM chain_logs t LEFT JOIN chain_blocks b ON b.chain_id=t.chain_id AND b.block_number=t.block_number
+              WHERE t.chain_id=$1 AND t.block_number > $2 AND t.block_number <= $3
+              ORDER BY t.block_number, t.log_index",
+    },
+    Dataset {
+        name: "marketplace_trades",
+        columns: &["tx_hash", "listing_id", "buyer", "price"],
+        sql: "SELECT COALESCE(to_char(b.block_time AT TIME ZONE 'UTC','YYYY-MM-DD'),'unknown') AS day, t.block_number,
+                     t.tx_hash, t.listing_id::TEXT, t.buyer, t.price::TEXT
+              FROM marketplace_trades t LEFT JOIN chain_blocks b ON b.chain_id=t.chain_id AND b.block_number=t.block_number
+              WHERE t.chain_id=$1 AND t.block_number > $2 AND t.block_number <= $3
+              ORDER BY t.block_number, t.tx_hash",
+    },
+    Dataset {
+        name: "token_swaps",
+        columns: &["tx_hash", "user_addr", "token_in", "token_out", "amount_in", "amount_out"],
+        sql: "SELECT COALESCE(to_char(b.block_time AT TIME ZONE 'UTC','YYYY-MM-DD'),'unknown') AS day, t.block_number,
+                     t.tx_hash, t.user_addr, t.token_in, t.token_out, t.amount_in::TEXT, t.amount_out::TEXT
+              FROM token_swaps t LEFT JOIN chain_blocks b ON b.chain_id=t.chain_id AND b.block_number=t.block_number
+              WHERE t.chain_id=$1 AND t.block_number > $2 AND t.block_number <= $3
+              ORDER BY t.block_number, t.tx_hash",
+    },
+    Dataset {
+        name: "ticket_transfers",
+        columns: &["tx_hash", "log_index", "token_id", "from_addr", "to_addr"],
+        sql: "SELECT COALESCE(to_char(b.block_time AT TIME ZONE 'UTC','YYYY-MM-DD'),'unknown') AS day, t.block_number,
+                     t.tx_hash, t.log_index::TEXT, t.token_id::TEXT, t.from_addr, t.to_addr
+              FROM ticket_transfers t LEFT JOIN chain_blocks b ON b.chain_id=t.chain_id AND b.block_number=t.block_number
+              WHERE t.chain_id=$1 AND t.block_number > $2 AND t.block_number <= $3
+              ORDER BY t.block_number, t.log_index",
+    },
+];
+
+#[derive(Debug, Default)]
+struct Partition {
+    block_numbers: Vec<i64>,
+    columns: Vec<Vec<Option<String>>>,
+}
+
+#[derive(Debug)]
+pub struct ExportSummary {
+    pub dataset: &'static str,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub rows: usize,
+    pub files: Vec<PathBuf>,
+}
+
+// 导出到索引器游标（已确认高度）为止的增量数据
+pub async fn export(
+    db: &Db,
+    chain_id: i64,
+    out: &Path,
+    format: Format,
+) -> anyhow::Result<Vec<ExportSummary>> {
+    let upper = load_cursor(db, chain_id).await?;
+    let mut summaries = Vec::new();
+    for ds in DATASETS {
+        sqlx::query!(
+            "INSERT INTO export_cursors(chain_id,dataset) VALUES($1,$2) ON CONFLICT DO NOTHING",
+            chain_id,
+            ds.name
+        )
+        .execute(&db.0)
+        .await?;
+        let last = sqlx::query_scalar!(
+            "SELECT last_block FROM export_cursors WHERE chain_id=$1 AND dataset=$2",
+            chain_id,
+            ds.name
+        )
+        .fetch_one(&db.0)
+        .await?;
+        // 分段导出，控制单次查询内存；每段写完即推进游标
+        let mut from = last;
+        while from < upper {
+            let to = (from + EXPORT_BATCH).min(upper);
+            let s = export_dataset(db, ds, chain_id, from, to, out, format).await?;
+            sqlx::query!(
+                "UPDATE export_cursors SET last_block=$3, updated_at=NOW() WHERE chain_id=$1 AND dataset=$2",
+                chain_id,
+                ds.name,
+                to
+            )
+            .execute(&db.0)
+            .await?;
+            tracing::info!(dataset = ds.name, rows = s.rows, from, to, "exported");
+            summaries.push(s);
+            from = to;
+        }
+    }
+    Ok(summaries)
+}
+
+async fn export_dataset(
+    db: &Db,
+    ds: &Dataset,
+    chain_id: i64,
+    from: i64,
+    to: i64,
+    out: &Path,
+    format: Format,
+) -> anyhow::Result<ExportSummary> {
+    let rows = sqlx::query(ds.sql)
+        .bind(chain_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&db.0)
+        .await
+        .with_context(|| format!("query {}", ds.name))?;
+
+    let mut parts: BTreeMap<String, Partition> = BTreeMap::new();
+    for r in rows.iter() {
+        let day: String = r.try_get(0)?;
+        let p = parts.entry(day).or_insert_with(|| Partition {
+            block_numbers: vec![],
+            columns: vec![vec![]; ds.columns.len()],
+        });
+        p.block_numbers.push(r.try_get(1)?);
+        for (i, col) in p.columns.iter_mut().enumerate() {
+            col.push(r.try_get(i + 2)?);
+        }
+    }
+
+    let mut files = Vec::new();
+    for (day, p) in parts.iter() {
+        let dir = out
+            .join(ds.name)
+            .join(format!("chain_id={chain_id}"))
+            .join(format!("date={day}"));
+        fs::create_dir_all(&dir)?;
+        let stem = format!("part-{}-{}", from + 1, to);
+        if matches!(format, Format::Csv | Format::Both) {
+            let path = dir.join(format!("{stem}.csv"));
+            write_csv(&path, ds, p)?;
+            files.push(path);
+        }
+        if matches!(format, Format::Parquet | Format::Both) {
+            let path = dir.join(format!("{stem}.parquet"));
+            write_parquet(&path, ds, p)?;
+            files.push(path);
+        }
+    }
+    Ok(ExportSummary {
+        dataset: ds.name,
+        from_block: from,
+        to_block: to,
+        rows: rows.len(),
+        files,
+    })
+}
+
+fn write_csv(path: &Path, ds: &Dataset, p: &Partition) -> anyhow::Result<()> {
+    let mut w = csv::Writer::from_path(path)?;
+    let mut header = vec!["block_number"];
+    header.extend_from_slice(ds.columns);
+    w.write_record(&header)?;
+    for (i, bn) in p.block_numbers.iter().enumerate() {
+        let mut rec = vec![bn.to_string()];
+        rec.extend(p.columns.iter().map(|c| c[i].clone().unwrap_or_default()));
+        w.write_record(&rec)?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+fn write_parquet(path: &Path, ds: &Dataset, p: &Partition) -> anyhow::Result<()> {
+    let mut fields = vec![Field::new("block_number", DataType::Int64, false)];
+    fields.extend(ds.columns.iter().map(|c| Field::new(*c, DataType::Utf8, true)));
+    let schema = Arc::new(Schema::new(fields));
+
+    let mut arrays: Vec<ArrayRef> = vec![Arc::new(Int64Array::from(p.block_numbers.clone()))];
+    arrays.extend(
+        p.columns
+            .iter()
+            .map(|c| Arc::new(StringArray::from(c.clone())) as ArrayRef),
+    );
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut w = ArrowWriter::try_new(File::create(path)?, schema, Some(props))?;
+    w.write(&batch)?;
+    w.close()?;
+    Ok(())
+}
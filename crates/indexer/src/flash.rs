@@ -0,0 +1,178 @@
+// 闪电贷监控：每批日志投影完成后执行（依赖已落库的价格观测与二级市场成交）
+// 疑似交易先尝试 debug_traceTransaction（callTracer）确认借款方并记录同交易内的其他合约调用；
+// 节点不支持调用轨迹时退回到交易调用数据，直接调用 flashLoan 以外的情况只能按事件推断，记为 suspected
+// 同区块二级市场成交只在与该交易相关（同一交易、买卖方为借款方或出现在调用轨迹中）时告警
+
+use alloy::primitives::{Address, B256};
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::Log;
+use alloy::sol_types::{SolCall, SolEvent};
+use serde_json::json;
+use shared::contracts::bindings::TokenSwap;
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use shared::domain::swap::flash::{self, AlertKind, CallFrame, Source, TxActivity, PRICE_MOVE_ALERT_BPS};
+use shared::repo::flash_repo::{FlashRepo, NewFlashLoan};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Default)]
+pub struct FlashReport {
+    pub candidates: usize,
+    pub detected: usize,
+    pub alerts: usize,
+}
+
+pub async fn inspect(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    logs: &[Log],
+) -> anyhow::Result<FlashReport> {
+    // 按交易聚合 TokenSwap 日志
+    let mut txs: BTreeMap<B256, (TxActivity, Vec<&Log>)> = BTreeMap::new();
+    for lg in logs.iter().filter(|l| l.address() == addrs.token_swap) {
+        let Some(hash) = lg.transaction_hash else {
+            continue;
+        };
+        let (activity, tx_logs) = txs.entry(hash).or_default();
+        match lg.topics().first() {
+            Some(t) if *t == TokenSwap::ReservesUpdated::SIGNATURE_HASH => activity.reserve_updates += 1,
+            Some(t) if *t == TokenSwap::Swap::SIGNATURE_HASH => activity.swaps += 1,
+            Some(t) if *t == TokenSwap::LiquidityAdded::SIGNATURE_HASH || *t == TokenSwap::LiquidityRemoved::SIGNATURE_HASH => {
+                activity.liquidity_ops += 1
+            }
+            _ => {}
+        }
+        tx_logs.push(lg);
+    }
+
+    let mut report = FlashReport::default();
+    for (hash, (activity, tx_logs)) in txs {
+        if !activity.suspected_flash_loan() {
+            continue;
+        }
+        report.candidates += 1;
+        match inspect_tx(db, provider, chain_id, addrs.token_swap, hash, &tx_logs).await {
+            Ok(Some(alerts)) => {
+                report.detected += 1;
+                report.alerts += alerts;
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!(?e, %hash, "flash loan inspection error"),
+        }
+    }
+    Ok(report)
+}
+
+// 返回 None 表示轨迹中没有 flashLoan 调用；否则返回新产生的告警数
+async fn inspect_tx(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    pool: Address,
+    hash: B256,
+    tx_logs: &[&Log],
+) -> anyhow::Result<Option<usize>> {
+    let Some(tx) = provider.get_transaction_by_hash(hash).await? else {
+        return Ok(None);
+    };
+    let selector = TokenSwap::flashLoanCall::SELECTOR;
+    let decode = |input: &[u8]| {
+        TokenSwap::flashLoanCall::abi_decode(input, true)
+            .ok()
+            .map(|c| (c.tokenAmount, c.ethAmount))
+    };
+    let trace = match provider
+        .raw_request::<_, CallFrame>("debug_traceTransaction".into(), (hash, json!({ "tracer": "callTracer" })))
+        .await
+    {
+        Ok(root) => Some(root),
+        Err(e) => {
+            tracing::debug!(?e, %hash, "call trace unavailable");
+            None
+        }
+    };
+    let (source, borrower, amounts, calls) = match &trace {
+        Some(root) => {
+            let Some(call) = flash::find_calls(root, pool, selector).into_iter().next() else {
+                return Ok(None);
+            };
+            (Source::Trace, call.from, decode(&call.input), flash::external_calls(root, pool))
+        }
+        None if tx.to == Some(pool) && tx.input.starts_with(&selector) => (Source::Calldata, tx.from, decode(&tx.input), Vec::new()),
+        None => (Source::Logs, tx.to.unwrap_or(tx.from), None, Vec::new()),
+    };
+
+    let block = tx_logs.iter().find_map(|l| l.block_number).unwrap_or_default() as i64;
+    let first_index = tx_logs.iter().filter_map(|l| l.log_index).min().unwrap_or_default() as i32;
+    let prices: Vec<_> = tx_logs
+        .iter()
+        .filter_map(|l| TokenSwap::PriceUpdated::decode_log(&l.inner, true).ok())
+        .map(|ev| ev.tokenPrice)
+        .collect();
+    let repo = FlashRepo::new(db);
+    let before = repo.price_before(chain_id, block, first_index).await?;
+    let price_move = before.map(|b| flash::price_move_bps(b, &prices));
+
+    let tx_hash = format!("0x{:x}", hash);
+    let borrower = format!("0x{:x}", borrower);
+    repo.record(&NewFlashLoan {
+        chain_id,
+        tx_hash: &tx_hash,
+        block_number: block,
+        tx_from: &format!("0x{:x}", tx.from),
+        borrower: &borrower,
+        token_amount: amounts.map(|a| a.0),
+        eth_amount: amounts.map(|a| a.1),
+        source,
+        calls: &calls,
+        max_price_move_bps: price_move,
+    })
+    .await?;
+    tracing::info!(tx = %tx_hash, %borrower, source = source.as_str(), status = source.status(), calls = calls.len(), ?price_move, "flash loan detected");
+
+    let mut alerts = 0;
+    if let (Some(before), Some(m)) = (before, price_move) {
+        if m >= PRICE_MOVE_ALERT_BPS {
+            let detail = json!({
+                "price_before": before.to_string(),
+                "prices": prices.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+                "move_bps": m,
+                "threshold_bps": PRICE_MOVE_ALERT_BPS,
+            });
+            raise(&repo, chain_id, &tx_hash, &borrower, AlertKind::PriceMove, detail).await?;
+            alerts += 1;
+        }
+    }
+    let related: Vec<_> = repo
+        .sales_in_block(chain_id, block)
+        .await?
+        .into_iter()
+        .filter_map(|s| {
+            let link = flash::sale_link(&s.tx_hash, &[s.buyer.as_deref(), s.seller.as_deref()], &tx_hash, &borrower, &calls)?;
+            Some(json!({ "link": link, "sale": s }))
+        })
+        .collect();
+    if !related.is_empty() {
+        let detail = json!({ "block": block, "sales": related });
+        raise(&repo, chain_id, &tx_hash, &borrower, AlertKind::MarketplaceSale, detail).await?;
+        alerts += 1;
+    }
+    Ok(Some(alerts))
+}
+
+async fn raise(
+    repo: &FlashRepo<'_>,
+    chain_id: i64,
+    tx_hash: &str,
+    borrower: &str,
+    kind: AlertKind,
+    detail: serde_json::Value,
+) -> anyhow::Result<()> {
+    repo.add_alert(chain_id, tx_hash, kind, detail.clone()).await?;
+    metrics::counter!("flash_loan_alerts_total", "kind" => kind.as_str()).increment(1);
+    tracing::warn!(tx = %tx_hash, %borrower, kind = kind.as_str(), %detail, "flash loan alert");
+    Ok(())
+}
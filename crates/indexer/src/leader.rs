@@ -0,0 +1,67 @@
+// 单主选举：在专用连接上持有 Postgres advisory lock（按 chain_id 区分），
+// 连接断开时锁自动释放，其他实例随后接管。
+
+use shared::db::pool::Db;
+use tokio::time::{sleep, Duration};
+
+use crate::control::IndexerControl;
+
+// 避免与其他业务的 advisory lock 冲突
+const LOCK_NAMESPACE: i64 = 0x4f54_0000;
+
+pub async fn leader_loop(db: Db, chain_id: i64, ctl: IndexerControl) {
+    let key = LOCK_NAMESPACE + chain_id;
+    loop {
+        let mut conn = match db.0.acquire().await {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!(?e, "leader: acquire connection failed");
+                sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        // 尝试加锁；拿到后保持连接并定期探活
+        loop {
+            let got = sqlx::query_scalar!("SELECT pg_try_advisory_lock($1)", key)
+                .fetch_one(&mut *conn)
+                .await;
+            match got {
+                Ok(Some(true)) => {
+                    if !ctl.is_leader() {
+                        tracing::info!(chain_id, "leader lock acquired");
+                    }
+                    ctl.set_leader(true);
+                    while sqlx::query!("SELECT 1 AS one").fetch_one(&mut *conn).await.is_ok() {
+                        sleep(Duration::from_secs(10)).await;
+                    }
+                    tracing::warn!(chain_id, "leader connection lost");
+                    ctl.set_leader(false);
+                    break;
+                }
+                Ok(_) => {
+                    ctl.set_leader(false);
+                    sleep(Duration::from_secs(10)).await;
+                }
+                Err(e) => {
+                    tracing::error!(?e, "leader: try lock failed");
+                    ctl.set_leader(false);
+                    break;
+                }
+            }
+        }
+        // 丢弃可能已损坏的连接，重新获取
+        conn.detach();
+        sleep(Duration::from_secs(1)).await;
+    }
+}
+
+pub async fn wait_for_leadership(ctl: &IndexerControl) {
+    let mut logged = false;
+    while !ctl.is_leader() {
+        if !logged {
+            tracing::info!("waiting for leader lock");
+            logged = true;
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,11 @@
+pub mod admin;
+pub mod control;
+pub mod cursor;
+pub mod export;
+pub mod flash;
+pub mod leader;
+pub mod pipeline;
+pub mod reconcile;
+pub mod recording;
+pub mod roles;
+pub mod whitelist;
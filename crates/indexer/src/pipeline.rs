@@ -0,0 +1,794 @@
+use alloy::eips::{BlockId, BlockNumberOrTag};
+use alloy::primitives::{Address, B256, U256};
+use alloy::rpc::types::eth::{Filter, Log};
+use alloy::sol_types::SolEvent;
+use shared::contracts::bindings::{EventManager, Marketplace, TicketManager, TokenSwap};
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use shared::domain::market::auction::AuctionStatus;
+use shared::domain::market::ListingStatus;
+use shared::domain::swap::history::swap_price;
+use shared::domain::ticket::TicketStatus;
+use shared::domain::user::Role;
+use shared::repo::event_repo::EventRepo;
+use shared::repo::market_repo::MarketRepo;
+use shared::repo::ticket_repo::TicketRepo;
+use shared::repo::user_repo::UserRepo;
+use std::collections::BTreeSet;
+use tokio::time::{interval, Duration};
+
+use crate::control::IndexerControl;
+use crate::cursor::{load_cursor, save_cursor};
+use crate::flash;
+
+pub const CONFIRM_DEPTH: i64 = 6; // 最小确认区块数，避免浅层重组
+
+// 每批持有 step 锁并重新读取游标；失去 leader 或被暂停时中止，剩余区间由增量循环接续
+pub async fn backfill(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    ctl: &IndexerControl,
+) -> anyhow::Result<()> {
+    let start = load_cursor(db, chain_id).await?;
+    let raw_latest = provider.get_block_number().await? as i64;
+    if raw_latest <= CONFIRM_DEPTH {
+        tracing::warn!(
+            raw_latest,
+            "chain height below confirm depth; skip backfill"
+        );
+        return Ok(());
+    }
+    let latest = raw_latest - CONFIRM_DEPTH; // 只处理已确认高度
+    if start >= latest {
+        tracing::info!("no backfill needed: start={start} latest={latest}");
+        return Ok(());
+    }
+    tracing::info!(
+        start,
+        latest,
+        head = raw_latest,
+        depth = CONFIRM_DEPTH,
+        "begin backfill range (finalized)"
+    );
+
+    let batch: i64 = 1_000;
+    loop {
+        let _step = ctl.lock_step().await;
+        if !ctl.is_active() {
+            tracing::info!("backfill stopped: paused or not leader");
+            return Ok(());
+        }
+        let from = load_cursor(db, chain_id).await?.max(0);
+        if from >= latest {
+            return Ok(());
+        }
+        let to = (from + batch).min(latest);
+        fetch_and_process(db, provider, chain_id, addrs, from + 1, to).await?; // (from, to]
+        save_cursor(db, chain_id, to).await?;
+    }
+}
+
+pub async fn incremental_loop(
+    db: Db,
+    provider: SharedProvider,
+    chain_id: i64,
+    addrs: ContractAddresses,
+    ctl: IndexerControl,
+) {
+    let mut intv = interval(Duration::from_secs(6));
+    loop {
+        intv.tick().await;
+        // 非 leader 或被管理接口暂停时跳过本轮
+        if !ctl.is_active() {
+            continue;
+        }
+        let _step = ctl.lock_step().await;
+        while let Some((from, to)) = ctl.take_reindex() {
+            match reindex_range(&db, &provider, chain_id, &addrs, from, to).await {
+                Ok(()) => tracing::info!(from, to, "reindex done"),
+                Err(e) => {
+                    tracing::error!(?e, from, to, "reindex error");
+                    ctl.record_error(format!("reindex {from}-{to}: {e:#}"));
+                }
+            }
+        }
+        match incremental_step(&db, &provider, chain_id, &addrs).await {
+            Ok(()) => {}
+            Err(e) => {
+                tracing::error!(?e, "incremental step error");
+                ctl.record_error(format!("incremental step: {e:#}"));
+            }
+        }
+    }
+}
+
+// 重新处理 [from, to]，不移动游标（写入均为幂等）
+pub async fn reindex_range(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    from: i64,
+    to: i64,
+) -> anyhow::Result<()> {
+    let batch: i64 = 500;
+    let mut start = from;
+    while start <= to {
+        let end = (start + batch - 1).min(to);
+        fetch_and_process(db, provider, chain_id, addrs, start, end).await?;
+        start = end + 1;
+    }
+    Ok(())
+}
+
+pub async fn incremental_step(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+) -> anyhow::Result<()> {
+    let mut last = load_cursor(db, chain_id).await?;
+    let raw_latest = provider.get_block_number().await? as i64;
+    if raw_latest <= CONFIRM_DEPTH {
+        return Ok(());
+    }
+    let latest = raw_latest - CONFIRM_DEPTH; // finalized tip
+    if last >= latest {
+        return Ok(());
+    }
+    let batch: i64 = 500;
+    while last < latest {
+        let to = (last + batch).min(latest);
+        fetch_and_process(db, provider, chain_id, addrs, last + 1, to).await?;
+        save_cursor(db, chain_id, to).await?;
+        last = to;
+    }
+    Ok(())
+}
+
+// 收集所有关心的合约地址
+pub(crate) fn topics(addrs: &ContractAddresses) -> Vec<Address> {
+    vec![
+        addrs.ticket_manager,
+        addrs.event_manager,
+        addrs.marketplace,
+        addrs.token_swap,
+    ]
+}
+
+// 构建过滤器（地址集合 + 区块范围）
+pub fn log_filter(addrs: &ContractAddresses, from: i64, to: i64) -> Filter {
+    Filter::new()
+        .address(topics(addrs))
+        .from_block(from as u64)
+        .to_block(to as u64)
+}
+
+pub async fn fetch_and_process(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    from: i64,
+    to: i64,
+) -> anyhow::Result<()> {
+    let logs = provider.get_logs(&log_filter(addrs, from, to)).await?;
+    tracing::info!(count = logs.len(), from, to, "fetched logs");
+    record_blocks(db, provider, chain_id, &logs).await?;
+    process_logs(db, chain_id, &logs).await;
+    if let Err(e) = refresh_auction_end_times(db, provider, chain_id, addrs, &logs, to).await {
+        tracing::error!(?e, from, to, "auction end time refresh error");
+    }
+    if let Err(e) = refresh_ticket_type_prices(db, provider, addrs, &logs).await {
+        tracing::error!(?e, from, to, "ticket type price refresh error");
+    }
+    // 闪电贷检测失败不阻断游标推进
+    match flash::inspect(db, provider, chain_id, addrs, &logs).await {
+        Ok(r) if r.detected > 0 => tracing::warn!(?r, "flash loans detected"),
+        Ok(_) => {}
+        Err(e) => tracing::error!(?e, "flash loan inspection error"),
+    }
+    Ok(())
+}
+
+// 出价可能触发防狙击延时（auctionExtensionTime），合约不发延时事件：
+// 对本批有出价的拍卖按批次末尾区块重读 endTime，只延后不提前（重索引旧区间不回退）
+async fn refresh_auction_end_times(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    logs: &[Log],
+    at: i64,
+) -> anyhow::Result<()> {
+    let ids: BTreeSet<U256> = logs
+        .iter()
+        .filter(|l| l.topics().first() == Some(&Marketplace::BidPlaced::SIGNATURE_HASH))
+        .filter_map(|l| Marketplace::BidPlaced::decode_log(&l.inner, true).ok())
+        .map(|ev| ev.auctionId)
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let mk = Marketplace::new(addrs.marketplace, provider.clone());
+    for id in ids {
+        let end = mk.auctions(id).block(BlockId::number(at as u64)).call().await?.endTime;
+        let end_time = chrono::DateTime::from_timestamp(end.saturating_to::<i64>(), 0).unwrap_or_default();
+        sqlx::query!("UPDATE marketplace_auctions SET end_time=$3 WHERE chain_id=$1 AND auction_id=$2::TEXT::NUMERIC AND end_time < $3",
+            chain_id,
+            id.to_string(),
+            end_time
+        ).execute(&db.0).await?;
+    }
+    Ok(())
+}
+
+// TicketTypeAdded 不含 ETH 价格：按日志所在区块读取 getTicketTypeInfo，记录面值供转售限价校验
+async fn refresh_ticket_type_prices(
+    db: &Db,
+    provider: &SharedProvider,
+    addrs: &ContractAddresses,
+    logs: &[Log],
+) -> anyhow::Result<()> {
+    let em = EventManager::new(addrs.event_manager, provider.clone());
+    let repo = EventRepo::new(db);
+    for lg in logs.iter().filter(|l| l.topics().first() == Some(&EventManager::TicketTypeAdded::SIGNATURE_HASH)) {
+        let Ok(ev) = EventManager::TicketTypeAdded::decode_log(&lg.inner, true) else {
+            continue;
+        };
+        let at = BlockId::number(lg.block_number.unwrap_or_default());
+        let info = em.getTicketTypeInfo(ev.eventId, ev.typeId).block(at).call().await?;
+        repo.set_chain_type_prices(ev.eventId.saturating_to(), ev.typeId.saturating_to(), &info.price.to_string(), &info.ethPrice.to_string())
+            .await?;
+    }
+    Ok(())
+}
+
+// 为含日志的区块保存区块头（时间戳用于按天聚合/导出）
+async fn record_blocks(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    logs: &[Log],
+) -> anyhow::Result<()> {
+    let blocks: BTreeSet<u64> = logs.iter().filter_map(|l| l.block_number).collect();
+    for n in blocks {
+        let Some(b) = provider
+            .get_block_by_number(BlockNumberOrTag::Number(n), false)
+            .await?
+        else {
+            continue;
+        };
+        save_block(db, chain_id, n, b.header.hash, b.header.parent_hash, b.header.timestamp).await?;
+    }
+    Ok(())
+}
+
+pub async fn save_block(
+    db: &Db,
+    chain_id: i64,
+    number: u64,
+    hash: B256,
+    parent_hash: B256,
+    timestamp: u64,
+) -> anyhow::Result<()> {
+    let block_time = chrono::DateTime::from_timestamp(timestamp as i64, 0).unwrap_or_default();
+    sqlx::query!(
+        "INSERT INTO chain_blocks(chain_id,block_number,block_hash,parent_hash,block_time) VALUES($1,$2,$3,$4,$5)
+         ON CONFLICT (chain_id,block_number) DO UPDATE SET block_hash=EXCLUDED.block_hash, parent_hash=EXCLUDED.parent_hash, block_time=EXCLUDED.block_time",
+        chain_id,
+        number as i64,
+        format!("0x{:x}", hash),
+        format!("0x{:x}", parent_hash),
+        block_time
+    )
+    .execute(&db.0)
+    .await?;
+    Ok(())
+}
+
+// 单条失败只记录错误，不阻断整批（与回放模式共用）；返回失败条数
+pub async fn process_logs(db: &Db, chain_id: i64, logs: &[Log]) -> usize {
+    let mut failed = 0;
+    for lg in logs {
+        if let Err(e) = process_log(db, chain_id, lg).await {
+            tracing::error!(?e, "process log error");
+            failed += 1;
+        }
+    }
+    failed
+}
+
+// 按活动转售政策标记绕过 API 直接上链的违规挂单
+async fn flag_listing(db: &Db, chain_id: i64, listing_id: U256, block: i64) -> anyhow::Result<()> {
+    let violations = MarketRepo::new(db)
+        .flag_listing(chain_id, &listing_id.to_string(), block)
+        .await?;
+    if !violations.is_empty() {
+        tracing::warn!(%listing_id, ?violations, "listing violates resale policy");
+    }
+    Ok(())
+}
+
+// 由交易内已铸造的 ticket_tokens 生成 tickets 业务投影，已存在的行不动
+// 票种取同一交易唯一的 TicketPurchased 票种，否则取活动唯一的已上链票种；都无法确定时暂不生成
+// 持有人位置记为 (最近更新区块, -1)，同区块及之后的 Transfer 仍可覆盖
+async fn project_tickets(db: &Db, chain_id: i64, tx_hash: &str) -> anyhow::Result<()> {
+    let n = sqlx::query!("INSERT INTO tickets(token_id,event_id,type_id,owner_wallet,seat_label,status,tx_mint,minted_block,owner_block,owner_log_index)
+         SELECT tk.token_id::BIGINT, e.id, ty.id, tk.owner, tk.seat_number::TEXT, 'valid', tk.mint_tx, tk.minted_block, tk.updated_block, -1
+         FROM ticket_tokens tk
+         JOIN events e ON e.chain_event_id = tk.event_id
+         JOIN ticket_types ty ON ty.event_id = e.id AND ty.chain_type_id = COALESCE(
+             (SELECT MIN(p.chain_type_id) FROM ticket_purchases p
+              WHERE p.chain_id = tk.chain_id AND p.tx_hash = tk.mint_tx AND p.chain_event_id = e.chain_event_id
+              HAVING COUNT(DISTINCT p.chain_type_id) = 1),
+             (SELECT MIN(t.chain_type_id) FROM ticket_types t
+              WHERE t.event_id = e.id AND t.chain_type_id IS NOT NULL
+              HAVING COUNT(*) = 1))
+         WHERE tk.chain_id = $1 AND tk.mint_tx = $2 AND tk.owner IS NOT NULL
+         ON CONFLICT (token_id) DO NOTHING",
+        chain_id,
+        tx_hash
+    ).execute(&db.0).await?.rows_affected();
+    if n > 0 {
+        tracing::debug!(tx = %tx_hash, tickets = n, "tickets projected from mint");
+    }
+    Ok(())
+}
+
+pub async fn process_log(db: &Db, chain_id: i64, lg: &Log) -> anyhow::Result<()> {
+    // 持久化原始记录 (避免重复: ON CONFLICT DO NOTHING)
+    let tx_hash = format!("0x{:x}", lg.transaction_hash.unwrap_or_default());
+    let primary_topic = lg
+        .topics()
+        .first()
+        .map(|t| format!("0x{:x}", t))
+        .unwrap_or_default();
+    sqlx::query!("INSERT INTO chain_logs(chain_id,block_number,tx_hash,log_index,primary_topic,contract_address,data) VALUES($1,$2,$3,$4,$5,$6,$7) ON CONFLICT DO NOTHING",
+        chain_id,
+        lg.block_number.unwrap_or_default() as i64,
+        tx_hash,
+        lg.log_index.unwrap_or_default() as i32,
+        primary_topic,
+        format!("0x{:x}", lg.address()),
+        serde_json::json!({ "data": lg.data().data.to_string() })
+    ).execute(&db.0).await?;
+
+    // ERC-721 Transfer（4 个 topic）；同签名的 ERC-20 Transfer 解码会失败，自然跳过
+    if lg.topics().first() == Some(&TicketManager::Transfer::SIGNATURE_HASH) {
+        if let Ok(ev) = TicketManager::Transfer::decode_log(&lg.inner, true) {
+            sqlx::query!("INSERT INTO ticket_transfers(chain_id,block_number,tx_hash,log_index,token_id,from_addr,to_addr) VALUES($1,$2,$3,$4,$5::TEXT::NUMERIC,$6,$7) ON CONFLICT DO NOTHING",
+                chain_id,
+                lg.block_number.unwrap_or_default() as i64,
+                tx_hash,
+                lg.log_index.unwrap_or_default() as i32,
+                ev.tokenId.to_string(),
+                format!("0x{:x}", ev.from),
+                format!("0x{:x}", ev.to)
+            ).execute(&db.0).await?;
+            // 持有人投影：仅接受不早于当前记录的区块，重放旧区间不会回退 owner
+            let block = lg.block_number.unwrap_or_default() as i64;
+            let owner = format!("0x{:x}", ev.to);
+            sqlx::query!("INSERT INTO ticket_tokens(chain_id,token_id,owner,minted_block,updated_block) VALUES($1,$2::TEXT::NUMERIC,$3,CASE WHEN $4 THEN $5::BIGINT END,$5)
+                 ON CONFLICT (chain_id,token_id) DO UPDATE SET owner=EXCLUDED.owner, updated_block=EXCLUDED.updated_block,
+                    minted_block=COALESCE(ticket_tokens.minted_block, EXCLUDED.minted_block)
+                 WHERE ticket_tokens.updated_block IS NULL OR ticket_tokens.updated_block <= EXCLUDED.updated_block",
+                chain_id,
+                ev.tokenId.to_string(),
+                owner,
+                ev.from == Address::ZERO,
+                block
+            ).execute(&db.0).await?;
+            sqlx::query!("UPDATE tickets SET owner_wallet=$2, owner_block=$3, owner_log_index=$4
+                 WHERE token_id=$1 AND (owner_block IS NULL OR (owner_block, owner_log_index) <= ($3, $4))",
+                ev.tokenId.saturating_to::<i64>(),
+                owner,
+                block,
+                lg.log_index.unwrap_or_default() as i32
+            ).execute(&db.0).await?;
+        }
+    }
+
+    // 门票元数据：铸造时记录链上 eventId、座位号与铸造交易并生成 tickets 投影，状态变更同步 tickets.status
+    if lg.topics().first() == Some(&TicketManager::TicketMinted::SIGNATURE_HASH) {
+        if let Ok(ev) = TicketManager::TicketMinted::decode_log(&lg.inner, true) {
+            sqlx::query!("INSERT INTO ticket_tokens(chain_id,token_id,event_id,owner,minted_block,updated_block,mint_tx,seat_number)
+                 VALUES($1,$2::TEXT::NUMERIC,$3::TEXT::NUMERIC,$4,$5,$5,$6,$7::TEXT::NUMERIC)
+                 ON CONFLICT (chain_id,token_id) DO UPDATE SET event_id=EXCLUDED.event_id, mint_tx=EXCLUDED.mint_tx,
+                    seat_number=EXCLUDED.seat_number, minted_block=COALESCE(ticket_tokens.minted_block, EXCLUDED.minted_block)",
+                chain_id,
+                ev.tokenId.to_string(),
+                ev.eventId.to_string(),
+                format!("0x{:x}", ev.buyer),
+                lg.block_number.unwrap_or_default() as i64,
+                tx_hash,
+                ev.seatNumber.to_string()
+            ).execute(&db.0).await?;
+            project_tickets(db, chain_id, &tx_hash).await?;
+        }
+    }
+    // ERC-4906：标记元数据失效，API 侧 ETag 随版本变化
+    let metadata_range = match lg.topics().first() {
+        Some(t) if *t == TicketManager::MetadataUpdate::SIGNATURE_HASH => {
+            TicketManager::MetadataUpdate::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e._tokenId, e._tokenId))
+        }
+        Some(t) if *t == TicketManager::BatchMetadataUpdate::SIGNATURE_HASH => {
+            TicketManager::BatchMetadataUpdate::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e._fromTokenId, e._toTokenId))
+        }
+        _ => None,
+    };
+    if let Some((from, to)) = metadata_range {
+        let n = TicketRepo::new(db)
+            .bump_metadata(chain_id, &from.to_string(), &to.to_string())
+            .await?;
+        tracing::debug!(%from, %to, tokens = n, "metadata invalidated");
+    }
+
+    let status_change = match lg.topics().first() {
+        Some(t) if *t == TicketManager::TicketStatusChanged::SIGNATURE_HASH => {
+            TicketManager::TicketStatusChanged::decode_log(&lg.inner, true)
+                .ok()
+                .and_then(|e| Some((e.tokenId, TicketStatus::from_chain(e.newStatus)?)))
+        }
+        Some(t) if *t == TicketManager::TicketUsed::SIGNATURE_HASH => {
+            TicketManager::TicketUsed::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e.tokenId, TicketStatus::Used))
+        }
+        Some(t) if *t == TicketManager::TicketCancelled::SIGNATURE_HASH => {
+            TicketManager::TicketCancelled::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e.tokenId, TicketStatus::Cancelled))
+        }
+        _ => None,
+    };
+    // 与持有人投影相同，按 (区块, 日志序号) 只前进不回退
+    if let Some((token_id, status)) = status_change {
+        sqlx::query!("UPDATE tickets SET status=$2, status_block=$3, status_log_index=$4
+             WHERE token_id=$1 AND (status_block IS NULL OR (status_block, status_log_index) <= ($3, $4))",
+            token_id.saturating_to::<i64>(),
+            status.as_str(),
+            lg.block_number.unwrap_or_default() as i64,
+            lg.log_index.unwrap_or_default() as i32
+        ).execute(&db.0).await?;
+    }
+
+    // 链上角色授权变更 -> chain_roles / users.role
+    let role_change = match lg.topics().first() {
+        Some(t) if *t == EventManager::OrganizerAuthorized::SIGNATURE_HASH => {
+            EventManager::OrganizerAuthorized::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e.organizer, Role::Organizer, e.authorized))
+        }
+        Some(t) if *t == TicketManager::VerifierAuthorized::SIGNATURE_HASH => {
+            TicketManager::VerifierAuthorized::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e.verifier, Role::Verifier, e.authorized))
+        }
+        Some(t) if *t == TicketManager::MinterAuthorized::SIGNATURE_HASH => {
+            TicketManager::MinterAuthorized::decode_log(&lg.inner, true)
+                .ok()
+                .map(|e| (e.minter, Role::Minter, e.authorized))
+        }
+        _ => None,
+    };
+    if let Some((who, role, authorized)) = role_change {
+        let wallet = format!("0x{:x}", who);
+        let users = UserRepo::new(db);
+        users
+            .apply_chain_role(
+                chain_id,
+                &wallet,
+                role,
+                authorized,
+                lg.block_number.unwrap_or_default() as i64,
+                lg.log_index.unwrap_or_default() as i32,
+            )
+            .await?;
+        users.sync_role(&wallet).await?;
+    }
+
+    // 组织者草稿发布：EventCreated / TicketTypeAdded 回填链上 ID
+    if lg.topics().first() == Some(&EventManager::EventCreated::SIGNATURE_HASH) {
+        if let Ok(ev) = EventManager::EventCreated::decode_log(&lg.inner, true) {
+            let ts = |v: alloy::primitives::U256| {
+                chrono::DateTime::from_timestamp(v.saturating_to::<i64>(), 0).unwrap_or_default()
+            };
+            let chain_event_id: i64 = ev.eventId.saturating_to();
+            let linked = EventRepo::new(db)
+                .link_chain_event(
+                    &format!("0x{:x}", ev.organizer),
+                    &ev.name,
+                    ts(ev.startTime),
+                    ts(ev.endTime),
+                    chain_event_id,
+                )
+                .await?;
+            tracing::info!(chain_event_id, ?linked, "EventCreated");
+        }
+    }
+    if lg.topics().first() == Some(&EventManager::TicketTypeAdded::SIGNATURE_HASH) {
+        if let Ok(ev) = EventManager::TicketTypeAdded::decode_log(&lg.inner, true) {
+            let linked = EventRepo::new(db)
+                .link_chain_ticket_type(ev.eventId.saturating_to(), &ev.name, ev.typeId.saturating_to())
+                .await?;
+            tracing::info!(event_id = %ev.eventId, type_id = %ev.typeId, ?linked, "TicketTypeAdded");
+        }
+    }
+    // 一级市场购买：剩余可购数量按此汇总
+    if lg.topics().first() == Some(&EventManager::TicketPurchased::SIGNATURE_HASH) {
+        if let Ok(ev) = EventManager::TicketPurchased::decode_log(&lg.inner, true) {
+            sqlx::query!("INSERT INTO ticket_purchases(chain_id,block_number,tx_hash,log_index,chain_event_id,chain_type_id,buyer,quantity,total_cost,paid_with_eth)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9::TEXT::NUMERIC,$10) ON CONFLICT DO NOTHING",
+                chain_id,
+                lg.block_number.unwrap_or_default() as i64,
+                tx_hash,
+                lg.log_index.unwrap_or_default() as i32,
+                ev.eventId.saturating_to::<i64>(),
+                ev.typeId.saturating_to::<i64>(),
+                format!("0x{:x}", ev.buyer),
+                ev.quantity.saturating_to::<i64>(),
+                ev.totalCost.to_string(),
+                ev.paidWithEth
+            ).execute(&db.0).await?;
+            // TicketMinted 先于 TicketPurchased 发出时，票种到这里才能确定
+            project_tickets(db, chain_id, &tx_hash).await?;
+        }
+    }
+
+    // 二级市场挂单 / 成交 / 拍卖投影；状态只接受不早于当前记录的区块
+    let block = lg.block_number.unwrap_or_default() as i64;
+    let ts = |v: alloy::primitives::U256| {
+        chrono::DateTime::from_timestamp(v.saturating_to::<i64>(), 0).unwrap_or_default()
+    };
+    match lg.topics().first() {
+        Some(t) if *t == Marketplace::ListingCreated::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::ListingCreated::decode_log(&lg.inner, true) {
+                sqlx::query!("INSERT INTO marketplace_listings(chain_id,listing_id,ticket_id,seller,status,price,eth_price,expires_at,created_block,updated_block)
+                     VALUES($1,$2::TEXT::NUMERIC,$3::TEXT::NUMERIC,$4,$5,$6::TEXT::NUMERIC,$7::TEXT::NUMERIC,$8,$9,$9)
+                     ON CONFLICT (chain_id,listing_id) DO UPDATE SET ticket_id=EXCLUDED.ticket_id, seller=EXCLUDED.seller,
+                        price=EXCLUDED.price, eth_price=EXCLUDED.eth_price, expires_at=EXCLUDED.expires_at, created_block=EXCLUDED.created_block,
+                        status=CASE WHEN marketplace_listings.updated_block > EXCLUDED.updated_block THEN marketplace_listings.status ELSE EXCLUDED.status END,
+                        updated_block=GREATEST(marketplace_listings.updated_block, EXCLUDED.updated_block)",
+                    chain_id,
+                    ev.listingId.to_string(),
+                    ev.tokenId.to_string(),
+                    format!("0x{:x}", ev.seller),
+                    ListingStatus::Active.to_chain(),
+                    ev.price.to_string(),
+                    ev.ethPrice.to_string(),
+                    ts(ev.expiresAt),
+                    block
+                ).execute(&db.0).await?;
+                flag_listing(db, chain_id, ev.listingId, block).await?;
+            }
+        }
+        Some(t) if *t == Marketplace::ListingUpdated::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::ListingUpdated::decode_log(&lg.inner, true) {
+                sqlx::query!("UPDATE marketplace_listings SET price=$3::TEXT::NUMERIC, eth_price=$4::TEXT::NUMERIC, expires_at=$5, updated_block=$6
+                     WHERE chain_id=$1 AND listing_id=$2::TEXT::NUMERIC AND (updated_block IS NULL OR updated_block <= $6)",
+                    chain_id,
+                    ev.listingId.to_string(),
+                    ev.newPrice.to_string(),
+                    ev.newEthPrice.to_string(),
+                    ts(ev.newExpiresAt),
+                    block
+                ).execute(&db.0).await?;
+                flag_listing(db, chain_id, ev.listingId, block).await?;
+            }
+        }
+        Some(t) if *t == Marketplace::ListingCancelled::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::ListingCancelled::decode_log(&lg.inner, true) {
+                sqlx::query!("UPDATE marketplace_listings SET status=$3, updated_block=$4
+                     WHERE chain_id=$1 AND listing_id=$2::TEXT::NUMERIC AND (updated_block IS NULL OR updated_block <= $4)",
+                    chain_id,
+                    ev.listingId.to_string(),
+                    ListingStatus::Cancelled.to_chain(),
+                    block
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == Marketplace::TicketSold::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::TicketSold::decode_log(&lg.inner, true) {
+                sqlx::query!("UPDATE marketplace_listings SET status=$3, updated_block=$4
+                     WHERE chain_id=$1 AND listing_id=$2::TEXT::NUMERIC AND (updated_block IS NULL OR updated_block <= $4)",
+                    chain_id,
+                    ev.listingId.to_string(),
+                    ListingStatus::Sold.to_chain(),
+                    block
+                ).execute(&db.0).await?;
+                sqlx::query!("INSERT INTO marketplace_trades(chain_id,listing_id,buyer,price,block_number,tx_hash,seller,token_id,paid_with_eth)
+                     VALUES($1,$2::TEXT::NUMERIC,$3,$4::TEXT::NUMERIC,$5,$6,$7,$8::TEXT::NUMERIC,$9) ON CONFLICT DO NOTHING",
+                    chain_id,
+                    ev.listingId.to_string(),
+                    format!("0x{:x}", ev.buyer),
+                    ev.price.to_string(),
+                    block,
+                    tx_hash,
+                    format!("0x{:x}", ev.seller),
+                    ev.tokenId.to_string(),
+                    ev.paidWithEth
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == Marketplace::AuctionCreated::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::AuctionCreated::decode_log(&lg.inner, true) {
+                sqlx::query!("INSERT INTO marketplace_auctions(chain_id,auction_id,token_id,seller,starting_price,eth_starting_price,reserve_price,end_time,status,created_block,updated_block)
+                     VALUES($1,$2::TEXT::NUMERIC,$3::TEXT::NUMERIC,$4,$5::TEXT::NUMERIC,$6::TEXT::NUMERIC,$7::TEXT::NUMERIC,$8,$9,$10,$10)
+                     ON CONFLICT (chain_id,auction_id) DO NOTHING",
+                    chain_id,
+                    ev.auctionId.to_string(),
+                    ev.tokenId.to_string(),
+                    format!("0x{:x}", ev.seller),
+                    ev.startingPrice.to_string(),
+                    ev.ethStartingPrice.to_string(),
+                    ev.reservePrice.to_string(),
+                    ts(ev.endTime),
+                    AuctionStatus::Active.to_chain(),
+                    block
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == Marketplace::BidPlaced::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::BidPlaced::decode_log(&lg.inner, true) {
+                let inserted = sqlx::query!("INSERT INTO marketplace_bids(chain_id,auction_id,bidder,amount,is_eth,block_number,tx_hash,log_index)
+                     VALUES($1,$2::TEXT::NUMERIC,$3,$4::TEXT::NUMERIC,$5,$6,$7,$8) ON CONFLICT DO NOTHING",
+                    chain_id,
+                    ev.auctionId.to_string(),
+                    format!("0x{:x}", ev.bidder),
+                    ev.amount.to_string(),
+                    ev.isEth,
+                    block,
+                    tx_hash,
+                    lg.log_index.unwrap_or_default() as i32
+                ).execute(&db.0).await?.rows_affected();
+                // 重放时不重复计数；当前最高价以最新一条出价为准
+                if inserted > 0 {
+                    sqlx::query!("UPDATE marketplace_auctions SET current_bid=$3::TEXT::NUMERIC, current_bidder=$4, current_bid_is_eth=$5,
+                            bid_count=bid_count+1, updated_block=GREATEST(updated_block,$6)
+                         WHERE chain_id=$1 AND auction_id=$2::TEXT::NUMERIC AND updated_block <= $6",
+                        chain_id,
+                        ev.auctionId.to_string(),
+                        ev.amount.to_string(),
+                        format!("0x{:x}", ev.bidder),
+                        ev.isEth,
+                        block
+                    ).execute(&db.0).await?;
+                }
+            }
+        }
+        Some(t) if *t == Marketplace::AuctionEnded::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::AuctionEnded::decode_log(&lg.inner, true) {
+                sqlx::query!("UPDATE marketplace_auctions SET status=$3, winner=$4, winning_bid=$5::TEXT::NUMERIC, updated_block=$6
+                     WHERE chain_id=$1 AND auction_id=$2::TEXT::NUMERIC AND updated_block <= $6",
+                    chain_id,
+                    ev.auctionId.to_string(),
+                    AuctionStatus::Ended.to_chain(),
+                    format!("0x{:x}", ev.winner),
+                    ev.winningBid.to_string(),
+                    block
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == Marketplace::AuctionCancelled::SIGNATURE_HASH => {
+            if let Ok(ev) = Marketplace::AuctionCancelled::decode_log(&lg.inner, true) {
+                sqlx::query!("UPDATE marketplace_auctions SET status=$3, updated_block=$4
+                     WHERE chain_id=$1 AND auction_id=$2::TEXT::NUMERIC AND updated_block <= $4",
+                    chain_id,
+                    ev.auctionId.to_string(),
+                    AuctionStatus::Cancelled.to_chain(),
+                    block
+                ).execute(&db.0).await?;
+            }
+        }
+        _ => {}
+    }
+
+    // TokenSwap 成交与价格观测（K 线 / TWAP）、流动性存取与 LP 代币转移、费率变更；ETH 一侧以零地址表示
+    match lg.topics().first() {
+        Some(t) if *t == TokenSwap::Swap::SIGNATURE_HASH => {
+            if let Ok(ev) = TokenSwap::Swap::decode_log(&lg.inner, true) {
+                let (eth, token) = if ev.tokenIn == Address::ZERO {
+                    (ev.amountIn, ev.amountOut)
+                } else {
+                    (ev.amountOut, ev.amountIn)
+                };
+                let price = swap_price(eth, token).map(|p| p.to_string());
+                sqlx::query!("INSERT INTO token_swaps(chain_id,tx_hash,log_index,user_addr,token_in,token_out,amount_in,amount_out,fee,eth_amount,token_amount,price,block_number)
+                     VALUES($1,$2,$3,$4,$5,$6,$7::TEXT::NUMERIC,$8::TEXT::NUMERIC,$9::TEXT::NUMERIC,$10::TEXT::NUMERIC,$11::TEXT::NUMERIC,$12::TEXT::NUMERIC,$13)
+                     ON CONFLICT DO NOTHING",
+                    chain_id,
+                    tx_hash,
+                    lg.log_index.unwrap_or_default() as i32,
+                    format!("0x{:x}", ev.user),
+                    format!("0x{:x}", ev.tokenIn),
+                    format!("0x{:x}", ev.tokenOut),
+                    ev.amountIn.to_string(),
+                    ev.amountOut.to_string(),
+                    ev.fee.to_string(),
+                    eth.to_string(),
+                    token.to_string(),
+                    price,
+                    block
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == TokenSwap::PriceUpdated::SIGNATURE_HASH => {
+            if let Ok(ev) = TokenSwap::PriceUpdated::decode_log(&lg.inner, true) {
+                sqlx::query!("INSERT INTO token_price_observations(chain_id,block_number,log_index,tx_hash,token_price,eth_price,observed_at)
+                     VALUES($1,$2,$3,$4,$5::TEXT::NUMERIC,$6::TEXT::NUMERIC,$7) ON CONFLICT DO NOTHING",
+                    chain_id,
+                    block,
+                    lg.log_index.unwrap_or_default() as i32,
+                    tx_hash,
+                    ev.tokenPrice.to_string(),
+                    ev.ethPrice.to_string(),
+                    ts(ev.timestamp)
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == TokenSwap::LiquidityAdded::SIGNATURE_HASH || *t == TokenSwap::LiquidityRemoved::SIGNATURE_HASH => {
+            let decoded = if *t == TokenSwap::LiquidityAdded::SIGNATURE_HASH {
+                TokenSwap::LiquidityAdded::decode_log(&lg.inner, true)
+                    .ok()
+                    .map(|ev| ("add", ev.provider, ev.tokenAmount, ev.ethAmount, ev.liquidity))
+            } else {
+                TokenSwap::LiquidityRemoved::decode_log(&lg.inner, true)
+                    .ok()
+                    .map(|ev| ("remove", ev.provider, ev.tokenAmount, ev.ethAmount, ev.liquidity))
+            };
+            if let Some((kind, provider, token_amount, eth_amount, liquidity)) = decoded {
+                sqlx::query!("INSERT INTO lp_events(chain_id,tx_hash,log_index,block_number,provider,kind,token_amount,eth_amount,liquidity)
+                     VALUES($1,$2,$3,$4,$5,$6,$7::TEXT::NUMERIC,$8::TEXT::NUMERIC,$9::TEXT::NUMERIC) ON CONFLICT DO NOTHING",
+                    chain_id,
+                    tx_hash,
+                    lg.log_index.unwrap_or_default() as i32,
+                    block,
+                    format!("0x{:x}", provider),
+                    kind,
+                    token_amount.to_string(),
+                    eth_amount.to_string(),
+                    liquidity.to_string()
+                ).execute(&db.0).await?;
+            }
+        }
+        // LP 代币为 ERC-20（3 个 topic），与门票 ERC-721 Transfer 同签名，按 topic 数区分
+        Some(t) if *t == TokenSwap::Transfer::SIGNATURE_HASH && lg.topics().len() == 3 => {
+            if let Ok(ev) = TokenSwap::Transfer::decode_log(&lg.inner, true) {
+                sqlx::query!("INSERT INTO lp_transfers(chain_id,tx_hash,log_index,block_number,from_addr,to_addr,value)
+                     VALUES($1,$2,$3,$4,$5,$6,$7::TEXT::NUMERIC) ON CONFLICT DO NOTHING",
+                    chain_id,
+                    tx_hash,
+                    lg.log_index.unwrap_or_default() as i32,
+                    block,
+                    format!("0x{:x}", ev.from),
+                    format!("0x{:x}", ev.to),
+                    ev.value.to_string()
+                ).execute(&db.0).await?;
+            }
+        }
+        Some(t) if *t == TokenSwap::FeeRatesUpdated::SIGNATURE_HASH => {
+            if let Ok(ev) = TokenSwap::FeeRatesUpdated::decode_log(&lg.inner, true) {
+                sqlx::query!("INSERT INTO swap_fee_rates(chain_id,block_number,log_index,swap_fee_rate,protocol_fee_rate)
+                     VALUES($1,$2,$3,$4::TEXT::NUMERIC,$5::TEXT::NUMERIC) ON CONFLICT DO NOTHING",
+                    chain_id,
+                    block,
+                    lg.log_index.unwrap_or_default() as i32,
+                    ev.swapFeeRate.to_string(),
+                    ev.protocolFeeRate.to_string()
+                ).execute(&db.0).await?;
+            }
+        }
+        _ => {}
+    }
+
+    // TODO: 根据 topic 匹配其余事件，解码后更新各业务表
+    Ok(())
+}
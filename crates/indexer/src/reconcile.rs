@@ -0,0 +1,432 @@
+// 链上 vs 数据库对账任务
+// 在固定区块（索引器游标高度，保证 DB 已投影到该高度）读取合约状态，与 Postgres 中的
+// tickets / marketplace_listings / ticket_types 对比，差异写入 reconciliations，可选自动修复。
+
+use alloy::eips::BlockId;
+use alloy::primitives::U256;
+use shared::contracts::bindings::{EventManager, Marketplace, TicketManager};
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use shared::domain::ticket::TicketStatus;
+use shared::AppConfig;
+use tokio::time::{interval, Duration};
+
+use crate::control::IndexerControl;
+
+#[derive(Debug)]
+struct Mismatch {
+    kind: &'static str,
+    subject: String,
+    field: &'static str,
+    db_value: String,
+    chain_value: String,
+    // 所在行已按链上值修复
+    repaired: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    pub block: i64,
+    pub tickets: usize,
+    pub listings: usize,
+    pub ticket_types: usize,
+    pub mismatches: usize,
+    // 实际修复的行数（一行可能含多个字段差异）
+    pub repaired: usize,
+}
+
+pub async fn reconcile_loop(
+    db: Db,
+    provider: SharedProvider,
+    chain_id: i64,
+    addrs: ContractAddresses,
+    cfg: AppConfig,
+    ctl: IndexerControl,
+) {
+    let mut intv = interval(Duration::from_secs(cfg.reconcile_interval_secs.max(60)));
+    loop {
+        intv.tick().await;
+        // leader 切换后旧实例不再执行，暂停时一并停止
+        if !ctl.is_active() {
+            continue;
+        }
+        match run_once(
+            &db,
+            &provider,
+            chain_id,
+            &addrs,
+            cfg.reconcile_sample_size,
+            cfg.reconcile_auto_repair,
+        )
+        .await
+        {
+            Ok(r) => tracing::info!(?r, "reconcile finished"),
+            Err(e) => tracing::error!(?e, "reconcile error"),
+        }
+    }
+}
+
+pub async fn run_once(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    sample: Option<i64>,
+    repair: bool,
+) -> anyhow::Result<ReconcileReport> {
+    // 以索引器游标作为对账高度：该高度之前的事件已全部投影
+    let block = crate::cursor::load_cursor(db, chain_id).await?;
+    let mut report = ReconcileReport {
+        block,
+        ..Default::default()
+    };
+    if block <= 0 {
+        return Ok(report);
+    }
+    let at = BlockId::number(block as u64);
+
+    let mut found = Vec::new();
+    let (tickets, repaired_tickets) =
+        check_tickets(db, provider, addrs, at, block, sample, repair, &mut found).await?;
+    let (listings, repaired_listings) =
+        check_listings(db, provider, chain_id, addrs, at, block, sample, repair, &mut found).await?;
+    let (ticket_types, repaired_types) =
+        check_ticket_types(db, provider, addrs, at, sample, repair, &mut found).await?;
+
+    report.tickets = tickets;
+    report.listings = listings;
+    report.ticket_types = ticket_types;
+    report.mismatches = found.len();
+    report.repaired = repaired_tickets + repaired_listings + repaired_types;
+    record(db, chain_id, &report, &found).await?;
+    Ok(report)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn check_tickets(
+    db: &Db,
+    provider: &SharedProvider,
+    addrs: &ContractAddresses,
+    at: BlockId,
+    block: i64,
+    sample: Option<i64>,
+    repair: bool,
+    found: &mut Vec<Mismatch>,
+) -> anyhow::Result<(usize, usize)> {
+    // LIMIT NULL 即全量扫描
+    let rows = sqlx::query!(
+        "SELECT token_id, owner_wallet, status FROM tickets WHERE minted_block IS NULL OR minted_block <= $1 ORDER BY random() LIMIT $2",
+        block,
+        sample
+    )
+    .fetch_all(&db.0)
+    .await?;
+    let tm = TicketManager::new(addrs.ticket_manager, provider.clone());
+    let mut repaired = 0;
+    for r in rows.iter() {
+        let token = U256::from(r.token_id as u64);
+        let owner = match tm.ownerOf(token).block(at).call().await {
+            Ok(v) => format!("0x{:x}", v._0),
+            Err(e) => {
+                tracing::warn!(?e, token_id = r.token_id, "ownerOf failed");
+                continue;
+            }
+        };
+        let info = match tm.getTicketInfo(token).block(at).call().await {
+            Ok(v) => v._0,
+            Err(e) => {
+                tracing::warn!(?e, token_id = r.token_id, "getTicketInfo failed");
+                continue;
+            }
+        };
+        // 未识别的链上状态只记录差异，不回写
+        let status = TicketStatus::from_chain(info.status).map(|s| s.as_str());
+        let chain_status = status
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("unmapped({})", info.status));
+
+        let before = found.len();
+        if r.owner_wallet.to_lowercase() != owner {
+            found.push(Mismatch {
+                kind: "ticket",
+                subject: r.token_id.to_string(),
+                field: "owner_wallet",
+                db_value: r.owner_wallet.clone(),
+                chain_value: owner.clone(),
+                repaired: false,
+            });
+        }
+        if status != Some(r.status.as_str()) {
+            found.push(Mismatch {
+                kind: "ticket",
+                subject: r.token_id.to_string(),
+                field: "status",
+                db_value: r.status.clone(),
+                chain_value: chain_status,
+                repaired: false,
+            });
+        }
+        if repair && found.len() > before {
+            let Some(status) = status else {
+                tracing::warn!(token_id = r.token_id, status = info.status, "unmapped chain ticket status; skip repair");
+                continue;
+            };
+            // 以对账高度为来源位置；之后区块的事件仍会覆盖
+            sqlx::query!(
+                "UPDATE tickets SET owner_wallet=$2, status=$3, owner_block=$4, owner_log_index=$5, status_block=$4, status_log_index=$5
+                 WHERE token_id=$1",
+                r.token_id,
+                owner,
+                status,
+                block,
+                i32::MAX
+            )
+            .execute(&db.0)
+            .await?;
+            mark_repaired(&mut found[before..]);
+            repaired += 1;
+        }
+    }
+    Ok((rows.len(), repaired))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn check_listings(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    at: BlockId,
+    block: i64,
+    sample: Option<i64>,
+    repair: bool,
+    found: &mut Vec<Mismatch>,
+) -> anyhow::Result<(usize, usize)> {
+    // NUMERIC 统一以文本读写，避免引入 bigdecimal
+    let rows = sqlx::query!(
+        r#"SELECT listing_id::TEXT AS "listing_id!", seller, status, price::TEXT AS price
+           FROM marketplace_listings
+           WHERE chain_id=$1 AND (created_block IS NULL OR created_block <= $2)
+           ORDER BY random() LIMIT $3"#,
+        chain_id,
+        block,
+        sample
+    )
+    .fetch_all(&db.0)
+    .await?;
+    let mk = Marketplace::new(addrs.marketplace, provider.clone());
+    let mut repaired = 0;
+    for r in rows.iter() {
+        let id: U256 = r.listing_id.parse()?;
+        let l = match mk.listings(id).block(at).call().await {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!(?e, listing_id = %r.listing_id, "listings() failed");
+                continue;
+            }
+        };
+        let seller = format!("0x{:x}", l.seller);
+        let status = l.status as i16;
+        let price = l.price.to_string();
+
+        let before = found.len();
+        let db_seller = r.seller.clone().unwrap_or_default();
+        if db_seller.to_lowercase() != seller {
+            found.push(Mismatch {
+                kind: "listing",
+                subject: r.listing_id.clone(),
+                field: "seller",
+                db_value: db_seller,
+                chain_value: seller.clone(),
+                repaired: false,
+            });
+        }
+        if r.status != Some(status) {
+            found.push(Mismatch {
+                kind: "listing",
+                subject: r.listing_id.clone(),
+                field: "status",
+                db_value: r.status.map(|s| s.to_string()).unwrap_or_default(),
+                chain_value: status.to_string(),
+                repaired: false,
+            });
+        }
+        if r.price.as_deref() != Some(price.as_str()) {
+            found.push(Mismatch {
+                kind: "listing",
+                subject: r.listing_id.clone(),
+                field: "price",
+                db_value: r.price.clone().unwrap_or_default(),
+                chain_value: price.clone(),
+                repaired: false,
+            });
+        }
+        if repair && found.len() > before {
+            sqlx::query!(
+                "UPDATE marketplace_listings SET seller=$3, status=$4, price=$5::TEXT::NUMERIC, updated_block=$6 WHERE chain_id=$1 AND listing_id=$2::TEXT::NUMERIC",
+                chain_id,
+                r.listing_id,
+                seller,
+                status,
+                price,
+                block
+            )
+            .execute(&db.0)
+            .await?;
+            mark_repaired(&mut found[before..]);
+            repaired += 1;
+        }
+    }
+    Ok((rows.len(), repaired))
+}
+
+async fn check_ticket_types(
+    db: &Db,
+    provider: &SharedProvider,
+    addrs: &ContractAddresses,
+    at: BlockId,
+    sample: Option<i64>,
+    repair: bool,
+    found: &mut Vec<Mismatch>,
+) -> anyhow::Result<(usize, usize)> {
+    // 仅对已关联链上 eventId/typeId 的票种对账
+    let rows = sqlx::query!(
+        r#"SELECT tt.id, e.chain_event_id AS "chain_event_id!", tt.chain_type_id AS "chain_type_id!",
+                  tt.supply_total, tt.supply_sold, tt.price_wei::TEXT AS "price_wei!"
+           FROM ticket_types tt JOIN events e ON e.id = tt.event_id
+           WHERE e.chain_event_id IS NOT NULL AND tt.chain_type_id IS NOT NULL
+           ORDER BY random() LIMIT $1"#,
+        sample
+    )
+    .fetch_all(&db.0)
+    .await?;
+    let em = EventManager::new(addrs.event_manager, provider.clone());
+    let mut repaired = 0;
+    for r in rows.iter() {
+        let info = match em
+            .getTicketTypeInfo(
+                U256::from(r.chain_event_id as u64),
+                U256::from(r.chain_type_id as u64),
+            )
+            .block(at)
+            .call()
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!(?e, ticket_type_id = r.id, "getTicketTypeInfo failed");
+                continue;
+            }
+        };
+        let total: i64 = info.totalSupply.try_into().unwrap_or(i64::MAX);
+        let sold: i64 = info.sold.try_into().unwrap_or(i64::MAX);
+        let price = info.price.to_string();
+        let subject = format!("{}:{}", r.chain_event_id, r.chain_type_id);
+
+        let before = found.len();
+        if r.supply_total != total {
+            found.push(Mismatch {
+                kind: "ticket_type",
+                subject: subject.clone(),
+                field: "supply_total",
+                db_value: r.supply_total.to_string(),
+                chain_value: total.to_string(),
+                repaired: false,
+            });
+        }
+        if r.supply_sold != sold {
+            found.push(Mismatch {
+                kind: "ticket_type",
+                subject: subject.clone(),
+                field: "supply_sold",
+                db_value: r.supply_sold.to_string(),
+                chain_value: sold.to_string(),
+                repaired: false,
+            });
+        }
+        if r.price_wei != price {
+            found.push(Mismatch {
+                kind: "ticket_type",
+                subject,
+                field: "price_wei",
+                db_value: r.price_wei.clone(),
+                chain_value: price.clone(),
+                repaired: false,
+            });
+        }
+        if repair && found.len() > before {
+            sqlx::query!(
+                "UPDATE ticket_types SET supply_total=$2, supply_sold=$3, price_wei=$4::TEXT::NUMERIC WHERE id=$1",
+                r.id,
+                total,
+                sold,
+                price
+            )
+            .execute(&db.0)
+            .await?;
+            mark_repaired(&mut found[before..]);
+            repaired += 1;
+        }
+    }
+    Ok((rows.len(), repaired))
+}
+
+fn mark_repaired(found: &mut [Mismatch]) {
+    for m in found {
+        m.repaired = true;
+    }
+}
+
+async fn record(
+    db: &Db,
+    chain_id: i64,
+    report: &ReconcileReport,
+    found: &[Mismatch],
+) -> anyhow::Result<()> {
+    let mut tx = db.0.begin().await?;
+    for m in found {
+        let status = if m.repaired { "repaired" } else { "mismatch" };
+        sqlx::query!(
+            "INSERT INTO reconciliations(block_number, status, chain_id, kind, subject, field, db_value, chain_value, repaired_at)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8, CASE WHEN $9 THEN NOW() END)",
+            report.block,
+            status,
+            chain_id,
+            m.kind,
+            m.subject,
+            m.field,
+            m.db_value,
+            m.chain_value,
+            m.repaired
+        )
+        .execute(&mut *tx)
+        .await?;
+        metrics::counter!("reconcile_mismatches_total", "kind" => m.kind).increment(1);
+    }
+    // 每轮写一条汇总记录，便于判断对账是否在正常运行
+    let run_status = if found.is_empty() {
+        "clean"
+    } else if found.iter().all(|m| m.repaired) {
+        "repaired"
+    } else {
+        "mismatch"
+    };
+    sqlx::query!(
+        "INSERT INTO reconciliations(block_number, status, chain_id, kind, details) VALUES($1,$2,$3,'run',$4)",
+        report.block,
+        run_status,
+        chain_id,
+        serde_json::json!({
+            "tickets": report.tickets,
+            "listings": report.listings,
+            "ticket_types": report.ticket_types,
+            "mismatches": report.mismatches,
+            "repaired": report.repaired,
+        })
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
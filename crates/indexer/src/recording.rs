@@ -0,0 +1,244 @@
+// 日志录制与回放
+// 文件格式为 JSON Lines：首行是带版本号的 header，其后每行一条 block / log / receipt 记录。
+// 录制：按区块范围抓取日志、所在区块头与交易回执（回执仅供离线排查，回放不使用）；
+// 回放：先用区块头与 chain_blocks 校验分叉，再直接走 process_logs，不访问 RPC。
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256};
+use alloy::rpc::types::eth::{Log, TransactionReceipt};
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::cursor::{ensure_cursor, load_cursor, save_cursor};
+use crate::pipeline::{log_filter, process_logs, save_block, topics};
+
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub version: u32,
+    pub chain_id: u64,
+    pub from_block: u64,
+    pub to_block: u64,
+    pub addresses: Vec<Address>,
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub number: u64,
+    pub hash: B256,
+    pub parent_hash: B256,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Record {
+    Header(RecordingHeader),
+    Block(BlockHeader),
+    Log(Box<Log>),
+    Receipt(Box<TransactionReceipt>),
+}
+
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub header: Option<RecordingHeader>,
+    pub blocks: Vec<BlockHeader>,
+    pub logs: Vec<Log>,
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+impl Recording {
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        let mut rec = Recording::default();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let r: Record = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}: invalid record", path.display(), i + 1))?;
+            match r {
+                Record::Header(h) => {
+                    if i != 0 {
+                        bail!("{}:{}: header must be the first line", path.display(), i + 1);
+                    }
+                    if h.version != FORMAT_VERSION {
+                        bail!("unsupported recording version {} (expected {FORMAT_VERSION})", h.version);
+                    }
+                    rec.header = Some(h);
+                }
+                Record::Block(b) => rec.blocks.push(b),
+                Record::Log(l) => rec.logs.push(*l),
+                Record::Receipt(r) => rec.receipts.push(*r),
+            }
+        }
+        if rec.header.is_none() {
+            bail!("{}: missing header", path.display());
+        }
+        Ok(rec)
+    }
+
+    pub fn header(&self) -> &RecordingHeader {
+        self.header.as_ref().expect("validated in read")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RecordSummary {
+    pub blocks: usize,
+    pub logs: usize,
+    pub receipts: usize,
+}
+
+pub async fn record(
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+    from: i64,
+    to: i64,
+    out: &Path,
+) -> anyhow::Result<RecordSummary> {
+    if from > to {
+        bail!("invalid range: {from} > {to}");
+    }
+    let mut w = BufWriter::new(File::create(out).with_context(|| format!("create {}", out.display()))?);
+    let header = RecordingHeader {
+        version: FORMAT_VERSION,
+        chain_id: chain_id as u64,
+        from_block: from as u64,
+        to_block: to as u64,
+        addresses: topics(addrs),
+        recorded_at: chrono::Utc::now(),
+    };
+    write_record(&mut w, &Record::Header(header))?;
+
+    let mut summary = RecordSummary::default();
+    let batch: i64 = 1_000;
+    let mut start = from;
+    while start <= to {
+        let end = (start + batch - 1).min(to);
+        let logs = provider.get_logs(&log_filter(addrs, start, end)).await?;
+        // 只保存有日志的区块头 + 区间末尾区块（回放时用于校验分叉）
+        let mut blocks: BTreeSet<u64> = logs.iter().filter_map(|l| l.block_number).collect();
+        blocks.insert(end as u64);
+        let txs: BTreeSet<B256> = logs.iter().filter_map(|l| l.transaction_hash).collect();
+
+        for n in blocks {
+            let b = provider
+                .get_block_by_number(BlockNumberOrTag::Number(n), false)
+                .await?
+                .ok_or_else(|| anyhow!("block {n} not found"))?;
+            write_record(
+                &mut w,
+                &Record::Block(BlockHeader {
+                    number: n,
+                    hash: b.header.hash,
+                    parent_hash: b.header.parent_hash,
+                    timestamp: b.header.timestamp,
+                }),
+            )?;
+            summary.blocks += 1;
+        }
+        for lg in logs.iter() {
+            write_record(&mut w, &Record::Log(Box::new(lg.clone())))?;
+        }
+        summary.logs += logs.len();
+        for tx in txs {
+            if let Some(r) = provider.get_transaction_receipt(tx).await? {
+                write_record(&mut w, &Record::Receipt(Box::new(r)))?;
+                summary.receipts += 1;
+            }
+        }
+        start = end + 1;
+    }
+    w.flush()?;
+    Ok(summary)
+}
+
+fn write_record(w: &mut impl Write, r: &Record) -> anyhow::Result<()> {
+    serde_json::to_writer(&mut *w, r)?;
+    w.write_all(b"\n")?;
+    Ok(())
+}
+
+// 录制的区块头需与已索引的 chain_blocks 一致，相邻区块的 parent_hash 需前后衔接
+async fn verify_blocks(db: &Db, chain_id: i64, blocks: &[BlockHeader]) -> anyhow::Result<()> {
+    let mut prev: Option<&BlockHeader> = None;
+    for b in blocks {
+        if let Some(p) = prev.filter(|p| p.number + 1 == b.number) {
+            if p.hash != b.parent_hash {
+                bail!("recording is not a single chain: block {} parent_hash does not match block {}", b.number, p.number);
+            }
+        }
+        prev = Some(b);
+        let numbers = [b.number as i64, b.number as i64 - 1];
+        let rows = sqlx::query!(
+            "SELECT block_number, block_hash FROM chain_blocks WHERE chain_id=$1 AND block_number = ANY($2)",
+            chain_id,
+            &numbers[..]
+        )
+        .fetch_all(&db.0)
+        .await?;
+        for row in rows {
+            let expected = if row.block_number == b.number as i64 { b.hash } else { b.parent_hash };
+            if row.block_hash != format!("0x{:x}", expected) {
+                bail!(
+                    "fork detected at block {}: indexed hash {} differs from recording {:#x}",
+                    row.block_number,
+                    row.block_hash,
+                    expected
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// 回放：按文件内日志写入 chain_logs，游标只前进不后退；
+// 录制起点与游标之间有缺口时不移动游标，避免跳过未索引的区块
+pub async fn replay(db: &Db, path: &Path) -> anyhow::Result<usize> {
+    let rec = Recording::read(path)?;
+    let h = rec.header();
+    let chain_id = h.chain_id as i64;
+    tracing::info!(
+        chain_id,
+        from = h.from_block,
+        to = h.to_block,
+        logs = rec.logs.len(),
+        "replaying recording"
+    );
+    ensure_cursor(db, chain_id).await?;
+    verify_blocks(db, chain_id, &rec.blocks).await?;
+    for b in rec.blocks.iter() {
+        save_block(db, chain_id, b.number, b.hash, b.parent_hash, b.timestamp).await?;
+    }
+    let failed = process_logs(db, chain_id, &rec.logs).await;
+    if failed > 0 {
+        bail!("{failed} of {} logs failed to project; cursor not advanced", rec.logs.len());
+    }
+    let to = h.to_block as i64;
+    let cursor = load_cursor(db, chain_id).await?;
+    if cursor < to {
+        if h.from_block as i64 <= cursor + 1 {
+            save_cursor(db, chain_id, to).await?;
+        } else {
+            tracing::warn!(
+                cursor,
+                from = h.from_block,
+                to,
+                "recording starts after the cursor; blocks in between are not indexed, cursor left unchanged"
+            );
+        }
+    }
+    Ok(rec.logs.len())
+}
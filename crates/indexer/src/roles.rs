@@ -0,0 +1,107 @@
+// 链上角色全量同步：按已确认高度（链头 - CONFIRM_DEPTH）读取 authorizedOrganizers / authorizedVerifiers / authorizedMinters，
+// 修正事件投影遗漏（例如索引起点之前的授权），并重算 users.role。
+// 核对范围为有链上授权记录的钱包与近期登录过的用户，按地址分页；更早的用户下次登录后纳入。
+
+use alloy::eips::BlockId;
+use alloy::primitives::Address;
+use shared::contracts::bindings::{EventManager, TicketManager};
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use shared::domain::user::Role;
+use shared::repo::user_repo::UserRepo;
+use shared::AppConfig;
+use tokio::time::{interval, Duration};
+
+use crate::control::IndexerControl;
+use crate::pipeline::CONFIRM_DEPTH;
+
+// 每页核对的钱包数
+const PAGE_SIZE: i64 = 500;
+// 近期登录窗口
+const LOGIN_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug, Default)]
+pub struct RoleSyncReport {
+    pub block: i64,
+    pub wallets: usize,
+    pub changed: usize,
+}
+
+pub async fn role_sync_loop(
+    db: Db,
+    provider: SharedProvider,
+    chain_id: i64,
+    addrs: ContractAddresses,
+    cfg: AppConfig,
+    ctl: IndexerControl,
+) {
+    let mut intv = interval(Duration::from_secs(cfg.role_sync_interval_secs.max(30)));
+    loop {
+        intv.tick().await;
+        // leader 切换后旧实例不再执行，暂停时一并停止
+        if !ctl.is_active() {
+            continue;
+        }
+        match sync_once(&db, &provider, chain_id, &addrs).await {
+            Ok(r) => tracing::info!(?r, "role sync finished"),
+            Err(e) => tracing::error!(?e, "role sync error"),
+        }
+    }
+}
+
+pub async fn sync_once(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+) -> anyhow::Result<RoleSyncReport> {
+    let block = provider.get_block_number().await? as i64 - CONFIRM_DEPTH;
+    if block <= 0 {
+        return Ok(RoleSyncReport::default());
+    }
+    let at = BlockId::number(block as u64);
+    let since = chrono::Utc::now() - chrono::Duration::days(LOGIN_WINDOW_DAYS);
+    let em = EventManager::new(addrs.event_manager, provider.clone());
+    let tm = TicketManager::new(addrs.ticket_manager, provider.clone());
+    let users = UserRepo::new(db);
+
+    let mut report = RoleSyncReport {
+        block,
+        ..Default::default()
+    };
+    let mut after = String::new();
+    loop {
+        let wallets = users.role_sync_candidates(chain_id, since, &after, PAGE_SIZE).await?;
+        let Some(last) = wallets.last() else {
+            break;
+        };
+        after = last.clone();
+        report.wallets += wallets.len();
+        for wallet in wallets.iter() {
+            let Ok(who) = wallet.parse::<Address>() else {
+                continue;
+            };
+            let organizer = em.authorizedOrganizers(who).block(at).call().await?._0;
+            let verifier = tm.authorizedVerifiers(who).block(at).call().await?._0;
+            let minter = tm.authorizedMinters(who).block(at).call().await?._0;
+            // 以该区块快照为准；之后区块的事件 (block, log_index) 更大，仍会覆盖
+            for (role, authorized) in [
+                (Role::Organizer, organizer),
+                (Role::Verifier, verifier),
+                (Role::Minter, minter),
+            ] {
+                users
+                    .apply_chain_role(chain_id, wallet, role, authorized, block, i32::MAX)
+                    .await?;
+            }
+            let before = users.find(wallet).await?.map(|u| u.role);
+            let synced = users.sync_role(wallet).await?;
+            if before.as_deref() != Some(synced.as_str()) {
+                tracing::info!(%wallet, ?before, after = %synced, "role updated from chain");
+                report.changed += 1;
+            }
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,93 @@
+// 预售白名单确认：addToPresaleWhitelist 不发事件，按已确认高度（链头 - CONFIRM_DEPTH）读取 isInPresaleWhitelist，
+// 把 API 导入时记为 pending 的条目置为 confirmed；未上链的条目保持 pending，下轮再查，
+// 导入超过 PENDING_TTL_HOURS 仍未上链的置为 expired，不再轮询。
+
+use alloy::eips::BlockId;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use shared::contracts::bindings::EventManager;
+use shared::contracts::provider::SharedProvider;
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use shared::repo::whitelist_repo::WhitelistRepo;
+use shared::AppConfig;
+use tokio::time::{interval, Duration};
+
+use crate::control::IndexerControl;
+use crate::pipeline::CONFIRM_DEPTH;
+
+// 单轮最多检查的条目数，避免大批导入时长时间占用 RPC
+const BATCH: i64 = 500;
+// pending 条目的有效期
+const PENDING_TTL_HOURS: i64 = 72;
+
+#[derive(Debug, Default)]
+pub struct WhitelistSyncReport {
+    pub block: i64,
+    pub checked: usize,
+    pub confirmed: usize,
+    pub expired: u64,
+}
+
+pub async fn whitelist_sync_loop(
+    db: Db,
+    provider: SharedProvider,
+    chain_id: i64,
+    addrs: ContractAddresses,
+    cfg: AppConfig,
+    ctl: IndexerControl,
+) {
+    let mut intv = interval(Duration::from_secs(cfg.whitelist_sync_interval_secs.max(15)));
+    loop {
+        intv.tick().await;
+        // leader 切换后旧实例不再执行，暂停时一并停止
+        if !ctl.is_active() {
+            continue;
+        }
+        match sync_once(&db, &provider, chain_id, &addrs).await {
+            Ok(r) if r.checked > 0 || r.expired > 0 => tracing::info!(?r, "whitelist sync finished"),
+            Ok(_) => {}
+            Err(e) => tracing::error!(?e, "whitelist sync error"),
+        }
+    }
+}
+
+pub async fn sync_once(
+    db: &Db,
+    provider: &SharedProvider,
+    chain_id: i64,
+    addrs: &ContractAddresses,
+) -> anyhow::Result<WhitelistSyncReport> {
+    let repo = WhitelistRepo::new(db);
+    let mut report = WhitelistSyncReport::default();
+    let before = chrono::Utc::now() - chrono::Duration::hours(PENDING_TTL_HOURS);
+    report.expired = repo.expire_pending(chain_id, before).await?;
+    let pending = repo.pending(chain_id, BATCH).await?;
+    if pending.is_empty() {
+        return Ok(report);
+    }
+    report.block = provider.get_block_number().await? as i64 - CONFIRM_DEPTH;
+    if report.block <= 0 {
+        return Ok(report);
+    }
+    let at = BlockId::number(report.block as u64);
+    let em = EventManager::new(addrs.event_manager, provider.clone());
+    for p in pending.iter() {
+        let Ok(who) = p.wallet.parse::<Address>() else {
+            continue;
+        };
+        let listed = em
+            .isInPresaleWhitelist(U256::from(p.chain_event_id), U256::from(p.chain_type_id), who)
+            .block(at)
+            .call()
+            .await?
+            ._0;
+        repo.mark_checked(chain_id, p.type_id, &p.wallet, listed.then_some(report.block))
+            .await?;
+        report.checked += 1;
+        if listed {
+            report.confirmed += 1;
+        }
+    }
+    Ok(report)
+}
@@ -0,0 +1,122 @@
+mod support;
+
+use indexer::admin::{self, AdminState};
+use indexer::control::IndexerControl;
+use indexer::pipeline::backfill;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use support::Harness;
+
+async fn serve(h: &Harness, ctl: &IndexerControl) -> String {
+    let state = Arc::new(AdminState {
+        db: h.db.clone(),
+        provider: h.provider.clone(),
+        chain_id: h.chain_id,
+        ctl: ctl.clone(),
+        token: "secret".into(),
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, admin::router(state)).await });
+    url
+}
+
+#[tokio::test]
+async fn admin_requires_token_and_controls_cursor() {
+    let Some(h) = Harness::start("basic").await else { return };
+    backfill(&h.db, &h.provider, h.chain_id, &h.addrs, &h.ctl).await.unwrap();
+    let ctl = IndexerControl::new();
+    let url = serve(&h, &ctl).await;
+    let http = reqwest::Client::new();
+
+    let res = http.get(format!("{url}/admin/status")).send().await.unwrap();
+    assert_eq!(res.status(), 401);
+    let res = http
+        .get(format!("{url}/admin/status"))
+        .bearer_auth("wrong")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 401);
+
+    let body: Value = http
+        .get(format!("{url}/admin/status"))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["cursor"], 14);
+    assert_eq!(body["head"], 20);
+    assert_eq!(body["lag"], 6);
+    assert_eq!(body["paused"], false);
+
+    let body: Value = http
+        .post(format!("{url}/admin/pause"))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["paused"], true);
+    assert!(ctl.is_paused());
+
+    // 只能回退，不能前进
+    let res = http
+        .post(format!("{url}/admin/rewind"))
+        .bearer_auth("secret")
+        .json(&json!({ "block": 15 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 400);
+    let res = http
+        .post(format!("{url}/admin/rewind"))
+        .bearer_auth("secret")
+        .json(&json!({ "block": 5 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    assert_eq!(h.cursor().await, 5);
+
+    // 未暂停时拒绝回退，避免与进行中的批次互相覆盖游标
+    ctl.set_paused(false);
+    let res = http
+        .post(format!("{url}/admin/rewind"))
+        .bearer_auth("secret")
+        .json(&json!({ "block": 3 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 409);
+    assert_eq!(h.cursor().await, 5);
+
+    h.teardown().await;
+}
+
+#[tokio::test]
+async fn admin_reindex_is_bounded_and_queued() {
+    let Some(h) = Harness::start("basic").await else { return };
+    let ctl = IndexerControl::new();
+    let url = serve(&h, &ctl).await;
+    let http = reqwest::Client::new();
+    let post = |body: Value| {
+        http.post(format!("{url}/admin/reindex"))
+            .bearer_auth("secret")
+            .json(&body)
+            .send()
+    };
+
+    assert_eq!(post(json!({ "from": 5, "to": 1 })).await.unwrap().status(), 400);
+    assert_eq!(post(json!({ "from": 1, "to": 20 })).await.unwrap().status(), 400);
+    assert_eq!(post(json!({ "from": 1, "to": 20_000 })).await.unwrap().status(), 400);
+    assert_eq!(post(json!({ "from": 1, "to": 14 })).await.unwrap().status(), 202);
+    assert_eq!(ctl.pending_reindex(), vec![(1, 14)]);
+
+    h.teardown().await;
+}
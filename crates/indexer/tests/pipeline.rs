@@ -0,0 +1,158 @@
+mod support;
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::rpc::types::eth::Filter;
+use indexer::pipeline::{backfill, fetch_and_process, incremental_step, CONFIRM_DEPTH};
+use support::fake_rpc::{FakeRpc, Fixture};
+use support::Harness;
+
+#[tokio::test]
+async fn fake_rpc_serves_fixture() {
+    let rpc = FakeRpc::start(Fixture::load("basic")).await;
+    let provider = support::provider(&rpc).await;
+
+    assert_eq!(provider.get_chain_id().await.unwrap(), 31337);
+    assert_eq!(provider.get_block_number().await.unwrap(), 20);
+
+    let all = Filter::new().from_block(0).to_block(20);
+    assert_eq!(provider.get_logs(&all).await.unwrap().len(), 5);
+    let ours = all.address(vec![support::TICKET_MANAGER, support::MARKETPLACE]);
+    assert_eq!(provider.get_logs(&ours).await.unwrap().len(), 3);
+    assert_eq!(rpc.calls("eth_getLogs"), 2);
+}
+
+#[tokio::test]
+async fn fake_rpc_reorg_changes_block_hashes() {
+    let rpc = FakeRpc::start(Fixture::load("basic")).await;
+    let provider = support::provider(&rpc).await;
+    let block = |n: u64| {
+        let provider = provider.clone();
+        async move {
+            provider
+                .get_block_by_number(BlockNumberOrTag::Number(n), false)
+                .await
+                .unwrap()
+                .unwrap()
+                .header
+                .hash
+        }
+    };
+
+    let before_15 = block(15).await;
+    let before_16 = block(16).await;
+    rpc.reorg(16, vec![]);
+    assert_eq!(block(15).await, before_15);
+    assert_ne!(block(16).await, before_16);
+
+    let logs = provider
+        .get_logs(&Filter::new().from_block(16).to_block(20))
+        .await
+        .unwrap();
+    assert!(logs.is_empty());
+}
+
+#[tokio::test]
+async fn backfill_persists_finalized_logs_and_advances_cursor() {
+    let Some(h) = Harness::start("basic").await else { return };
+
+    backfill(&h.db, &h.provider, h.chain_id, &h.addrs, &h.ctl).await.unwrap();
+
+    // head=20，确认深度 6：只处理到 14，区块 16 的 Swap 尚未确认；无关合约的日志被过滤
+    assert_eq!(h.cursor().await, 20 - CONFIRM_DEPTH);
+    assert_eq!(h.count("chain_logs").await, 3);
+    // 区块 3 的 ERC-721 Transfer 被投影；有日志的区块 3、10 记录了区块头
+    assert_eq!(h.count("ticket_transfers").await, 1);
+    // 同一条 Transfer 同时投影出当前持有人
+    assert_eq!(h.count("ticket_tokens").await, 1);
+    // 区块 10 的 ListingCreated 投影为挂单
+    assert_eq!(h.count("marketplace_listings").await, 1);
+    assert_eq!(h.count("chain_blocks").await, 2);
+    h.teardown().await;
+}
+
+#[tokio::test]
+async fn backfill_honours_pause() {
+    let Some(h) = Harness::start("basic").await else { return };
+    h.ctl.set_paused(true);
+    backfill(&h.db, &h.provider, h.chain_id, &h.addrs, &h.ctl).await.unwrap();
+    assert_eq!(h.cursor().await, 0);
+    assert_eq!(h.count("chain_logs").await, 0);
+    h.teardown().await;
+}
+
+#[tokio::test]
+async fn incremental_step_follows_head() {
+    let Some(h) = Harness::start("basic").await else { return };
+    backfill(&h.db, &h.provider, h.chain_id, &h.addrs, &h.ctl).await.unwrap();
+
+    // 链头不变时不做任何事
+    incremental_step(&h.db, &h.provider, h.chain_id, &h.addrs).await.unwrap();
+    assert_eq!(h.cursor().await, 14);
+
+    h.rpc.set_head(30);
+    incremental_step(&h.db, &h.provider, h.chain_id, &h.addrs).await.unwrap();
+    assert_eq!(h.cursor().await, 30 - CONFIRM_DEPTH);
+    assert_eq!(h.count("chain_logs").await, 4);
+    h.teardown().await;
+}
+
+#[tokio::test]
+async fn reprocessing_range_is_idempotent() {
+    let Some(h) = Harness::start("basic").await else { return };
+
+    fetch_and_process(&h.db, &h.provider, h.chain_id, &h.addrs, 1, 14).await.unwrap();
+    fetch_and_process(&h.db, &h.provider, h.chain_id, &h.addrs, 1, 14).await.unwrap();
+    assert_eq!(h.count("chain_logs").await, 3);
+    assert_eq!(h.count("ticket_transfers").await, 1);
+    h.teardown().await;
+}
+
+#[tokio::test]
+async fn mint_projects_ticket_row() {
+    let Some(h) = Harness::start("basic").await else { return };
+    // 区块 3 的 TicketMinted：token 1、链上活动 1、座位 7；活动只有一个已上链票种
+    sqlx::query("INSERT INTO users(wallet, nonce) VALUES('0x00000000000000000000000000000000000000c1', 'n')")
+        .execute(&h.db.0)
+        .await
+        .unwrap();
+    let event_id: i64 = sqlx::query_scalar(
+        "INSERT INTO events(organizer_wallet, chain_event_id, start_time, end_time, status)
+         VALUES('0x00000000000000000000000000000000000000c1', 1, NOW(), NOW() + INTERVAL '1 day', 'published') RETURNING id",
+    )
+    .fetch_one(&h.db.0)
+    .await
+    .unwrap();
+    let type_id: i64 = sqlx::query_scalar(
+        "INSERT INTO ticket_types(event_id, price_wei, supply_total, chain_type_id) VALUES($1, 1, 10, 0) RETURNING id",
+    )
+    .bind(event_id)
+    .fetch_one(&h.db.0)
+    .await
+    .unwrap();
+
+    fetch_and_process(&h.db, &h.provider, h.chain_id, &h.addrs, 1, 14).await.unwrap();
+    let row: (i64, i64, String, Option<String>, String) =
+        sqlx::query_as("SELECT event_id, type_id, owner_wallet, seat_label, status FROM tickets WHERE token_id = 1")
+            .fetch_one(&h.db.0)
+            .await
+            .unwrap();
+    assert_eq!(
+        row,
+        (event_id, type_id, "0x00000000000000000000000000000000000000b1".into(), Some("7".into()), "valid".into())
+    );
+    h.teardown().await;
+}
+
+#[tokio::test]
+async fn unconfirmed_reorg_is_never_persisted() {
+    let Some(h) = Harness::start("basic").await else { return };
+    backfill(&h.db, &h.provider, h.chain_id, &h.addrs, &h.ctl).await.unwrap();
+
+    // 区块 16 在确认深度内被重组掉，之后链头推进：旧分叉的 Swap 不应入库
+    h.rpc.reorg(16, vec![]);
+    h.rpc.set_head(30);
+    incremental_step(&h.db, &h.provider, h.chain_id, &h.addrs).await.unwrap();
+    assert_eq!(h.cursor().await, 24);
+    assert_eq!(h.count("chain_logs").await, 3);
+    h.teardown().await;
+}
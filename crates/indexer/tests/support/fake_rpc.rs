@@ -0,0 +1,229 @@
+// 进程内 JSON-RPC 替身：根据 fixture 回放 eth_chainId / eth_blockNumber /
+// eth_getBlockByNumber / eth_getLogs / eth_getTransactionReceipt，
+// 区块哈希按 (fork, number) 确定性生成，可模拟重组。
+
+use alloy::primitives::{keccak256, Address, B256};
+use axum::{extract::State, routing::post, Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    pub chain_id: u64,
+    pub head: u64,
+    // RPC 格式的日志（blockHash 由替身按当前分叉填充）
+    #[serde(default)]
+    pub logs: Vec<Value>,
+}
+
+impl Fixture {
+    pub fn load(name: &str) -> Self {
+        let path = format!("{}/tests/fixtures/{name}.json", env!("CARGO_MANIFEST_DIR"));
+        let raw = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {path}: {e}"));
+        serde_json::from_str(&raw).unwrap_or_else(|e| panic!("parse {path}: {e}"))
+    }
+}
+
+struct Chain {
+    chain_id: u64,
+    head: u64,
+    // 每个区块所在分叉编号；重组时从某高度起递增
+    forks: HashMap<u64, u64>,
+    logs: Vec<Value>,
+    calls: HashMap<String, usize>,
+}
+
+impl Chain {
+    fn block_hash(&self, n: u64) -> B256 {
+        let fork = self.forks.get(&n).copied().unwrap_or(0);
+        keccak256(format!("{}:{fork}:{n}", self.chain_id))
+    }
+
+    fn block(&self, n: u64) -> Value {
+        let parent = if n == 0 { B256::ZERO } else { self.block_hash(n - 1) };
+        json!({
+            "hash": self.block_hash(n),
+            "parentHash": parent,
+            "sha3Uncles": B256::ZERO,
+            "miner": Address::ZERO,
+            "stateRoot": B256::ZERO,
+            "transactionsRoot": B256::ZERO,
+            "receiptsRoot": B256::ZERO,
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "difficulty": "0x0",
+            "number": format!("0x{n:x}"),
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x0",
+            "timestamp": format!("0x{:x}", 1_700_000_000 + n * 12),
+            "extraData": "0x",
+            "mixHash": B256::ZERO,
+            "nonce": "0x0000000000000000",
+            "baseFeePerGas": "0x7",
+            "size": "0x220",
+            "uncles": [],
+            "transactions": [],
+        })
+    }
+
+    fn resolve_tag(&self, v: &Value) -> Option<u64> {
+        match v.as_str()? {
+            "latest" | "pending" | "safe" | "finalized" => Some(self.head),
+            "earliest" => Some(0),
+            hex => parse_hex(hex),
+        }
+    }
+
+    fn get_logs(&self, filter: &Value) -> Value {
+        let from = filter.get("fromBlock").and_then(|v| self.resolve_tag(v)).unwrap_or(0);
+        let to = filter
+            .get("toBlock")
+            .and_then(|v| self.resolve_tag(v))
+            .unwrap_or(self.head)
+            .min(self.head);
+        let addresses: Vec<String> = match filter.get("address") {
+            Some(Value::String(a)) => vec![a.to_lowercase()],
+            Some(Value::Array(a)) => a
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_lowercase))
+                .collect(),
+            _ => vec![],
+        };
+        let out: Vec<Value> = self
+            .logs
+            .iter()
+            .filter(|lg| {
+                let n = lg["blockNumber"].as_str().and_then(parse_hex).unwrap_or(u64::MAX);
+                let addr = lg["address"].as_str().unwrap_or_default().to_lowercase();
+                n >= from && n <= to && (addresses.is_empty() || addresses.contains(&addr))
+            })
+            .map(|lg| {
+                let mut lg = lg.clone();
+                let n = lg["blockNumber"].as_str().and_then(parse_hex).unwrap_or_default();
+                lg["blockHash"] = json!(self.block_hash(n));
+                lg
+            })
+            .collect();
+        Value::Array(out)
+    }
+
+    // 由该交易的日志合成回执
+    fn receipt(&self, tx: &str) -> Value {
+        let logs: Vec<Value> = self
+            .logs
+            .iter()
+            .filter(|lg| lg["transactionHash"].as_str() == Some(tx))
+            .cloned()
+            .collect();
+        let Some(first) = logs.first() else {
+            return Value::Null;
+        };
+        let n = first["blockNumber"].as_str().and_then(parse_hex).unwrap_or_default();
+        json!({
+            "transactionHash": tx,
+            "transactionIndex": first["transactionIndex"],
+            "blockHash": self.block_hash(n),
+            "blockNumber": format!("0x{n:x}"),
+            "from": Address::ZERO,
+            "to": first["address"],
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x7",
+            "contractAddress": null,
+            "logs": logs,
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "type": "0x2",
+            "status": "0x1",
+        })
+    }
+}
+
+fn parse_hex(s: &str) -> Option<u64> {
+    u64::from_str_radix(s.trim_start_matches("0x"), 16).ok()
+}
+
+#[derive(Clone)]
+pub struct FakeRpc {
+    chain: Arc<Mutex<Chain>>,
+    pub url: String,
+}
+
+impl FakeRpc {
+    pub async fn start(fixture: Fixture) -> Self {
+        let chain = Arc::new(Mutex::new(Chain {
+            chain_id: fixture.chain_id,
+            head: fixture.head,
+            forks: HashMap::new(),
+            logs: fixture.logs,
+            calls: HashMap::new(),
+        }));
+        let app = Router::new()
+            .route("/", post(handle))
+            .with_state(chain.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind fake rpc");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("fake rpc serve");
+        });
+        Self { chain, url }
+    }
+
+    pub fn set_head(&self, head: u64) {
+        self.chain.lock().unwrap().head = head;
+    }
+
+    // 从 `from` 高度起切换到新分叉：区块哈希全部改变，原日志被 `logs` 替换
+    pub fn reorg(&self, from: u64, logs: Vec<Value>) {
+        let mut c = self.chain.lock().unwrap();
+        let head = c.head;
+        for n in from..=head {
+            *c.forks.entry(n).or_insert(0) += 1;
+        }
+        c.logs.retain(|lg| {
+            lg["blockNumber"].as_str().and_then(parse_hex).unwrap_or_default() < from
+        });
+        c.logs.extend(logs);
+    }
+
+    pub fn calls(&self, method: &str) -> usize {
+        self.chain.lock().unwrap().calls.get(method).copied().unwrap_or(0)
+    }
+}
+
+async fn handle(State(chain): State<Arc<Mutex<Chain>>>, Json(req): Json<Value>) -> Json<Value> {
+    match req {
+        Value::Array(batch) => Json(Value::Array(
+            batch.iter().map(|r| dispatch(&chain, r)).collect(),
+        )),
+        single => Json(dispatch(&chain, &single)),
+    }
+}
+
+fn dispatch(chain: &Mutex<Chain>, req: &Value) -> Value {
+    let id = req.get("id").cloned().unwrap_or(Value::Null);
+    let method = req["method"].as_str().unwrap_or_default();
+    let params = req.get("params").cloned().unwrap_or(json!([]));
+    let mut c = chain.lock().unwrap();
+    *c.calls.entry(method.to_string()).or_insert(0) += 1;
+    let result = match method {
+        "eth_chainId" => json!(format!("0x{:x}", c.chain_id)),
+        "eth_blockNumber" => json!(format!("0x{:x}", c.head)),
+        "eth_getBlockByNumber" => match c.resolve_tag(&params[0]) {
+            Some(n) if n <= c.head => c.block(n),
+            _ => Value::Null,
+        },
+        "eth_getLogs" => c.get_logs(&params[0]),
+        "eth_getTransactionReceipt" => c.receipt(params[0].as_str().unwrap_or_default()),
+        other => {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": format!("method not found: {other}") },
+            })
+        }
+    };
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
@@ -0,0 +1,134 @@
+// 索引器集成测试夹具：假 RPC + 一次性 Postgres schema
+// 需要设置 OT_TEST_DATABASE_URL；未设置时依赖数据库的用例直接跳过。
+
+#![allow(dead_code)]
+
+pub mod fake_rpc;
+
+use alloy::primitives::{address, Address};
+use fake_rpc::{FakeRpc, Fixture};
+use indexer::control::IndexerControl;
+use shared::contracts::provider::{build_provider, SharedProvider};
+use shared::contracts::registry::ContractAddresses;
+use shared::db::pool::Db;
+use shared::AppConfig;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Executor, PgPool};
+
+pub const TICKET_MANAGER: Address = address!("00000000000000000000000000000000000000a1");
+pub const EVENT_MANAGER: Address = address!("00000000000000000000000000000000000000a2");
+pub const MARKETPLACE: Address = address!("00000000000000000000000000000000000000a3");
+pub const TOKEN_SWAP: Address = address!("00000000000000000000000000000000000000a4");
+
+pub fn addresses() -> ContractAddresses {
+    ContractAddresses {
+        ticket_manager: TICKET_MANAGER,
+        event_manager: EVENT_MANAGER,
+        marketplace: MARKETPLACE,
+        token_swap: TOKEN_SWAP,
+    }
+}
+
+// 只填必填项，其余走 serde 默认值，新增配置项无需改动这里
+pub fn test_config(rpc_url: &str, database_url: &str) -> AppConfig {
+    serde_json::from_value(serde_json::json!({
+        "rpc_http_url": rpc_url,
+        "rpc_ws_url": rpc_url.replace("http", "ws"),
+        "database_url": database_url,
+        "redis_url": "redis://127.0.0.1:6379",
+        "jwt_secret": "test",
+        "qr_hmac_secret": "test",
+    }))
+    .expect("test config")
+}
+
+pub async fn provider(rpc: &FakeRpc) -> SharedProvider {
+    build_provider(&test_config(&rpc.url, ""))
+        .await
+        .expect("provider")
+}
+
+pub struct Harness {
+    pub db: Db,
+    pub rpc: FakeRpc,
+    pub provider: SharedProvider,
+    pub addrs: ContractAddresses,
+    pub chain_id: i64,
+    // 已是 leader、未暂停的控制状态，供回填使用
+    pub ctl: IndexerControl,
+    admin: PgPool,
+    schema: String,
+}
+
+impl Harness {
+    pub async fn start(fixture: &str) -> Option<Self> {
+        let Ok(url) = std::env::var("OT_TEST_DATABASE_URL") else {
+            eprintln!("OT_TEST_DATABASE_URL not set; skipping");
+            return None;
+        };
+        let fixture = Fixture::load(fixture);
+        let chain_id = fixture.chain_id as i64;
+        let rpc = FakeRpc::start(fixture).await;
+
+        // 每个用例独立 schema，结束后整体删除
+        let schema = format!("it_{}", uuid::Uuid::new_v4().simple());
+        let admin = PgPool::connect(&url).await.expect("admin connect");
+        admin
+            .execute(format!("CREATE SCHEMA {schema}").as_str())
+            .await
+            .expect("create schema");
+        let search_path = format!("SET search_path TO {schema}");
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .after_connect(move |conn, _| {
+                let sql = search_path.clone();
+                Box::pin(async move {
+                    conn.execute(sql.as_str()).await?;
+                    Ok(())
+                })
+            })
+            .connect(&url)
+            .await
+            .expect("schema connect");
+        let db = Db(pool);
+        db.migrate().await.expect("migrate");
+        indexer::cursor::ensure_cursor(&db, chain_id)
+            .await
+            .expect("ensure cursor");
+
+        let provider = provider(&rpc).await;
+        let ctl = IndexerControl::new();
+        ctl.set_leader(true);
+        Some(Self {
+            db,
+            rpc,
+            provider,
+            addrs: addresses(),
+            chain_id,
+            ctl,
+            admin,
+            schema,
+        })
+    }
+
+    pub async fn cursor(&self) -> i64 {
+        indexer::cursor::load_cursor(&self.db, self.chain_id)
+            .await
+            .expect("load cursor")
+    }
+
+    pub async fn count(&self, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(&self.db.0)
+            .await
+            .expect("count")
+    }
+
+    pub async fn teardown(self) {
+        self.db.0.close().await;
+        let _ = self
+            .admin
+            .execute(format!("DROP SCHEMA {} CASCADE", self.schema).as_str())
+            .await;
+    }
+}
@@ -0,0 +1,18 @@
+-- 0006_reconciliations
+-- 链上 vs 数据库对账：为 reconciliations 增加差异明细字段
+
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS chain_id BIGINT;
+-- kind: run | ticket | listing | ticket_type
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS kind TEXT NOT NULL DEFAULT 'run';
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS subject TEXT;
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS field TEXT;
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS db_value TEXT;
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS chain_value TEXT;
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS details JSONB;
+ALTER TABLE reconciliations ADD COLUMN IF NOT EXISTS repaired_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS idx_reconciliations_chain_kind ON reconciliations(chain_id, kind, created_at);
+
+-- 票种与链上 typeId 的映射（getTicketTypeInfo(eventId, typeId) 需要）
+ALTER TABLE ticket_types ADD COLUMN IF NOT EXISTS chain_type_id BIGINT;
+CREATE UNIQUE INDEX IF NOT EXISTS uniq_ticket_types_event_chain_type
+  ON ticket_types(event_id, chain_type_id) WHERE chain_type_id IS NOT NULL;
@@ -0,0 +1,33 @@
+-- 0007_export
+-- 区块头（按天分区导出、后续重组校验使用）
+CREATE TABLE IF NOT EXISTS chain_blocks (
+    chain_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    block_hash TEXT NOT NULL,
+    parent_hash TEXT NOT NULL,
+    block_time TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY(chain_id, block_number)
+);
+CREATE INDEX IF NOT EXISTS idx_chain_blocks_time ON chain_blocks(chain_id, block_time);
+
+-- 门票 NFT 转移历史（ERC-721 Transfer）
+CREATE TABLE IF NOT EXISTS ticket_transfers (
+    chain_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INT NOT NULL,
+    token_id NUMERIC(78,0) NOT NULL,
+    from_addr TEXT NOT NULL,
+    to_addr TEXT NOT NULL,
+    PRIMARY KEY(chain_id, tx_hash, log_index)
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_transfers_token ON ticket_transfers(chain_id, token_id);
+
+-- 分析导出进度（每个数据集独立游标）
+CREATE TABLE IF NOT EXISTS export_cursors (
+    chain_id BIGINT NOT NULL,
+    dataset TEXT NOT NULL,
+    last_block BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(chain_id, dataset)
+);
@@ -0,0 +1,7 @@
+-- 0008_auth: SIWE 登录挑战 nonce（按钱包覆盖，验证时删除）
+CREATE TABLE IF NOT EXISTS siwe_nonces (
+    wallet TEXT PRIMARY KEY,
+    nonce TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
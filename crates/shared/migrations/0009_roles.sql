@@ -0,0 +1,14 @@
+-- 0009_roles
+-- 链上授权镜像：EventManager.authorizedOrganizers / TicketManager.authorizedVerifiers / authorizedMinters
+-- (block_number, log_index) 记录最后一次生效的来源位置，全量同步写入时 log_index 取 INT 最大值
+CREATE TABLE IF NOT EXISTS chain_roles (
+    chain_id BIGINT NOT NULL,
+    wallet TEXT NOT NULL,
+    role TEXT NOT NULL,
+    authorized BOOLEAN NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index INT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(chain_id, wallet, role)
+);
+CREATE INDEX IF NOT EXISTS idx_chain_roles_wallet ON chain_roles(wallet) WHERE authorized;
@@ -0,0 +1,4 @@
+-- 0010_ticket_views
+-- 门票详情 / 钱包持仓查询所需索引
+CREATE INDEX IF NOT EXISTS idx_ticket_tokens_owner ON ticket_tokens(chain_id, owner);
+CREATE INDEX IF NOT EXISTS idx_marketplace_listings_ticket ON marketplace_listings(chain_id, ticket_id);
@@ -0,0 +1,4 @@
+-- 0011_ticket_metadata
+-- tokenURI 元数据缓存版本：索引器见到 MetadataUpdate / BatchMetadataUpdate 时递增，API 据此更换 ETag
+ALTER TABLE ticket_tokens ADD COLUMN IF NOT EXISTS metadata_version BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ticket_tokens ADD COLUMN IF NOT EXISTS metadata_updated_at TIMESTAMPTZ;
@@ -0,0 +1,55 @@
+-- 0012_market
+-- 二级市场挂单/成交投影补充字段与统一查询视图
+
+ALTER TABLE marketplace_listings ADD COLUMN IF NOT EXISTS eth_price NUMERIC(78,0);
+ALTER TABLE marketplace_listings ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS idx_marketplace_listings_status ON marketplace_listings(chain_id, status, price);
+
+ALTER TABLE marketplace_trades ADD COLUMN IF NOT EXISTS seller TEXT;
+ALTER TABLE marketplace_trades ADD COLUMN IF NOT EXISTS token_id NUMERIC(78,0);
+ALTER TABLE marketplace_trades ADD COLUMN IF NOT EXISTS paid_with_eth BOOLEAN;
+CREATE INDEX IF NOT EXISTS idx_marketplace_trades_block ON marketplace_trades(chain_id, block_number DESC);
+
+-- 链上（marketplace_listings）与链下（market_listings）挂单合并视图
+-- 链上 id 直接使用 listingId，链下 id 加 "off-" 前缀避免冲突；status 统一为文本，
+-- 链上 Active 但已过期的挂单视为 expired
+CREATE OR REPLACE VIEW v_market_listings AS
+SELECT ml.listing_id::TEXT AS id,
+       'chain'::TEXT AS source,
+       ml.chain_id,
+       ml.ticket_id::BIGINT AS token_id,
+       t.event_id,
+       t.type_id,
+       ml.seller,
+       ml.price AS price_wei,
+       ml.eth_price AS eth_price_wei,
+       'secondary'::TEXT AS side,
+       CASE ml.status
+         WHEN 0 THEN CASE WHEN ml.expires_at IS NOT NULL AND ml.expires_at <= NOW() THEN 'expired' ELSE 'active' END
+         WHEN 1 THEN 'sold'
+         WHEN 2 THEN 'cancelled'
+         WHEN 3 THEN 'expired'
+         ELSE 'unknown'
+       END AS status,
+       ml.expires_at,
+       COALESCE(b.block_time, to_timestamp(0)) AS created_at
+FROM marketplace_listings ml
+LEFT JOIN tickets t ON t.token_id = ml.ticket_id
+LEFT JOIN chain_blocks b ON b.chain_id = ml.chain_id AND b.block_number = ml.created_block
+WHERE ml.seller IS NOT NULL AND ml.price IS NOT NULL
+UNION ALL
+SELECT 'off-' || l.id::TEXT,
+       'offchain'::TEXT,
+       NULL::BIGINT,
+       l.ticket_id,
+       t.event_id,
+       t.type_id,
+       l.seller_wallet,
+       l.price_wei,
+       NULL::NUMERIC,
+       l.side,
+       l.status,
+       NULL::TIMESTAMPTZ,
+       l.created_at
+FROM market_listings l
+LEFT JOIN tickets t ON t.token_id = l.ticket_id;
@@ -0,0 +1,37 @@
+-- 0013_auctions
+-- 拍卖投影：AuctionCreated / BidPlaced / AuctionEnded / AuctionCancelled
+-- end_time 为创建时的结束时间；防狙击延长后的实际结束时间以链上 auctions(id) 为准
+CREATE TABLE IF NOT EXISTS marketplace_auctions (
+    chain_id BIGINT NOT NULL,
+    auction_id NUMERIC(78,0) NOT NULL,
+    token_id NUMERIC(78,0) NOT NULL,
+    seller TEXT NOT NULL,
+    starting_price NUMERIC(78,0) NOT NULL,
+    eth_starting_price NUMERIC(78,0) NOT NULL,
+    reserve_price NUMERIC(78,0) NOT NULL,
+    end_time TIMESTAMPTZ NOT NULL,
+    status SMALLINT NOT NULL,
+    current_bid NUMERIC(78,0),
+    current_bidder TEXT,
+    current_bid_is_eth BOOLEAN,
+    bid_count INT NOT NULL DEFAULT 0,
+    winner TEXT,
+    winning_bid NUMERIC(78,0),
+    created_block BIGINT NOT NULL,
+    updated_block BIGINT NOT NULL,
+    PRIMARY KEY(chain_id, auction_id)
+);
+CREATE INDEX IF NOT EXISTS idx_marketplace_auctions_live ON marketplace_auctions(chain_id, status, end_time);
+
+CREATE TABLE IF NOT EXISTS marketplace_bids (
+    chain_id BIGINT NOT NULL,
+    auction_id NUMERIC(78,0) NOT NULL,
+    bidder TEXT NOT NULL,
+    amount NUMERIC(78,0) NOT NULL,
+    is_eth BOOLEAN NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INT NOT NULL,
+    PRIMARY KEY(chain_id, tx_hash, log_index)
+);
+CREATE INDEX IF NOT EXISTS idx_marketplace_bids_auction ON marketplace_bids(chain_id, auction_id, block_number);
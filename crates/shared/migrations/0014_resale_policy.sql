@@ -0,0 +1,25 @@
+-- 0014_resale_policy
+-- 按活动配置的转售政策与违规挂单标记
+-- max_price_bps 为相对票种面值的万分比上限，NULL 表示不限制
+CREATE TABLE IF NOT EXISTS event_resale_policies (
+    event_id BIGINT PRIMARY KEY REFERENCES events(id) ON DELETE CASCADE,
+    max_price_bps INT CHECK (max_price_bps IS NULL OR max_price_bps >= 10000),
+    max_listings_per_wallet INT CHECK (max_listings_per_wallet IS NULL OR max_listings_per_wallet > 0),
+    updated_by TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- 索引器在 ListingCreated / ListingUpdated 投影后重新评估，每次整体替换该挂单的标记
+CREATE TABLE IF NOT EXISTS listing_policy_violations (
+    chain_id BIGINT NOT NULL,
+    listing_id NUMERIC(78,0) NOT NULL,
+    event_id BIGINT NOT NULL,
+    rule TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    detected_block BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(chain_id, listing_id, rule)
+);
+CREATE INDEX IF NOT EXISTS idx_listing_policy_violations_event ON listing_policy_violations(event_id, chain_id);
+-- 单钱包挂单计数
+CREATE INDEX IF NOT EXISTS idx_marketplace_listings_seller ON marketplace_listings(chain_id, seller, status);
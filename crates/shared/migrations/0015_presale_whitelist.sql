@@ -0,0 +1,15 @@
+-- 0015_presale_whitelist
+-- 预售白名单：addToPresaleWhitelist 不发事件，API 导入时记为 pending，
+-- 索引器按 isInPresaleWhitelist 读取确认后置为 confirmed
+CREATE TABLE IF NOT EXISTS presale_whitelist (
+    chain_id BIGINT NOT NULL,
+    type_id BIGINT NOT NULL REFERENCES ticket_types(id) ON DELETE CASCADE,
+    wallet TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    requested_by TEXT,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    confirmed_block BIGINT,
+    checked_at TIMESTAMPTZ,
+    PRIMARY KEY(chain_id, type_id, wallet)
+);
+CREATE INDEX IF NOT EXISTS idx_presale_whitelist_pending ON presale_whitelist(chain_id, checked_at NULLS FIRST) WHERE status = 'pending';
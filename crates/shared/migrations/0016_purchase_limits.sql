@@ -0,0 +1,29 @@
+-- 0016_purchase_limits
+-- 一级市场购买投影（EventManager.TicketPurchased），用于计算钱包剩余可购数量
+CREATE TABLE IF NOT EXISTS ticket_purchases (
+    chain_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INT NOT NULL,
+    chain_event_id BIGINT NOT NULL,
+    chain_type_id BIGINT NOT NULL,
+    buyer TEXT NOT NULL,
+    quantity BIGINT NOT NULL,
+    total_cost NUMERIC(78,0) NOT NULL,
+    paid_with_eth BOOLEAN NOT NULL,
+    PRIMARY KEY(chain_id, tx_hash, log_index)
+);
+CREATE INDEX IF NOT EXISTS idx_ticket_purchases_buyer ON ticket_purchases(chain_id, chain_event_id, buyer);
+CREATE INDEX IF NOT EXISTS idx_ticket_purchases_type ON ticket_purchases(chain_id, chain_event_id, chain_type_id);
+
+-- setTicketTypePurchaseLimit 既不发事件也没有读取接口，这里记录经 API 下发的单钱包票种限额
+CREATE TABLE IF NOT EXISTS ticket_type_purchase_limits (
+    chain_id BIGINT NOT NULL,
+    chain_event_id BIGINT NOT NULL,
+    chain_type_id BIGINT NOT NULL,
+    wallet TEXT NOT NULL,
+    purchase_limit BIGINT NOT NULL,
+    set_by TEXT NOT NULL,
+    set_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(chain_id, chain_event_id, chain_type_id, wallet)
+);
@@ -0,0 +1,24 @@
+-- 0017_price_history
+-- Swap 事件投影：同一交易可能包含多次兑换（multiSwap），主键补充 log_index
+-- price 为成交价（每 1e18 代币最小单位的 ETH wei），ETH 以零地址表示
+ALTER TABLE token_swaps ADD COLUMN IF NOT EXISTS log_index INT NOT NULL DEFAULT 0;
+ALTER TABLE token_swaps ADD COLUMN IF NOT EXISTS fee NUMERIC(78,0);
+ALTER TABLE token_swaps ADD COLUMN IF NOT EXISTS eth_amount NUMERIC(78,0);
+ALTER TABLE token_swaps ADD COLUMN IF NOT EXISTS token_amount NUMERIC(78,0);
+ALTER TABLE token_swaps ADD COLUMN IF NOT EXISTS price NUMERIC(78,0);
+ALTER TABLE token_swaps DROP CONSTRAINT IF EXISTS token_swaps_pkey;
+ALTER TABLE token_swaps ADD PRIMARY KEY (chain_id, tx_hash, log_index);
+CREATE INDEX IF NOT EXISTS idx_token_swaps_block ON token_swaps(chain_id, block_number);
+
+-- PriceUpdated 观测值（TWAP）
+CREATE TABLE IF NOT EXISTS token_price_observations (
+    chain_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index INT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    token_price NUMERIC(78,0) NOT NULL,
+    eth_price NUMERIC(78,0) NOT NULL,
+    observed_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY(chain_id, block_number, log_index)
+);
+CREATE INDEX IF NOT EXISTS idx_token_price_observations_time ON token_price_observations(chain_id, observed_at);
@@ -0,0 +1,39 @@
+-- 0018_lp_positions
+-- 流动性提供者：LiquidityAdded / LiquidityRemoved 记录存取，LP 代币 Transfer（含铸造/销毁）汇总持仓余额
+CREATE TABLE IF NOT EXISTS lp_events (
+    chain_id BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INT NOT NULL,
+    block_number BIGINT NOT NULL,
+    provider TEXT NOT NULL,
+    -- add | remove
+    kind TEXT NOT NULL,
+    token_amount NUMERIC(78,0) NOT NULL,
+    eth_amount NUMERIC(78,0) NOT NULL,
+    liquidity NUMERIC(78,0) NOT NULL,
+    PRIMARY KEY(chain_id, tx_hash, log_index)
+);
+CREATE INDEX IF NOT EXISTS idx_lp_events_provider ON lp_events(chain_id, provider);
+
+CREATE TABLE IF NOT EXISTS lp_transfers (
+    chain_id BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INT NOT NULL,
+    block_number BIGINT NOT NULL,
+    from_addr TEXT NOT NULL,
+    to_addr TEXT NOT NULL,
+    value NUMERIC(78,0) NOT NULL,
+    PRIMARY KEY(chain_id, tx_hash, log_index)
+);
+CREATE INDEX IF NOT EXISTS idx_lp_transfers_from ON lp_transfers(chain_id, from_addr);
+CREATE INDEX IF NOT EXISTS idx_lp_transfers_to ON lp_transfers(chain_id, to_addr);
+
+-- 费率变更历史（FeeRatesUpdated），按成交所在区块取当时生效的费率
+CREATE TABLE IF NOT EXISTS swap_fee_rates (
+    chain_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index INT NOT NULL,
+    swap_fee_rate NUMERIC(78,0) NOT NULL,
+    protocol_fee_rate NUMERIC(78,0) NOT NULL,
+    PRIMARY KEY(chain_id, block_number, log_index)
+);
@@ -0,0 +1,30 @@
+-- 0019_flash_loans
+-- 闪电贷记录：借款方、借出数量与同一交易内的其他合约调用（callTracer 可用时）
+CREATE TABLE IF NOT EXISTS flash_loans (
+    chain_id BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_from TEXT NOT NULL,
+    borrower TEXT NOT NULL,
+    -- 仅凭事件推断时未知
+    token_amount NUMERIC(78,0),
+    eth_amount NUMERIC(78,0),
+    -- calldata | trace | logs
+    source TEXT NOT NULL,
+    calls JSONB NOT NULL DEFAULT '[]',
+    max_price_move_bps INT,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(chain_id, tx_hash)
+);
+CREATE INDEX IF NOT EXISTS idx_flash_loans_block ON flash_loans(chain_id, block_number DESC);
+
+-- price_move：交易内价格大幅偏离；marketplace_sale：同区块有二级市场成交
+CREATE TABLE IF NOT EXISTS flash_loan_alerts (
+    chain_id BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    detail JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY(chain_id, tx_hash, kind),
+    FOREIGN KEY(chain_id, tx_hash) REFERENCES flash_loans(chain_id, tx_hash) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- 0020_siwe_nonce_key: nonce 按值存储，同一钱包可并存多个未过期挑战；签发新挑战不再覆盖旧的
+ALTER TABLE siwe_nonces DROP CONSTRAINT IF EXISTS siwe_nonces_pkey;
+ALTER TABLE siwe_nonces ADD PRIMARY KEY (nonce);
+CREATE INDEX IF NOT EXISTS idx_siwe_nonces_wallet ON siwe_nonces(wallet);
+CREATE INDEX IF NOT EXISTS idx_siwe_nonces_expires ON siwe_nonces(expires_at);
@@ -0,0 +1,6 @@
+-- 0021_ticket_state_blocks: 记录 tickets.owner_wallet / status 最近一次投影来源的 (区块, 日志序号)
+-- 重放或重索引旧区间时只接受不早于已记录位置的事件，避免状态回退（例如 used -> valid）
+ALTER TABLE tickets ADD COLUMN IF NOT EXISTS owner_block BIGINT;
+ALTER TABLE tickets ADD COLUMN IF NOT EXISTS owner_log_index INT;
+ALTER TABLE tickets ADD COLUMN IF NOT EXISTS status_block BIGINT;
+ALTER TABLE tickets ADD COLUMN IF NOT EXISTS status_log_index INT;
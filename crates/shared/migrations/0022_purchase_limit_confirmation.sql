@@ -0,0 +1,11 @@
+-- 0022_purchase_limit_confirmation
+-- 单钱包票种限额改为 待确认 / 已确认 两段：API 只记录 pending_limit，
+-- 组织者提交 setTicketTypePurchaseLimit 交易哈希并校验回执后才写入 purchase_limit 参与限购
+ALTER TABLE ticket_type_purchase_limits ALTER COLUMN purchase_limit DROP NOT NULL;
+ALTER TABLE ticket_type_purchase_limits ADD COLUMN IF NOT EXISTS pending_limit BIGINT;
+ALTER TABLE ticket_type_purchase_limits ADD COLUMN IF NOT EXISTS confirmed_block BIGINT;
+ALTER TABLE ticket_type_purchase_limits ADD COLUMN IF NOT EXISTS confirmed_tx TEXT;
+
+-- 历史记录均未经链上确认，全部转为待确认
+UPDATE ticket_type_purchase_limits SET pending_limit = purchase_limit, purchase_limit = NULL
+WHERE confirmed_block IS NULL AND purchase_limit IS NOT NULL;
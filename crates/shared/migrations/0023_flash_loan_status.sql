@@ -0,0 +1,7 @@
+-- 0023_flash_loan_status
+-- 仅凭事件推断（source = logs）的记录没有调用数据或轨迹佐证，标记为 suspected；calldata / trace 确认的为 confirmed
+ALTER TABLE flash_loans ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'confirmed';
+UPDATE flash_loans SET status = 'suspected' WHERE source = 'logs';
+CREATE INDEX IF NOT EXISTS idx_flash_loans_status ON flash_loans(chain_id, status, block_number DESC);
+
+-- marketplace_sale 告警自此只针对与闪电贷交易相关的同区块成交（detail.sales[].link 说明关联方式）
@@ -0,0 +1,7 @@
+-- 0024_ticket_mint_projection
+-- TicketMinted 记录铸造交易与座位号，用于生成 tickets 业务投影：
+-- 活动取 events.chain_event_id，票种取同一交易的 TicketPurchased（活动只有一个已上链票种时直接取该票种）
+ALTER TABLE ticket_tokens ADD COLUMN IF NOT EXISTS mint_tx TEXT;
+ALTER TABLE ticket_tokens ADD COLUMN IF NOT EXISTS seat_number NUMERIC(78,0);
+CREATE INDEX IF NOT EXISTS idx_ticket_tokens_mint_tx ON ticket_tokens(chain_id, mint_tx);
+CREATE INDEX IF NOT EXISTS idx_ticket_purchases_tx ON ticket_purchases(chain_id, tx_hash);
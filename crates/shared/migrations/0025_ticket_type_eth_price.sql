@@ -0,0 +1,5 @@
+-- 0025_ticket_type_eth_price
+-- 票种 ETH 面值：索引器在 TicketTypeAdded 后按 getTicketTypeInfo 写入，转售限价据此校验 ETH 挂单
+ALTER TABLE ticket_types ADD COLUMN IF NOT EXISTS eth_price_wei NUMERIC(78,0);
+UPDATE ticket_types SET eth_price_wei = (meta->>'eth_price_wei')::NUMERIC
+WHERE eth_price_wei IS NULL AND meta->>'eth_price_wei' ~ '^[0-9]+$';
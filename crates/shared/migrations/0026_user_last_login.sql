@@ -0,0 +1,4 @@
+-- 0026_user_last_login
+-- 记录最近登录时间：链上角色全量同步只核对有授权记录的钱包与近期登录过的用户
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_login_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS idx_users_last_login ON users(last_login_at) WHERE last_login_at IS NOT NULL;
@@ -15,9 +15,55 @@ pub struct AppConfig {
     #[serde(default)] pub event_manager_addr: Option<String>,
     #[serde(default)] pub marketplace_addr: Option<String>,
     #[serde(default)] pub token_swap_addr: Option<String>,
+    // 链上对账任务：间隔秒数；抽样条数（为空则全量扫描）；是否自动修复
+    #[serde(default = "default_reconcile_interval_secs")]
+    pub reconcile_interval_secs: u64,
+    #[serde(default)] pub reconcile_sample_size: Option<i64>,
+    #[serde(default)] pub reconcile_auto_repair: bool,
+    // 索引器管理接口：未配置 token 时不启动
+    #[serde(default = "default_indexer_admin_addr")]
+    pub indexer_admin_addr: String,
+    #[serde(default)] pub indexer_admin_token: Option<String>,
+    // SIWE 登录：消息中的 domain/URI 必须与此一致；访问/刷新令牌有效期
+    #[serde(default = "default_siwe_domain")]
+    pub siwe_domain: String,
+    #[serde(default = "default_siwe_uri")]
+    pub siwe_uri: String,
+    #[serde(default = "default_jwt_access_ttl_secs")]
+    pub jwt_access_ttl_secs: i64,
+    #[serde(default = "default_jwt_refresh_ttl_secs")]
+    pub jwt_refresh_ttl_secs: i64,
+    // 角色：索引器链上全量同步间隔；API 侧角色缓存秒数
+    #[serde(default = "default_role_sync_interval_secs")]
+    pub role_sync_interval_secs: u64,
+    #[serde(default = "default_role_cache_ttl_secs")]
+    pub role_cache_ttl_secs: u64,
+    // tokenURI 元数据：对外访问地址（external_url/image 拼接）；缓存秒数
+    #[serde(default = "default_public_base_url")]
+    pub public_base_url: String,
+    #[serde(default = "default_metadata_max_age_secs")]
+    pub metadata_max_age_secs: u64,
+    // 预售白名单：索引器按 isInPresaleWhitelist 确认待上链条目的间隔
+    #[serde(default = "default_whitelist_sync_interval_secs")]
+    pub whitelist_sync_interval_secs: u64,
+    // Swap 报价 Redis 缓存秒数
+    #[serde(default = "default_swap_quote_cache_secs")]
+    pub swap_quote_cache_secs: u64,
 }
 
 fn default_listen_addr() -> String { "0.0.0.0:8080".into() }
+fn default_reconcile_interval_secs() -> u64 { 3600 }
+fn default_indexer_admin_addr() -> String { "127.0.0.1:9090".into() }
+fn default_siwe_domain() -> String { "localhost".into() }
+fn default_siwe_uri() -> String { "http://localhost".into() }
+fn default_jwt_access_ttl_secs() -> i64 { 900 }
+fn default_jwt_refresh_ttl_secs() -> i64 { 30 * 24 * 3600 }
+fn default_role_sync_interval_secs() -> u64 { 300 }
+fn default_role_cache_ttl_secs() -> u64 { 30 }
+fn default_public_base_url() -> String { "http://localhost:8080".into() }
+fn default_metadata_max_age_secs() -> u64 { 300 }
+fn default_whitelist_sync_interval_secs() -> u64 { 60 }
+fn default_swap_quote_cache_secs() -> u64 { 10 }
 
 impl AppConfig {
     pub fn from_env() -> Self {
@@ -28,3 +28,19 @@ sol!(
     TokenSwap,
     "../../crates/shared/abis/TokenSwap.json"
 );
+
+// 合约钱包签名校验（EIP-1271）
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface IERC1271 {
+        function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4 magicValue);
+    }
+}
+
+sol!(
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    PlatformToken,
+    "../../crates/shared/abis/PlatformToken.json"
+);
@@ -0,0 +1,74 @@
+// 待用户钱包签名的合约调用（仅 to/data/value，不含 gas/nonce）
+use crate::contracts::bindings::EventManager;
+use crate::domain::event::draft::{parse_wei, unix, DraftTicketType, EventDraft};
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol_types::SolCall;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparedCall {
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+    // 便于前端展示的函数名
+    pub function: &'static str,
+}
+
+impl PreparedCall {
+    pub fn new<C: SolCall>(to: Address, call: &C, value: U256) -> Self {
+        Self {
+            to,
+            data: call.abi_encode().into(),
+            value,
+            function: C::SIGNATURE.split('(').next().unwrap_or(C::SIGNATURE),
+        }
+    }
+}
+
+pub fn create_event(event_manager: Address, d: &EventDraft) -> PreparedCall {
+    let call = EventManager::createEventCall {
+        name: d.title.clone(),
+        description: d.description.clone(),
+        imageURI: d.image_uri.clone(),
+        venue: d.venue.clone(),
+        startTime: unix(Some(d.start_time)),
+        endTime: unix(Some(d.end_time)),
+        requiresApproval: d.requires_approval,
+    };
+    PreparedCall::new(event_manager, &call, U256::ZERO)
+}
+
+pub fn add_ticket_type(event_manager: Address, chain_event_id: u64, t: &DraftTicketType) -> PreparedCall {
+    let call = EventManager::addTicketTypeCall {
+        eventId: U256::from(chain_event_id),
+        name: t.name.clone(),
+        price: parse_wei(&t.price_wei).unwrap_or_default(),
+        ethPrice: parse_wei(&t.eth_price_wei).unwrap_or_default(),
+        totalSupply: U256::from(t.supply.max(0)),
+        presaleStart: unix(t.presale_start),
+        saleStart: unix(t.sale_start),
+        saleEnd: unix(t.sale_end),
+        isTransferable: t.is_transferable,
+        presaleOnly: t.presale_only,
+    };
+    PreparedCall::new(event_manager, &call, U256::ZERO)
+}
+
+pub fn add_to_presale_whitelist(event_manager: Address, chain_event_id: u64, chain_type_id: u64, users: &[Address]) -> PreparedCall {
+    let call = EventManager::addToPresaleWhitelistCall {
+        eventId: U256::from(chain_event_id),
+        typeId: U256::from(chain_type_id),
+        users: users.to_vec(),
+    };
+    PreparedCall::new(event_manager, &call, U256::ZERO)
+}
+
+pub fn set_ticket_type_purchase_limit(event_manager: Address, chain_event_id: u64, chain_type_id: u64, user: Address, limit: u64) -> PreparedCall {
+    let call = EventManager::setTicketTypePurchaseLimitCall {
+        eventId: U256::from(chain_event_id),
+        typeId: U256::from(chain_type_id),
+        user,
+        limit: U256::from(limit),
+    };
+    PreparedCall::new(event_manager, &call, U256::ZERO)
+}
@@ -1,3 +1,7 @@
 pub mod bindings;
+pub mod calldata;
+pub mod permit;
 pub mod provider;
 pub mod registry;
+pub mod revert;
+pub mod txbuilder;
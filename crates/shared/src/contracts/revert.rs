@@ -0,0 +1,272 @@
+// 合约回滚数据解码：Error(string) / Panic(uint256) / ABI 自定义错误 -> 稳定的 error_code
+// 自定义错误经 sol! 生成的 <Contract>Errors 枚举解码；
+// require 文本形如 "<Contract>: <reason>"，reason 按完整单词的短语前缀归类，
+// 对应多种链上状态的文本（如 "ticket not valid" 可能是已核销、已取消或已过期）只给通用码。
+
+use crate::contracts::bindings::PlatformToken::PlatformTokenErrors;
+use crate::contracts::bindings::TicketManager::TicketManagerErrors;
+use crate::error::ApiError;
+use alloy::primitives::Bytes;
+use alloy::sol_types::{Panic, Revert, SolError, SolInterface};
+use alloy::transports::RpcError;
+use serde::Serialize;
+
+pub const CODE_REVERTED: &str = "CONTRACT_REVERTED";
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ContractRevert {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip)]
+    pub data: Bytes,
+}
+
+// (reason 短语, error_code)；短语须按完整单词匹配，先匹配者优先
+const REASONS: &[(&str, &str)] = &[
+    // TicketManager
+    ("purchase limit exceeded", "PURCHASE_LIMIT_EXCEEDED"),
+    ("seat already taken", "SEAT_ALREADY_TAKEN"),
+    ("ticket does not exist", "TICKET_NOT_FOUND"),
+    ("ticket not in validity", "TICKET_OUTSIDE_VALIDITY_WINDOW"),
+    ("ticket already used", "TICKET_ALREADY_USED"),
+    ("ticket expired", "TICKET_EXPIRED"),
+    ("ticket not transferable", "TICKET_NOT_TRANSFERABLE"),
+    // 已核销 / 已取消 / 已过期都会落到这两条，无法区分
+    ("ticket not valid", "INVALID_TICKET_STATUS"),
+    ("invalid ticket status", "INVALID_TICKET_STATUS"),
+    ("unauthorized", "NOT_AUTHORIZED"),
+    // EventManager
+    ("event does not exist", "EVENT_NOT_FOUND"),
+    ("event not approved", "EVENT_NOT_APPROVED"),
+    ("event does not require", "INVALID_EVENT_STATUS"),
+    ("cannot add ticket", "INVALID_EVENT_STATUS"),
+    ("cannot update event", "INVALID_EVENT_STATUS"),
+    ("invalid status transition", "INVALID_EVENT_STATUS"),
+    ("invalid ticket type", "TICKET_TYPE_NOT_FOUND"),
+    ("insufficient tickets", "TICKETS_SOLD_OUT"),
+    ("not authorized organizer", "NOT_AUTHORIZED"),
+    ("not event organizer", "NOT_EVENT_ORGANIZER"),
+    ("not in presale whitelist", "NOT_IN_PRESALE_WHITELIST"),
+    ("presale not active", "PRESALE_NOT_ACTIVE"),
+    ("presale only", "PRESALE_ONLY"),
+    ("sale not active", "SALE_NOT_ACTIVE"),
+    ("ETH payment not supported", "ETH_PAYMENT_NOT_SUPPORTED"),
+    ("token payment not supported", "TOKEN_PAYMENT_NOT_SUPPORTED"),
+    ("insufficient payment", "INSUFFICIENT_PAYMENT"),
+    ("insufficient balance", "INSUFFICIENT_BALANCE"),
+    // Marketplace
+    ("cannot buy own ticket", "CANNOT_BUY_OWN_TICKET"),
+    ("listing does not exist", "LISTING_NOT_FOUND"),
+    ("listing expired", "LISTING_EXPIRED"),
+    ("listing not active", "LISTING_NOT_ACTIVE"),
+    ("not seller", "NOT_SELLER"),
+    ("not ticket owner", "NOT_TICKET_OWNER"),
+    ("ticket already in auction", "TICKET_IN_AUCTION"),
+    ("ticket in auction", "TICKET_IN_AUCTION"),
+    ("ticket already listed", "TICKET_ALREADY_LISTED"),
+    ("invalid duration", "INVALID_DURATION"),
+    // TokenSwap
+    ("slippage too high", "SLIPPAGE_EXCEEDED"),
+    ("insufficient output", "SLIPPAGE_EXCEEDED"),
+    ("insufficient liquidity", "INSUFFICIENT_LIQUIDITY"),
+    ("insufficient reserves", "INSUFFICIENT_LIQUIDITY"),
+    ("insufficient ETH liquidity", "INSUFFICIENT_LIQUIDITY"),
+    ("insufficient token liquidity", "INSUFFICIENT_LIQUIDITY"),
+    ("no liquidity", "INSUFFICIENT_LIQUIDITY"),
+    ("insufficient LP", "INSUFFICIENT_LP_BALANCE"),
+    ("flash loan not repaid", "FLASH_LOAN_NOT_REPAID"),
+    // PlatformToken
+    ("exceeds max supply", "MAX_SUPPLY_EXCEEDED"),
+    // 通用参数错误
+    ("invalid", "INVALID_ARGUMENT"),
+    ("empty", "INVALID_ARGUMENT"),
+    ("array length mismatch", "INVALID_ARGUMENT"),
+    ("seat number mismatch", "INVALID_ARGUMENT"),
+];
+
+pub fn classify_reason(reason: &str) -> &'static str {
+    let rest = reason.split_once(": ").map(|(_, r)| r).unwrap_or(reason);
+    REASONS
+        .iter()
+        .find(|(p, _)| {
+            rest.strip_prefix(p)
+                .is_some_and(|tail| !tail.starts_with(|c: char| c.is_ascii_alphanumeric()))
+        })
+        .map(|(_, c)| *c)
+        .unwrap_or(CODE_REVERTED)
+}
+
+// TicketManager 的错误集包含各合约共有的 OpenZeppelin 错误（Pausable / Ownable / ReentrancyGuard），
+// PlatformToken 的错误集覆盖 TokenSwap 的 ERC20 错误；两者合起来即全部 ABI 自定义错误
+fn custom_error(data: &[u8]) -> Option<(&'static str, String)> {
+    if let Ok(e) = TicketManagerErrors::abi_decode(data, false) {
+        return Some(ticket_manager_error(e));
+    }
+    if let Ok(e) = PlatformTokenErrors::abi_decode(data, false) {
+        return Some(platform_token_error(e));
+    }
+    None
+}
+
+fn ticket_manager_error(e: TicketManagerErrors) -> (&'static str, String) {
+    use TicketManagerErrors as E;
+    match e {
+        E::ERC721NonexistentToken(e) => ("TICKET_NOT_FOUND", format!("ticket {} does not exist", e.tokenId)),
+        E::ERC721IncorrectOwner(e) => ("NOT_TICKET_OWNER", format!("ticket {} is owned by {}", e.tokenId, e.owner)),
+        E::ERC721InsufficientApproval(e) => {
+            ("TICKET_NOT_APPROVED", format!("{} is not approved for ticket {}", e.operator, e.tokenId))
+        }
+        E::ERC721InvalidApprover(e) => ("INVALID_ARGUMENT", format!("invalid approver {}", e.approver)),
+        E::ERC721InvalidOperator(e) => ("INVALID_ARGUMENT", format!("invalid operator {}", e.operator)),
+        E::ERC721InvalidOwner(e) => ("INVALID_ARGUMENT", format!("invalid owner {}", e.owner)),
+        E::ERC721InvalidReceiver(e) => ("INVALID_ARGUMENT", format!("invalid receiver {}", e.receiver)),
+        E::ERC721InvalidSender(e) => ("INVALID_ARGUMENT", format!("invalid sender {}", e.sender)),
+        E::ERC721OutOfBoundsIndex(e) => ("INVALID_ARGUMENT", format!("index {} out of bounds for {}", e.index, e.owner)),
+        E::ERC721EnumerableForbiddenBatchMint(_) => ("INVALID_ARGUMENT", "batch mint is not supported".into()),
+        E::EnforcedPause(_) => ("CONTRACT_PAUSED", "contract is paused".into()),
+        E::ExpectedPause(_) => ("CONTRACT_NOT_PAUSED", "contract is not paused".into()),
+        E::OwnableUnauthorizedAccount(e) => ("NOT_AUTHORIZED", format!("{} is not the owner", e.account)),
+        E::OwnableInvalidOwner(e) => ("INVALID_ARGUMENT", format!("invalid owner {}", e.owner)),
+        E::ReentrancyGuardReentrantCall(_) => (CODE_REVERTED, "reentrant call".into()),
+    }
+}
+
+fn platform_token_error(e: PlatformTokenErrors) -> (&'static str, String) {
+    use PlatformTokenErrors as E;
+    match e {
+        E::ERC20InsufficientBalance(e) => {
+            ("INSUFFICIENT_BALANCE", format!("balance {} is below required {}", e.balance, e.needed))
+        }
+        E::ERC20InsufficientAllowance(e) => (
+            "INSUFFICIENT_ALLOWANCE",
+            format!("allowance {} for {} is below required {}", e.allowance, e.spender, e.needed),
+        ),
+        E::ERC20InvalidApprover(e) => ("INVALID_ARGUMENT", format!("invalid approver {}", e.approver)),
+        E::ERC20InvalidReceiver(e) => ("INVALID_ARGUMENT", format!("invalid receiver {}", e.receiver)),
+        E::ERC20InvalidSender(e) => ("INVALID_ARGUMENT", format!("invalid sender {}", e.sender)),
+        E::ERC20InvalidSpender(e) => ("INVALID_ARGUMENT", format!("invalid spender {}", e.spender)),
+        E::ERC2612ExpiredSignature(_) => ("PERMIT_EXPIRED", "permit deadline has passed".into()),
+        E::ERC2612InvalidSigner(_) => ("PERMIT_INVALID_SIGNATURE", "permit signer does not match owner".into()),
+        E::ECDSAInvalidSignature(_) | E::ECDSAInvalidSignatureLength(_) | E::ECDSAInvalidSignatureS(_) => {
+            ("PERMIT_INVALID_SIGNATURE", "malformed permit signature".into())
+        }
+        E::InvalidAccountNonce(e) => {
+            ("PERMIT_INVALID_SIGNATURE", format!("permit nonce is stale, current nonce {}", e.currentNonce))
+        }
+        E::InvalidShortString(_) | E::StringTooLong(_) => ("INVALID_ARGUMENT", "invalid string argument".into()),
+        E::EnforcedPause(_) => ("CONTRACT_PAUSED", "contract is paused".into()),
+        E::ExpectedPause(_) => ("CONTRACT_NOT_PAUSED", "contract is not paused".into()),
+        E::OwnableUnauthorizedAccount(e) => ("NOT_AUTHORIZED", format!("{} is not the owner", e.account)),
+        E::OwnableInvalidOwner(e) => ("INVALID_ARGUMENT", format!("invalid owner {}", e.owner)),
+        E::ReentrancyGuardReentrantCall(_) => (CODE_REVERTED, "reentrant call".into()),
+    }
+}
+
+pub fn decode(data: &[u8]) -> ContractRevert {
+    let raw = Bytes::copy_from_slice(data);
+    if let Ok(r) = Revert::abi_decode(data, false) {
+        return ContractRevert {
+            code: classify_reason(&r.reason),
+            message: r.reason,
+            data: raw,
+        };
+    }
+    if let Ok(p) = Panic::abi_decode(data, false) {
+        return ContractRevert {
+            code: "CONTRACT_PANIC",
+            message: format!("panic code {:#x}", p.code),
+            data: raw,
+        };
+    }
+    if let Some((code, message)) = custom_error(data) {
+        return ContractRevert { code, message, data: raw };
+    }
+    ContractRevert {
+        code: CODE_REVERTED,
+        message: if data.is_empty() {
+            "execution reverted".into()
+        } else {
+            format!("execution reverted with unknown error {}", Bytes::copy_from_slice(&data[..data.len().min(4)]))
+        },
+        data: raw,
+    }
+}
+
+// 从 JSON-RPC 错误中提取回滚；非回滚错误返回 None
+pub fn from_rpc_error<E>(e: &RpcError<E>) -> Option<ContractRevert> {
+    let RpcError::ErrorResp(payload) = e else {
+        return None;
+    };
+    if let Some(data) = payload.as_revert_data() {
+        return Some(decode(&data));
+    }
+    // 部分节点只在 message 中给出文本
+    let msg = payload.message.strip_prefix("execution reverted")?;
+    let reason = msg.trim_start_matches(':').trim();
+    Some(ContractRevert {
+        code: classify_reason(reason),
+        message: if reason.is_empty() { "execution reverted".into() } else { reason.to_string() },
+        data: Bytes::new(),
+    })
+}
+
+pub fn from_contract_error(e: &alloy::contract::Error) -> Option<ContractRevert> {
+    match e {
+        alloy::contract::Error::TransportError(t) => from_rpc_error(t),
+        _ => None,
+    }
+}
+
+impl From<ContractRevert> for ApiError {
+    fn from(r: ContractRevert) -> Self {
+        ApiError::Contract { code: r.code, message: r.message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::bindings::{PlatformToken, TicketManager, TokenSwap};
+    use alloy::primitives::{address, U256};
+
+    #[test]
+    fn error_string_maps_to_stable_code() {
+        let data = Revert::from("TicketManager: purchase limit exceeded").abi_encode();
+        let r = decode(&data);
+        assert_eq!(r.code, "PURCHASE_LIMIT_EXCEEDED");
+        assert_eq!(r.message, "TicketManager: purchase limit exceeded");
+
+        // 多义文本只给通用码；短语须按完整单词匹配
+        assert_eq!(classify_reason("TicketManager: ticket not valid"), "INVALID_TICKET_STATUS");
+        assert_eq!(classify_reason("TicketManager: ticket already used"), "TICKET_ALREADY_USED");
+        assert_eq!(classify_reason("Marketplace: invalid price"), "INVALID_ARGUMENT");
+        assert_eq!(classify_reason("Marketplace: invalidated"), CODE_REVERTED);
+        assert_eq!(classify_reason("something else"), CODE_REVERTED);
+    }
+
+    #[test]
+    fn custom_errors_and_panics() {
+        let data = TicketManager::ERC721NonexistentToken { tokenId: U256::from(7) }.abi_encode();
+        assert_eq!(decode(&data).code, "TICKET_NOT_FOUND");
+
+        let data = TokenSwap::ERC20InsufficientAllowance {
+            spender: address!("00000000000000000000000000000000000000a1"),
+            allowance: U256::ZERO,
+            needed: U256::from(5),
+        }
+        .abi_encode();
+        assert_eq!(decode(&data).code, "INSUFFICIENT_ALLOWANCE");
+
+        // 共有的 OpenZeppelin 错误与 ERC2612 错误
+        let data = TicketManager::EnforcedPause {}.abi_encode();
+        assert_eq!(decode(&data).code, "CONTRACT_PAUSED");
+        let data = PlatformToken::ERC2612ExpiredSignature { deadline: U256::from(1) }.abi_encode();
+        assert_eq!(decode(&data).code, "PERMIT_EXPIRED");
+
+        let data = Panic::from(0x11).abi_encode();
+        assert_eq!(decode(&data).code, "CONTRACT_PANIC");
+
+        assert_eq!(decode(&[]).code, CODE_REVERTED);
+        assert_eq!(decode(&[0xde, 0xad, 0xbe, 0xef]).code, CODE_REVERTED);
+    }
+}
@@ -0,0 +1,332 @@
+// 用户操作 -> 未签名 EIP-1559 交易
+// 用 sol! 绑定编码调用数据，按发送方 eth_call 预执行（回滚时返回解码后的原因），
+// 再估算 gas（加缓冲）并给出 EIP-1559 费用建议；nonce 取发送方当前 pending 计数。
+
+use crate::contracts::bindings::{EventManager, Marketplace, TokenSwap};
+use crate::contracts::calldata::PreparedCall;
+use crate::contracts::provider::SharedProvider;
+use crate::contracts::registry::ContractAddresses;
+use crate::contracts::revert::{self, ContractRevert};
+use alloy::primitives::{Address, Bytes, TxKind, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::eth::{TransactionInput, TransactionRequest};
+use alloy::transports::RpcError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+// gas 估算上浮比例（%）
+pub const GAS_BUFFER_PCT: u64 = 20;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TxAction {
+    // 一级市场购票：pay_with_eth 时 value = ethPrice * quantity（链上读取）
+    PurchaseTickets {
+        event_id: u64,
+        type_id: u64,
+        quantity: u64,
+        #[serde(default)]
+        seat_numbers: Vec<u64>,
+        #[serde(default)]
+        pay_with_eth: bool,
+    },
+    CreateListing {
+        token_id: U256,
+        price: U256,
+        #[serde(default)]
+        eth_price: U256,
+        duration_secs: u64,
+        #[serde(default)]
+        accepts_eth: bool,
+    },
+    UpdateListing {
+        listing_id: U256,
+        price: U256,
+        #[serde(default)]
+        eth_price: U256,
+        duration_secs: u64,
+    },
+    CancelListing {
+        listing_id: U256,
+    },
+    // 二级市场购买：pay_with_eth 时 value = 挂单 ethPrice（链上读取）
+    BuyListing {
+        listing_id: U256,
+        #[serde(default)]
+        pay_with_eth: bool,
+    },
+    CreateAuction {
+        token_id: U256,
+        starting_price: U256,
+        #[serde(default)]
+        eth_starting_price: U256,
+        #[serde(default)]
+        reserve_price: U256,
+        duration_secs: u64,
+        #[serde(default)]
+        accepts_eth: bool,
+    },
+    SwapEthForTokens {
+        eth_in: U256,
+        min_tokens_out: U256,
+    },
+    SwapTokensForEth {
+        token_amount_in: U256,
+        min_eth_out: U256,
+    },
+    AddLiquidity {
+        token_amount: U256,
+        eth_amount: U256,
+        #[serde(default)]
+        min_token_amount: U256,
+        #[serde(default)]
+        min_eth_amount: U256,
+    },
+    RemoveLiquidity {
+        liquidity: U256,
+        #[serde(default)]
+        min_token_amount: U256,
+        #[serde(default)]
+        min_eth_amount: U256,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnsignedTx {
+    pub chain_id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub data: Bytes,
+    pub value: U256,
+    pub nonce: u64,
+    pub gas: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    // EIP-1559
+    #[serde(rename = "type")]
+    pub tx_type: &'static str,
+    pub function: &'static str,
+}
+
+#[derive(Debug, Error)]
+pub enum TxBuildError {
+    #[error("{0}")]
+    Invalid(String),
+    // 预执行回滚（已解码）
+    #[error("simulation reverted: {}", .0.message)]
+    Reverted(ContractRevert),
+    #[error(transparent)]
+    Rpc(#[from] anyhow::Error),
+}
+
+pub struct TxBuilder<'a> {
+    pub provider: &'a SharedProvider,
+    pub addrs: &'a ContractAddresses,
+    pub chain_id: u64,
+}
+
+fn request(from: Address, call: &PreparedCall) -> TransactionRequest {
+    TransactionRequest {
+        from: Some(from),
+        to: Some(TxKind::Call(call.to)),
+        value: Some(call.value),
+        input: TransactionInput::new(call.data.clone()),
+        ..Default::default()
+    }
+}
+
+fn invalid(msg: &str) -> TxBuildError {
+    TxBuildError::Invalid(msg.to_string())
+}
+
+impl<'a> TxBuilder<'a> {
+    pub fn new(provider: &'a SharedProvider, addrs: &'a ContractAddresses, chain_id: u64) -> Self {
+        Self { provider, addrs, chain_id }
+    }
+
+    // 编码调用；ETH 支付金额需要读取链上价格
+    pub async fn prepare(&self, action: &TxAction) -> Result<PreparedCall, TxBuildError> {
+        let a = self.addrs;
+        let call = match action.clone() {
+            TxAction::PurchaseTickets { event_id, type_id, quantity, seat_numbers, pay_with_eth } => {
+                if quantity == 0 {
+                    return Err(invalid("quantity must be positive"));
+                }
+                if !seat_numbers.is_empty() && seat_numbers.len() as u64 != quantity {
+                    return Err(invalid("seat_numbers must be empty or match quantity"));
+                }
+                let (event_id, type_id, qty) = (U256::from(event_id), U256::from(type_id), U256::from(quantity));
+                let seats: Vec<U256> = seat_numbers.into_iter().map(U256::from).collect();
+                if pay_with_eth {
+                    let (_, eth_price) = self.ticket_prices(event_id.to(), type_id.to()).await?;
+                    if eth_price.is_zero() {
+                        return Err(invalid("ticket type is not sold for ETH"));
+                    }
+                    let value = eth_price.saturating_mul(qty);
+                    let c = EventManager::purchaseTicketsWithEthCall { eventId: event_id, typeId: type_id, quantity: qty, seatNumbers: seats };
+                    PreparedCall::new(a.event_manager, &c, value)
+                } else {
+                    let c = EventManager::purchaseTicketsCall { eventId: event_id, typeId: type_id, quantity: qty, seatNumbers: seats };
+                    PreparedCall::new(a.event_manager, &c, U256::ZERO)
+                }
+            }
+            TxAction::CreateListing { token_id, price, eth_price, duration_secs, accepts_eth } => {
+                if accepts_eth && eth_price.is_zero() {
+                    return Err(invalid("eth_price is required when accepts_eth"));
+                }
+                let c = Marketplace::createListingCall {
+                    tokenId: token_id,
+                    price,
+                    ethPrice: eth_price,
+                    duration: U256::from(duration_secs),
+                    acceptsEth: accepts_eth,
+                };
+                PreparedCall::new(a.marketplace, &c, U256::ZERO)
+            }
+            TxAction::UpdateListing { listing_id, price, eth_price, duration_secs } => {
+                let c = Marketplace::updateListingCall {
+                    listingId: listing_id,
+                    newPrice: price,
+                    newEthPrice: eth_price,
+                    newDuration: U256::from(duration_secs),
+                };
+                PreparedCall::new(a.marketplace, &c, U256::ZERO)
+            }
+            TxAction::CancelListing { listing_id } => {
+                PreparedCall::new(a.marketplace, &Marketplace::cancelListingCall { listingId: listing_id }, U256::ZERO)
+            }
+            TxAction::BuyListing { listing_id, pay_with_eth } => {
+                if pay_with_eth {
+                    let mk = Marketplace::new(a.marketplace, self.provider.clone());
+                    let l = mk.listings(listing_id).call().await.map_err(|e| self.call_error(e))?;
+                    if !l.acceptsEth {
+                        return Err(invalid("listing does not accept ETH"));
+                    }
+                    PreparedCall::new(a.marketplace, &Marketplace::buyTicketWithEthCall { listingId: listing_id }, l.ethPrice)
+                } else {
+                    PreparedCall::new(a.marketplace, &Marketplace::buyTicketCall { listingId: listing_id }, U256::ZERO)
+                }
+            }
+            TxAction::CreateAuction { token_id, starting_price, eth_starting_price, reserve_price, duration_secs, accepts_eth } => {
+                let c = Marketplace::createAuctionCall {
+                    tokenId: token_id,
+                    startingPrice: starting_price,
+                    ethStartingPrice: eth_starting_price,
+                    reservePrice: reserve_price,
+                    duration: U256::from(duration_secs),
+                    acceptsEth: accepts_eth,
+                };
+                PreparedCall::new(a.marketplace, &c, U256::ZERO)
+            }
+            TxAction::SwapEthForTokens { eth_in, min_tokens_out } => {
+                if eth_in.is_zero() {
+                    return Err(invalid("eth_in must be positive"));
+                }
+                PreparedCall::new(a.token_swap, &TokenSwap::swapETHForTokensCall { minTokensOut: min_tokens_out }, eth_in)
+            }
+            TxAction::SwapTokensForEth { token_amount_in, min_eth_out } => {
+                let c = TokenSwap::swapTokensForETHCall { tokenAmountIn: token_amount_in, minETHOut: min_eth_out };
+                PreparedCall::new(a.token_swap, &c, U256::ZERO)
+            }
+            TxAction::AddLiquidity { token_amount, eth_amount, min_token_amount, min_eth_amount } => {
+                let c = TokenSwap::addLiquidityCall {
+                    tokenAmount: token_amount,
+                    minTokenAmount: min_token_amount,
+                    minETHAmount: min_eth_amount,
+                };
+                PreparedCall::new(a.token_swap, &c, eth_amount)
+            }
+            TxAction::RemoveLiquidity { liquidity, min_token_amount, min_eth_amount } => {
+                let c = TokenSwap::removeLiquidityCall {
+                    liquidity,
+                    minTokenAmount: min_token_amount,
+                    minETHAmount: min_eth_amount,
+                };
+                PreparedCall::new(a.token_swap, &c, U256::ZERO)
+            }
+        };
+        Ok(call)
+    }
+
+    pub async fn build(&self, from: Address, action: &TxAction) -> Result<UnsignedTx, TxBuildError> {
+        let call = self.prepare(action).await?;
+        self.finalize(from, call).await
+    }
+
+    // 预执行 + gas/费用/nonce
+    pub async fn finalize(&self, from: Address, call: PreparedCall) -> Result<UnsignedTx, TxBuildError> {
+        let req = request(from, &call);
+        self.simulate_request(&req).await?;
+        let estimate = self.provider.estimate_gas(&req).await.map_err(|e| self.rpc_error(e))?;
+        self.with_fees(from, call, estimate.saturating_mul(100 + GAS_BUFFER_PCT) / 100).await
+    }
+
+    // 无法预执行的交易（依赖尚未上链的前置调用，如 permit）使用调用方给定的 gas 上限
+    pub async fn finalize_with_gas(&self, from: Address, call: PreparedCall, gas: u64) -> Result<UnsignedTx, TxBuildError> {
+        self.with_fees(from, call, gas).await
+    }
+
+    pub async fn simulate(&self, from: Address, call: &PreparedCall) -> Result<(), TxBuildError> {
+        self.simulate_request(&request(from, call)).await
+    }
+
+    async fn simulate_request(&self, req: &TransactionRequest) -> Result<(), TxBuildError> {
+        self.provider.call(req).await.map(|_| ()).map_err(|e| self.rpc_error(e))
+    }
+
+    async fn with_fees(&self, from: Address, call: PreparedCall, gas: u64) -> Result<UnsignedTx, TxBuildError> {
+        let fees = self
+            .provider
+            .estimate_eip1559_fees(None)
+            .await
+            .map_err(|e| TxBuildError::Rpc(e.into()))?;
+        let nonce = self
+            .provider
+            .get_transaction_count(from)
+            .pending()
+            .await
+            .map_err(|e| TxBuildError::Rpc(e.into()))?;
+        Ok(UnsignedTx {
+            chain_id: self.chain_id,
+            from,
+            to: call.to,
+            data: call.data,
+            value: call.value,
+            nonce,
+            gas,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            tx_type: "0x2",
+            function: call.function,
+        })
+    }
+
+    // 票种价格 (代币价, ETH 价)
+    pub async fn ticket_prices(&self, event_id: u64, type_id: u64) -> Result<(U256, U256), TxBuildError> {
+        let em = EventManager::new(self.addrs.event_manager, self.provider.clone());
+        let info = em
+            .getTicketTypeInfo(U256::from(event_id), U256::from(type_id))
+            .call()
+            .await
+            .map_err(|e| self.call_error(e))?;
+        Ok((info.price, info.ethPrice))
+    }
+
+    // JSON-RPC 错误中带回滚数据的视为回滚，其余为节点错误
+    fn rpc_error<E: std::fmt::Debug + std::fmt::Display + Send + Sync + 'static>(
+        &self,
+        e: RpcError<E>,
+    ) -> TxBuildError {
+        match revert::from_rpc_error(&e) {
+            Some(r) => TxBuildError::Reverted(r),
+            None => TxBuildError::Rpc(anyhow::anyhow!("rpc error: {e}")),
+        }
+    }
+
+    fn call_error(&self, e: alloy::contract::Error) -> TxBuildError {
+        match revert::from_contract_error(&e) {
+            Some(r) => TxBuildError::Reverted(r),
+            None => TxBuildError::Rpc(e.into()),
+        }
+    }
+}
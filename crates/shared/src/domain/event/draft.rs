@@ -0,0 +1,243 @@
+// 组织者活动草稿：链下编辑，发布时生成 EventManager.createEvent / addTicketType 调用数据
+// 状态流转：draft -> publishing（已下发 createEvent，等待索引器）-> published（已关联 chain_event_id）
+
+use super::EventWithTypes;
+use alloy::primitives::U256;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_DRAFT: &str = "draft";
+pub const STATUS_PUBLISHING: &str = "publishing";
+pub const STATUS_PUBLISHED: &str = "published";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventDraft {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub venue: String,
+    #[serde(default)]
+    pub image_uri: String,
+    // 额外图片元数据（尺寸、alt 等），原样存入 events.meta
+    #[serde(default)]
+    pub images: Vec<serde_json::Value>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    #[serde(default)]
+    pub requires_approval: bool,
+    pub ticket_types: Vec<DraftTicketType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftTicketType {
+    pub name: String,
+    // 平台代币价格与 ETH 价格（wei，十进制字符串）
+    pub price_wei: String,
+    #[serde(default = "zero")]
+    pub eth_price_wei: String,
+    pub supply: i64,
+    #[serde(default)]
+    pub presale_start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sale_start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sale_end: Option<DateTime<Utc>>,
+    #[serde(default = "yes")]
+    pub is_transferable: bool,
+    #[serde(default)]
+    pub presale_only: bool,
+}
+
+fn zero() -> String {
+    "0".into()
+}
+
+fn yes() -> bool {
+    true
+}
+
+pub fn parse_wei(s: &str) -> Option<U256> {
+    U256::from_str_radix(s, 10).ok()
+}
+
+pub fn unix(t: Option<DateTime<Utc>>) -> U256 {
+    U256::from(t.map(|t| t.timestamp().max(0)).unwrap_or(0))
+}
+
+impl EventDraft {
+    // events.meta 中保存的草稿字段
+    pub fn event_meta(&self) -> serde_json::Value {
+        serde_json::json!({
+            "title": self.title,
+            "description": self.description,
+            "image_uri": self.image_uri,
+            "images": self.images,
+            "requires_approval": self.requires_approval,
+        })
+    }
+
+    // 从已存储的活动与票种还原草稿
+    pub fn from_stored(e: &EventWithTypes) -> Option<Self> {
+        let meta = e.event.meta.clone().unwrap_or_else(|| serde_json::json!({}));
+        let str_of = |k: &str| meta.get(k).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let ticket_types = e
+            .ticket_types
+            .iter()
+            .map(|t| DraftTicketType::from_row(&t.price_wei, t.supply_total, t.meta.as_ref()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            title: str_of("title"),
+            description: str_of("description"),
+            venue: e.event.venue.clone().unwrap_or_default(),
+            image_uri: str_of("image_uri"),
+            images: meta
+                .get("images")
+                .and_then(|v| v.as_array().cloned())
+                .unwrap_or_default(),
+            start_time: e.event.start_time,
+            end_time: e.event.end_time,
+            requires_approval: meta
+                .get("requires_approval")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            ticket_types,
+        })
+    }
+
+    // 发布前校验；编辑时仅做基本格式校验（时间可暂不在未来）
+    pub fn validate(&self, now: DateTime<Utc>, publishing: bool) -> Result<(), String> {
+        if self.title.trim().is_empty() {
+            return Err("title is required".into());
+        }
+        if self.venue.trim().is_empty() {
+            return Err("venue is required".into());
+        }
+        if self.start_time >= self.end_time {
+            return Err("start_time must be before end_time".into());
+        }
+        if publishing && self.start_time <= now {
+            return Err("start_time must be in the future".into());
+        }
+        if publishing && self.ticket_types.is_empty() {
+            return Err("at least one ticket type is required".into());
+        }
+        let mut names = std::collections::HashSet::new();
+        for t in self.ticket_types.iter() {
+            t.validate(self.end_time)?;
+            if !names.insert(t.name.as_str()) {
+                return Err(format!("duplicate ticket type name: {}", t.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DraftTicketType {
+    pub fn meta(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "eth_price_wei": self.eth_price_wei,
+            "presale_start": self.presale_start,
+            "sale_start": self.sale_start,
+            "sale_end": self.sale_end,
+            "is_transferable": self.is_transferable,
+            "presale_only": self.presale_only,
+        })
+    }
+
+    // 从 ticket_types 行还原（meta 缺失字段取默认值）
+    pub fn from_row(price_wei: &str, supply: i64, meta: Option<&serde_json::Value>) -> Option<Self> {
+        let mut m = meta.cloned().unwrap_or_else(|| serde_json::json!({}));
+        let obj = m.as_object_mut()?;
+        obj.insert("price_wei".into(), price_wei.into());
+        obj.insert("supply".into(), supply.into());
+        serde_json::from_value(m).ok()
+    }
+
+    fn validate(&self, event_end: DateTime<Utc>) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("ticket type name is required".into());
+        }
+        if parse_wei(&self.price_wei).is_none() || parse_wei(&self.eth_price_wei).is_none() {
+            return Err(format!("{}: prices must be decimal wei", self.name));
+        }
+        if self.supply <= 0 {
+            return Err(format!("{}: supply must be positive", self.name));
+        }
+        if let (Some(a), Some(b)) = (self.sale_start, self.sale_end) {
+            if a >= b {
+                return Err(format!("{}: sale_start must be before sale_end", self.name));
+            }
+        }
+        if let (Some(p), Some(s)) = (self.presale_start, self.sale_start) {
+            if p > s {
+                return Err(format!("{}: presale_start must not be after sale_start", self.name));
+            }
+        }
+        if self.sale_end.is_some_and(|e| e > event_end) {
+            return Err(format!("{}: sale_end must not be after the event ends", self.name));
+        }
+        if self.presale_only && self.presale_start.is_none() {
+            return Err(format!("{}: presale_only requires presale_start", self.name));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn draft(now: DateTime<Utc>) -> EventDraft {
+        EventDraft {
+            title: "Concert".into(),
+            description: String::new(),
+            venue: "Hall".into(),
+            image_uri: String::new(),
+            images: vec![],
+            start_time: now + Duration::days(10),
+            end_time: now + Duration::days(10) + Duration::hours(3),
+            requires_approval: false,
+            ticket_types: vec![DraftTicketType {
+                name: "GA".into(),
+                price_wei: "1000000000000000000".into(),
+                eth_price_wei: "0".into(),
+                supply: 100,
+                presale_start: None,
+                sale_start: Some(now + Duration::days(1)),
+                sale_end: Some(now + Duration::days(9)),
+                is_transferable: true,
+                presale_only: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn validate_rules() {
+        let now = Utc::now();
+        assert_eq!(draft(now).validate(now, true), Ok(()));
+
+        let mut d = draft(now);
+        d.ticket_types.push(d.ticket_types[0].clone());
+        assert!(d.validate(now, false).is_err());
+
+        let mut d = draft(now);
+        d.ticket_types[0].price_wei = "1.5".into();
+        assert!(d.validate(now, false).is_err());
+
+        let mut d = draft(now);
+        d.start_time = now - Duration::days(1);
+        assert!(d.validate(now, false).is_ok());
+        assert!(d.validate(now, true).is_err());
+    }
+
+    #[test]
+    fn ticket_type_meta_roundtrip() {
+        let t = draft(Utc::now()).ticket_types.remove(0);
+        let back = DraftTicketType::from_row(&t.price_wei, t.supply, Some(&t.meta())).unwrap();
+        assert_eq!(back.name, t.name);
+        assert_eq!(back.sale_end, t.sale_end);
+        assert_eq!(back.supply, 100);
+    }
+}
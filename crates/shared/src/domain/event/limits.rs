@@ -0,0 +1,63 @@
+// 购买限额：活动级 eventPurchaseLimit（链上读取）与单钱包票种限额（API 下发、凭交易回执确认），
+// 已购数量取自 TicketPurchased 投影；限额为 0 表示不限制
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct TypeAllowance {
+    pub type_id: i64,
+    pub chain_type_id: i64,
+    pub type_limit: Option<i64>,
+    // 已下发但尚未确认上链的票种限额，不参与 remaining 计算
+    pub pending_type_limit: Option<i64>,
+    pub purchased: i64,
+    pub supply_remaining: i64,
+    // 综合活动限额、票种限额与剩余库存后的可购数量
+    pub remaining: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PurchaseAllowance {
+    pub wallet: String,
+    pub event_id: i64,
+    pub chain_event_id: i64,
+    pub event_limit: Option<i64>,
+    pub purchased: i64,
+    pub ticket_types: Vec<TypeAllowance>,
+}
+
+impl PurchaseAllowance {
+    pub fn for_type(&self, chain_type_id: i64) -> Option<&TypeAllowance> {
+        self.ticket_types.iter().find(|t| t.chain_type_id == chain_type_id)
+    }
+}
+
+// 链上以 0 表示未设置
+pub fn limit(v: i64) -> Option<i64> {
+    (v > 0).then_some(v)
+}
+
+pub fn remaining(event_limit: Option<i64>, event_purchased: i64, type_limit: Option<i64>, type_purchased: i64, supply_remaining: i64) -> i64 {
+    let mut left = supply_remaining;
+    if let Some(l) = event_limit {
+        left = left.min(l - event_purchased);
+    }
+    if let Some(l) = type_limit {
+        left = left.min(l - type_purchased);
+    }
+    left.max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_takes_the_tightest_bound() {
+        assert_eq!(remaining(None, 0, None, 0, 50), 50);
+        assert_eq!(remaining(Some(4), 3, None, 0, 50), 1);
+        assert_eq!(remaining(Some(4), 1, Some(2), 1, 50), 1);
+        assert_eq!(remaining(Some(4), 5, None, 0, 50), 0);
+        assert_eq!(remaining(None, 0, Some(10), 0, 3), 3);
+        assert_eq!(limit(0), None);
+    }
+}
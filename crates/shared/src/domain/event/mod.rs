@@ -1,3 +1,7 @@
+pub mod draft;
+pub mod limits;
+pub mod whitelist;
+
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
@@ -5,10 +9,13 @@ use serde::{Serialize, Deserialize};
 pub struct Event {
     pub id: i64,
     pub organizer_wallet: String,
+    pub chain_event_id: Option<i64>,
     pub start_time: DateTime<Utc>,
     pub end_time: DateTime<Utc>,
     pub venue: Option<String>,
     pub status: String,
+    pub meta: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug)]
@@ -19,3 +26,99 @@ pub struct NewEvent<'a> {
     pub venue: Option<&'a str>,
     pub status: &'a str,
 }
+
+// 价格以 wei 十进制字符串表示，避免 NUMERIC(78,0) 精度丢失
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TicketType {
+    pub id: i64,
+    pub event_id: i64,
+    pub chain_type_id: Option<i64>,
+    pub price_wei: String,
+    pub supply_total: i64,
+    pub supply_sold: i64,
+    pub meta: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EventWithTypes {
+    #[serde(flatten)]
+    pub event: Event,
+    pub ticket_types: Vec<TicketType>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct EventFilter {
+    pub status: Option<String>,
+    pub organizer: Option<String>,
+    // 开始时间窗口 [starts_after, starts_before)
+    pub starts_after: Option<DateTime<Utc>>,
+    pub starts_before: Option<DateTime<Utc>>,
+    // 场馆名称模糊匹配（不区分大小写）
+    pub venue: Option<String>,
+    // 公开目录隐藏未发布的草稿
+    pub exclude_drafts: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventSort {
+    #[default]
+    StartAsc,
+    StartDesc,
+    CreatedDesc,
+}
+
+impl std::str::FromStr for EventSort {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "start_time" => Ok(EventSort::StartAsc),
+            "-start_time" => Ok(EventSort::StartDesc),
+            "-created_at" => Ok(EventSort::CreatedDesc),
+            _ => Err(()),
+        }
+    }
+}
+
+// keyset 游标：上一页最后一条的 (排序键, id)，编码为 "<微秒时间戳>_<id>"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventCursor {
+    pub key: DateTime<Utc>,
+    pub id: i64,
+}
+
+impl EventCursor {
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.key.timestamp_micros(), self.id)
+    }
+
+    pub fn decode(s: &str) -> Option<Self> {
+        let (k, id) = s.split_once('_')?;
+        Some(Self {
+            key: DateTime::from_timestamp_micros(k.parse().ok()?)?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventPage {
+    pub items: Vec<EventWithTypes>,
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn cursor_roundtrip() {
+        let c = EventCursor {
+            key: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap(),
+            id: 42,
+        };
+        assert_eq!(EventCursor::decode(&c.encode()), Some(c));
+        assert_eq!(EventCursor::decode("garbage"), None);
+        assert_eq!(EventCursor::decode("1_x"), None);
+    }
+}
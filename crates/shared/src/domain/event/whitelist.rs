@@ -0,0 +1,86 @@
+// 预售白名单：组织者上传 CSV，与已确认的链上白名单求差后生成分批 addToPresaleWhitelist
+// 合约不发事件，条目先记为 pending，由索引器按 isInPresaleWhitelist 确认；超时未上链的置为 expired，重新导入后恢复 pending
+use alloy::primitives::Address;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_CONFIRMED: &str = "confirmed";
+pub const STATUS_EXPIRED: &str = "expired";
+
+// 单次导入上限与每笔交易的地址数（控制 calldata 大小与 gas）
+pub const MAX_IMPORT_WALLETS: usize = 10_000;
+pub const BATCH_SIZE: usize = 200;
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct InvalidRow {
+    pub line: usize,
+    pub value: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedWallets {
+    // 按首次出现顺序去重
+    pub wallets: Vec<Address>,
+    pub invalid: Vec<InvalidRow>,
+    pub duplicates: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WhitelistEntry {
+    pub wallet: String,
+    pub status: String,
+    pub requested_by: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub confirmed_block: Option<i64>,
+}
+
+// 公开资格查询：按票种给出，只认已确认条目
+#[derive(Debug, Serialize, Clone)]
+pub struct Eligibility {
+    pub type_id: i64,
+    pub chain_type_id: Option<i64>,
+    pub eligible: bool,
+    pub status: Option<String>,
+}
+
+// 每行取第一列；首行为 wallet/address 表头时跳过，空行忽略
+pub fn parse_csv(body: &str) -> ParsedWallets {
+    let mut out = ParsedWallets::default();
+    let mut seen = HashSet::new();
+    for (i, line) in body.lines().enumerate() {
+        let value = line.split(',').next().unwrap_or_default().trim().trim_matches('"').trim();
+        if value.is_empty() {
+            continue;
+        }
+        if i == 0 && (value.eq_ignore_ascii_case("wallet") || value.eq_ignore_ascii_case("address")) {
+            continue;
+        }
+        match value.parse::<Address>() {
+            Ok(a) if seen.insert(a) => out.wallets.push(a),
+            Ok(_) => out.duplicates += 1,
+            Err(_) => out.invalid.push(InvalidRow { line: i + 1, value: value.to_string() }),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_first_column_and_dedups() {
+        let csv = "wallet,note\n\
+                   0x00000000000000000000000000000000000000aa,vip\n\
+                   \n\
+                   \"0x00000000000000000000000000000000000000aa\"\n\
+                   not-an-address\n\
+                   0x00000000000000000000000000000000000000bb\n";
+        let p = parse_csv(csv);
+        assert_eq!(p.wallets.len(), 2);
+        assert_eq!(p.duplicates, 1);
+        assert_eq!(p.invalid, vec![InvalidRow { line: 5, value: "not-an-address".into() }]);
+    }
+}
@@ -0,0 +1,151 @@
+// 拍卖：索引投影 + 出价辅助（最低有效出价、防狙击延时说明）
+use alloy::primitives::U256;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+// 与 Marketplace.AuctionStatus 枚举顺序保持一致
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuctionStatus {
+    Active,
+    Ended,
+    Cancelled,
+}
+
+impl AuctionStatus {
+    pub fn from_chain(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Active),
+            1 => Some(Self::Ended),
+            2 => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn to_chain(self) -> i16 {
+        self as i16
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Ended => "ended",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+// 金额均为 wei 十进制字符串
+#[derive(Debug, Serialize, Clone)]
+pub struct Auction {
+    pub auction_id: String,
+    pub token_id: String,
+    pub event_id: Option<i64>,
+    pub type_id: Option<i64>,
+    pub seller: String,
+    pub starting_price_wei: String,
+    pub eth_starting_price_wei: String,
+    pub reserve_price_wei: String,
+    pub end_time: DateTime<Utc>,
+    pub status: String,
+    pub current_bid_wei: Option<String>,
+    pub current_bidder: Option<String>,
+    pub current_bid_is_eth: Option<bool>,
+    pub bid_count: i32,
+    pub winner: Option<String>,
+    pub winning_bid_wei: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuctionBid {
+    pub bidder: String,
+    pub amount_wei: String,
+    pub is_eth: bool,
+    pub block_number: i64,
+    pub block_time: Option<DateTime<Utc>>,
+    pub tx_hash: String,
+}
+
+// 某一币种下的最低有效出价
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct MinBid {
+    pub currency: &'static str,
+    pub min_next_bid_wei: String,
+}
+
+// 新出价须严格高于同币种当前最高价，且不低于该币种起拍价
+pub fn min_next_bid(starting: U256, current: Option<U256>) -> U256 {
+    match current {
+        Some(c) => c.saturating_add(U256::from(1u8)).max(starting),
+        None => starting,
+    }
+}
+
+// 当前出价币种之外的币种无法与之直接比较，只给出起拍价
+pub fn min_bids(starting: U256, eth_starting: U256, accepts_eth: bool, current: Option<(U256, bool)>) -> Vec<MinBid> {
+    let same = |is_eth: bool| current.filter(|(_, e)| *e == is_eth).map(|(v, _)| v);
+    let mut out = vec![MinBid {
+        currency: "token",
+        min_next_bid_wei: min_next_bid(starting, same(false)).to_string(),
+    }];
+    if accepts_eth {
+        out.push(MinBid {
+            currency: "eth",
+            min_next_bid_wei: min_next_bid(eth_starting, same(true)).to_string(),
+        });
+    }
+    out
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct AntiSniping {
+    pub extension_secs: u64,
+    pub ends_at: DateTime<Utc>,
+    // 当前是否处于结束前的延时窗口
+    pub in_extension_window: bool,
+    // 若此刻出价成功，拍卖的结束时间
+    pub ends_at_if_bid_now: DateTime<Utc>,
+    pub rule: String,
+}
+
+pub fn anti_sniping(ends_at: DateTime<Utc>, extension_secs: u64, now: DateTime<Utc>) -> AntiSniping {
+    let ext = Duration::seconds(extension_secs as i64);
+    let in_window = extension_secs > 0 && now < ends_at && now >= ends_at - ext;
+    AntiSniping {
+        extension_secs,
+        ends_at,
+        in_extension_window: in_window,
+        ends_at_if_bid_now: if in_window { now + ext } else { ends_at },
+        rule: format!(
+            "A bid placed within the last {extension_secs} seconds before the auction ends extends the end time to {extension_secs} seconds after that bid."
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn min_bid_is_above_current_and_starting() {
+        let s = U256::from(100u64);
+        assert_eq!(min_next_bid(s, None), s);
+        assert_eq!(min_next_bid(s, Some(U256::from(150u64))), U256::from(151u64));
+        let bids = min_bids(s, U256::from(5u64), true, Some((U256::from(7u64), true)));
+        assert_eq!(bids[0].min_next_bid_wei, "100");
+        assert_eq!(bids[1], MinBid { currency: "eth", min_next_bid_wei: "8".into() });
+        assert_eq!(min_bids(s, U256::ZERO, false, None).len(), 1);
+    }
+
+    #[test]
+    fn extension_window() {
+        let end = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let early = anti_sniping(end, 300, end - Duration::seconds(600));
+        assert!(!early.in_extension_window);
+        assert_eq!(early.ends_at_if_bid_now, end);
+        let late = anti_sniping(end, 300, end - Duration::seconds(60));
+        assert!(late.in_extension_window);
+        assert_eq!(late.ends_at_if_bid_now, end + Duration::seconds(240));
+    }
+}
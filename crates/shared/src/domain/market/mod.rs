@@ -0,0 +1,185 @@
+pub mod auction;
+pub mod policy;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 二级市场卖单的 side；链上挂单恒为此值，链下挂单的其他 side（一级发售等）不计入地板价与深度
+pub const SIDE_SECONDARY: &str = "secondary";
+
+// 与 Marketplace.ListingStatus 枚举顺序保持一致
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ListingStatus {
+    Active,
+    Sold,
+    Cancelled,
+    Expired,
+}
+
+impl ListingStatus {
+    pub fn from_chain(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Active),
+            1 => Some(Self::Sold),
+            2 => Some(Self::Cancelled),
+            3 => Some(Self::Expired),
+            _ => None,
+        }
+    }
+
+    // marketplace_listings.status 存放链上枚举值
+    pub fn to_chain(self) -> i16 {
+        self as i16
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Sold => "sold",
+            Self::Cancelled => "cancelled",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+impl std::str::FromStr for ListingStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "active" => Ok(Self::Active),
+            "sold" => Ok(Self::Sold),
+            "cancelled" => Ok(Self::Cancelled),
+            "expired" => Ok(Self::Expired),
+            _ => Err(()),
+        }
+    }
+}
+
+// v_market_listings 的一行；id 对链下挂单带 "off-" 前缀
+#[derive(Debug, Serialize, Clone)]
+pub struct MarketListing {
+    pub id: String,
+    pub source: String,
+    pub token_id: i64,
+    pub event_id: Option<i64>,
+    pub type_id: Option<i64>,
+    pub seller: String,
+    pub price_wei: String,
+    pub eth_price_wei: Option<String>,
+    pub side: String,
+    pub status: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ListingFilter {
+    pub event_id: Option<i64>,
+    pub type_id: Option<i64>,
+    pub seller: Option<String>,
+    // 价格区间 [min_price, max_price]，wei 十进制字符串
+    pub min_price: Option<String>,
+    pub max_price: Option<String>,
+    pub status: Option<ListingStatus>,
+    pub side: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListingSort {
+    #[default]
+    PriceAsc,
+    PriceDesc,
+    CreatedDesc,
+}
+
+impl std::str::FromStr for ListingSort {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "price" => Ok(Self::PriceAsc),
+            "-price" => Ok(Self::PriceDesc),
+            "-created_at" => Ok(Self::CreatedDesc),
+            _ => Err(()),
+        }
+    }
+}
+
+// keyset 游标："<排序键>_<id>"，价格排序时键为 wei，时间排序时为微秒时间戳
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListingCursor {
+    pub key: String,
+    pub id: String,
+}
+
+impl ListingCursor {
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.key, self.id)
+    }
+
+    pub fn decode(s: &str) -> Option<Self> {
+        let (k, id) = s.split_once('_')?;
+        if k.is_empty() || !k.bytes().all(|b| b.is_ascii_digit()) || id.is_empty() {
+            return None;
+        }
+        Some(Self { key: k.to_string(), id: id.to_string() })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListingPage {
+    pub items: Vec<MarketListing>,
+    pub next_cursor: Option<String>,
+}
+
+// 每个票种的地板价（仅 active 的二级市场卖单）
+#[derive(Debug, Serialize, Clone)]
+pub struct FloorPrice {
+    pub event_id: i64,
+    pub type_id: i64,
+    pub floor_price_wei: String,
+    pub active_listings: i64,
+}
+
+// 挂单深度：按价格分桶 [price_from_wei, price_to_wei)
+#[derive(Debug, Serialize, Clone)]
+pub struct DepthBucket {
+    pub price_from_wei: String,
+    pub price_to_wei: String,
+    pub listings: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Sale {
+    pub listing_id: String,
+    pub token_id: Option<i64>,
+    pub event_id: Option<i64>,
+    pub type_id: Option<i64>,
+    pub buyer: Option<String>,
+    pub seller: Option<String>,
+    pub price_wei: Option<String>,
+    pub paid_with_eth: Option<bool>,
+    pub tx_hash: String,
+    pub block_number: Option<i64>,
+    pub block_time: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrip() {
+        let c = ListingCursor { key: "1000000000000000000".into(), id: "off-12".into() };
+        assert_eq!(ListingCursor::decode(&c.encode()), Some(c));
+        assert_eq!(ListingCursor::decode("abc_1"), None);
+        assert_eq!(ListingCursor::decode("12_"), None);
+    }
+
+    #[test]
+    fn status_matches_chain_enum() {
+        assert_eq!(ListingStatus::from_chain(0), Some(ListingStatus::Active));
+        assert_eq!(ListingStatus::Cancelled.to_chain(), 2);
+        assert_eq!("sold".parse(), Ok(ListingStatus::Sold));
+    }
+}
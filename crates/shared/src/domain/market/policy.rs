@@ -0,0 +1,199 @@
+// 转售政策（防黄牛）：按活动配置的二级市场限价与单钱包挂单数上限
+// 构建 createListing/updateListing 时强制执行；链上绕过 API 的挂单由索引器投影后标记
+use alloy::primitives::U256;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 万分比基数：max_price_bps = 12000 即不超过面值的 120%
+pub const BPS: u32 = 10_000;
+// 限价下限为面值本身，上限防止误填（100 倍面值）
+pub const MIN_PRICE_BPS: i32 = BPS as i32;
+pub const MAX_PRICE_BPS: i32 = 100 * BPS as i32;
+
+// 代币价与 ETH 价分开记录，同一挂单两者都超限时各有一条标记
+pub const RULE_PRICE_CAP: &str = "RESALE_PRICE_CAP_EXCEEDED";
+pub const RULE_ETH_PRICE_CAP: &str = "RESALE_ETH_PRICE_CAP_EXCEEDED";
+// 配置了限价但无法确定面值时拒绝（票种未索引，或票种不支持 ETH 却以 ETH 挂单）
+pub const RULE_FACE_VALUE_UNKNOWN: &str = "RESALE_FACE_VALUE_UNKNOWN";
+pub const RULE_ETH_FACE_VALUE_UNKNOWN: &str = "RESALE_ETH_FACE_VALUE_UNKNOWN";
+pub const RULE_LISTING_LIMIT: &str = "LISTING_LIMIT_EXCEEDED";
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct ResalePolicy {
+    pub event_id: i64,
+    pub max_price_bps: Option<i32>,
+    pub max_listings_per_wallet: Option<i32>,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// 组织者提交的政策；字段为空表示不限制
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ResalePolicyInput {
+    #[serde(default)]
+    pub max_price_bps: Option<i32>,
+    #[serde(default)]
+    pub max_listings_per_wallet: Option<i32>,
+}
+
+impl ResalePolicyInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(b) = self.max_price_bps {
+            if !(MIN_PRICE_BPS..=MAX_PRICE_BPS).contains(&b) {
+                return Err(format!("max_price_bps must be within [{MIN_PRICE_BPS}, {MAX_PRICE_BPS}]"));
+            }
+        }
+        if self.max_listings_per_wallet.is_some_and(|n| n < 1) {
+            return Err("max_listings_per_wallet must be positive".into());
+        }
+        Ok(())
+    }
+}
+
+// 待校验的挂单：面值取自票种（链上 getTicketTypeInfo），未知时为 None
+#[derive(Debug, Clone)]
+pub struct ListingCheck {
+    pub face_value: Option<U256>,
+    pub eth_face_value: Option<U256>,
+    pub price: U256,
+    pub eth_price: U256,
+    // 同一卖家在该活动下的其他进行中挂单数（不含本挂单）
+    pub other_active_listings: i64,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+// 索引器标记的违规挂单
+#[derive(Debug, Serialize, Clone)]
+pub struct ListingViolation {
+    pub listing_id: String,
+    pub token_id: Option<i64>,
+    pub seller: Option<String>,
+    pub price_wei: Option<String>,
+    pub rule: String,
+    pub detail: String,
+    pub detected_block: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn price_cap(face_value: U256, bps: i32) -> U256 {
+    face_value.saturating_mul(U256::from(bps.max(0) as u32)) / U256::from(BPS)
+}
+
+impl ResalePolicy {
+    pub fn check(&self, l: &ListingCheck) -> Vec<Violation> {
+        let mut out = Vec::new();
+        if let Some(bps) = self.max_price_bps {
+            match l.face_value {
+                Some(face) => {
+                    let cap = price_cap(face, bps);
+                    if l.price > cap {
+                        out.push(Violation {
+                            rule: RULE_PRICE_CAP,
+                            message: format!("price {} exceeds the resale cap of {cap} wei ({bps} bps of face value)", l.price),
+                        });
+                    }
+                }
+                None => out.push(Violation {
+                    rule: RULE_FACE_VALUE_UNKNOWN,
+                    message: "face value of this ticket is unknown, so the resale price cap cannot be checked".into(),
+                }),
+            }
+            // eth_price 为 0 表示不接受 ETH；ETH 面值为 0 说明票种不支持 ETH，同样无法限价
+            if !l.eth_price.is_zero() {
+                match l.eth_face_value.filter(|v| !v.is_zero()) {
+                    Some(eth_face) => {
+                        let cap = price_cap(eth_face, bps);
+                        if l.eth_price > cap {
+                            out.push(Violation {
+                                rule: RULE_ETH_PRICE_CAP,
+                                message: format!("eth price {} exceeds the resale cap of {cap} wei ({bps} bps of face value)", l.eth_price),
+                            });
+                        }
+                    }
+                    None => out.push(Violation {
+                        rule: RULE_ETH_FACE_VALUE_UNKNOWN,
+                        message: "ticket type has no ETH face value, so an ETH price cannot be capped".into(),
+                    }),
+                }
+            }
+        }
+        if let Some(max) = self.max_listings_per_wallet {
+            if l.other_active_listings >= max as i64 {
+                out.push(Violation {
+                    rule: RULE_LISTING_LIMIT,
+                    message: format!("seller already has {} active listings for this event (limit {max})", l.other_active_listings),
+                });
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(bps: Option<i32>, max: Option<i32>) -> ResalePolicy {
+        ResalePolicy {
+            event_id: 1,
+            max_price_bps: bps,
+            max_listings_per_wallet: max,
+            updated_by: "0xabc".into(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn listing(price: u64, eth_price: u64, others: i64) -> ListingCheck {
+        ListingCheck {
+            face_value: Some(U256::from(100u64)),
+            eth_face_value: Some(U256::from(10u64)),
+            price: U256::from(price),
+            eth_price: U256::from(eth_price),
+            other_active_listings: others,
+        }
+    }
+
+    #[test]
+    fn price_cap_is_inclusive() {
+        let p = policy(Some(12_000), None);
+        assert!(p.check(&listing(120, 12, 0)).is_empty());
+        let v = p.check(&listing(121, 13, 0));
+        let rules: Vec<_> = v.iter().map(|v| v.rule).collect();
+        assert_eq!(rules, [RULE_PRICE_CAP, RULE_ETH_PRICE_CAP]);
+    }
+
+    #[test]
+    fn unknown_face_value_fails_closed() {
+        let p = policy(Some(12_000), None);
+        let mut l = listing(100, 5, 0);
+        l.face_value = None;
+        l.eth_face_value = None;
+        let rules: Vec<_> = p.check(&l).iter().map(|v| v.rule).collect();
+        assert_eq!(rules, [RULE_FACE_VALUE_UNKNOWN, RULE_ETH_FACE_VALUE_UNKNOWN]);
+        // 不以 ETH 挂单时无需 ETH 面值
+        l.face_value = Some(U256::from(100u64));
+        l.eth_price = U256::ZERO;
+        assert!(p.check(&l).is_empty());
+        // 未配置限价时不需要面值
+        assert!(policy(None, None).check(&listing(1_000, 1_000, 0)).is_empty());
+    }
+
+    #[test]
+    fn listing_limit_counts_other_listings() {
+        let p = policy(None, Some(2));
+        assert!(p.check(&listing(1_000, 0, 1)).is_empty());
+        assert_eq!(p.check(&listing(1_000, 0, 2))[0].rule, RULE_LISTING_LIMIT);
+    }
+
+    #[test]
+    fn input_validation() {
+        assert!(ResalePolicyInput { max_price_bps: Some(9_999), ..Default::default() }.validate().is_err());
+        assert!(ResalePolicyInput { max_listings_per_wallet: Some(0), ..Default::default() }.validate().is_err());
+        assert!(ResalePolicyInput { max_price_bps: Some(12_000), max_listings_per_wallet: Some(4) }.validate().is_ok());
+    }
+}
@@ -0,0 +1,223 @@
+// 闪电贷监控：TokenSwap.flashLoan 不发专门事件，但会以 ReservesUpdated 结算储备
+// 同一交易内 ReservesUpdated 多于 Swap / 流动性存取次数即视为疑似闪电贷，再用调用数据或 callTracer 轨迹确认
+use alloy::primitives::{Address, Bytes, U256};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::FEE_DENOMINATOR;
+
+// 交易内价格相对交易前偏离超过该值（万分比）即告警
+pub const PRICE_MOVE_ALERT_BPS: u32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    PriceMove,
+    MarketplaceSale,
+}
+
+impl AlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PriceMove => "price_move",
+            Self::MarketplaceSale => "marketplace_sale",
+        }
+    }
+}
+
+// 检测来源：calldata 为直接调用 flashLoan；trace 为调用轨迹中的内部调用；logs 仅凭事件推断（借款方取交易目标合约）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Calldata,
+    Trace,
+    Logs,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Calldata => "calldata",
+            Self::Trace => "trace",
+            Self::Logs => "logs",
+        }
+    }
+
+    // 仅凭事件推断的记录只算疑似
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::Calldata | Self::Trace => "confirmed",
+            Self::Logs => "suspected",
+        }
+    }
+}
+
+// 单笔交易内 TokenSwap 日志计数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TxActivity {
+    pub reserve_updates: usize,
+    pub swaps: usize,
+    pub liquidity_ops: usize,
+}
+
+impl TxActivity {
+    pub fn suspected_flash_loan(&self) -> bool {
+        self.reserve_updates > self.swaps + self.liquidity_ops
+    }
+}
+
+// debug_traceTransaction callTracer 输出（仅取用到的字段）
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub from: Address,
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub input: Bytes,
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+// 同一交易内的其他合约调用，存为 JSONB
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TracedCall {
+    pub depth: u32,
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    // 4 字节函数选择器；无调用数据（纯转账）时为空
+    pub selector: Option<String>,
+}
+
+// 轨迹中所有以 selector 调用 pool 的帧
+pub fn find_calls<'a>(root: &'a CallFrame, pool: Address, selector: [u8; 4]) -> Vec<&'a CallFrame> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(f) = stack.pop() {
+        if f.to == Some(pool) && f.input.get(..4) == Some(&selector[..]) {
+            out.push(f);
+        }
+        stack.extend(f.calls.iter().rev());
+    }
+    out
+}
+
+// 按调用顺序展开，排除对 pool 自身的调用
+pub fn external_calls(root: &CallFrame, pool: Address) -> Vec<TracedCall> {
+    fn walk(f: &CallFrame, depth: u32, pool: Address, out: &mut Vec<TracedCall>) {
+        if let Some(to) = f.to.filter(|t| *t != pool) {
+            out.push(TracedCall {
+                depth,
+                kind: f.kind.to_lowercase(),
+                from: format!("{:#x}", f.from),
+                to: format!("{to:#x}"),
+                selector: f.input.get(..4).map(|s| format!("0x{}", alloy::hex::encode(s))),
+            });
+        }
+        for c in &f.calls {
+            walk(c, depth + 1, pool, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(root, 0, pool, &mut out);
+    out
+}
+
+// 金额为 wei 十进制字符串
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashLoan {
+    pub tx_hash: String,
+    pub block_number: i64,
+    pub tx_from: String,
+    pub borrower: String,
+    pub token_amount_wei: Option<String>,
+    pub eth_amount_wei: Option<String>,
+    pub source: String,
+    // confirmed | suspected
+    pub status: String,
+    pub calls: serde_json::Value,
+    pub max_price_move_bps: Option<i32>,
+    pub detected_at: DateTime<Utc>,
+    pub alerts: Vec<FlashLoanAlert>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashLoanAlert {
+    pub kind: String,
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+// 同区块成交与闪电贷的关联：同一交易，或买方 / 卖方为借款方、出现在调用轨迹中；无关联返回 None
+pub fn sale_link(sale_tx: &str, parties: &[Option<&str>], tx_hash: &str, borrower: &str, calls: &[TracedCall]) -> Option<&'static str> {
+    if sale_tx == tx_hash {
+        return Some("same_tx");
+    }
+    let known = || parties.iter().flatten();
+    if known().any(|p| p.eq_ignore_ascii_case(borrower)) {
+        return Some("borrower");
+    }
+    known()
+        .any(|p| calls.iter().any(|c| c.from.eq_ignore_ascii_case(p) || c.to.eq_ignore_ascii_case(p)))
+        .then_some("traced_call")
+}
+
+// 交易内各价格相对交易前价格的最大偏离
+pub fn price_move_bps(before: U256, prices: &[U256]) -> u32 {
+    if before.is_zero() {
+        return 0;
+    }
+    prices
+        .iter()
+        .map(|p| {
+            let diff = if *p > before { *p - before } else { before - *p };
+            (diff.saturating_mul(U256::from(FEE_DENOMINATOR)) / before).saturating_to::<u32>()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(to: Address, input: &[u8], calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame { kind: "CALL".into(), from: Address::repeat_byte(1), to: Some(to), input: Bytes::copy_from_slice(input), calls }
+    }
+
+    #[test]
+    fn detects_nested_flash_loan() {
+        let pool = Address::repeat_byte(9);
+        let market = Address::repeat_byte(7);
+        let sel = [0xde, 0xad, 0xbe, 0xef];
+        let root = frame(
+            Address::repeat_byte(2),
+            &[1, 2, 3, 4],
+            vec![frame(pool, &[0xde, 0xad, 0xbe, 0xef, 0], vec![frame(market, &[5, 6, 7, 8], vec![]), frame(pool, &[0, 0, 0, 1], vec![])])],
+        );
+        assert_eq!(find_calls(&root, pool, sel).len(), 1);
+        let calls = external_calls(&root, pool);
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[1].depth, calls[1].selector.as_deref()), (2, Some("0x05060708")));
+        assert_eq!(calls[1].to, format!("{market:#x}"));
+    }
+
+    #[test]
+    fn links_only_related_sales() {
+        let calls = vec![TracedCall { depth: 1, kind: "call".into(), from: "0xaa".into(), to: "0xbb".into(), selector: None }];
+        assert_eq!(sale_link("0x1", &[None, None], "0x1", "0xcc", &[]), Some("same_tx"));
+        assert_eq!(sale_link("0x2", &[Some("0xCC"), None], "0x1", "0xcc", &[]), Some("borrower"));
+        assert_eq!(sale_link("0x2", &[Some("0xdd"), Some("0xbb")], "0x1", "0xcc", &calls), Some("traced_call"));
+        assert_eq!(sale_link("0x2", &[Some("0xdd"), None], "0x1", "0xcc", &calls), None);
+        assert_eq!(Source::Logs.status(), "suspected");
+    }
+
+    #[test]
+    fn activity_and_price_move() {
+        assert!(TxActivity { reserve_updates: 2, swaps: 1, liquidity_ops: 0 }.suspected_flash_loan());
+        assert!(!TxActivity { reserve_updates: 1, swaps: 1, liquidity_ops: 0 }.suspected_flash_loan());
+        let p = |v: u64| U256::from(v);
+        assert_eq!(price_move_bps(p(1_000), &[p(1_020), p(900), p(1_000)]), 1_000);
+        assert_eq!(price_move_bps(U256::ZERO, &[p(1)]), 0);
+        assert_eq!(price_move_bps(p(1), &[]), 0);
+    }
+}
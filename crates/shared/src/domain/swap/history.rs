@@ -0,0 +1,152 @@
+// 价格历史：PriceUpdated 观测值的时间加权均价（TWAP）与基于成交的 OHLCV K 线
+// K 线价格为 1e18 个代币最小单位对应的 ETH wei（即 18 位精度下每枚代币的 ETH 价）
+use alloy::primitives::U256;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+pub const PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+// 单次请求的最多 K 线根数
+pub const MAX_CANDLES: i64 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandleInterval {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl CandleInterval {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Self::Minute => 60,
+            Self::Hour => 3_600,
+            Self::Day => 86_400,
+        }
+    }
+
+    // Postgres date_trunc 精度
+    pub fn trunc_unit(&self) -> &'static str {
+        match self {
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
+
+impl std::str::FromStr for CandleInterval {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "1m" => Ok(Self::Minute),
+            "1h" => Ok(Self::Hour),
+            "1d" => Ok(Self::Day),
+            _ => Err(()),
+        }
+    }
+}
+
+// 金额与价格均为 wei 十进制字符串
+#[derive(Debug, Serialize, Clone)]
+pub struct Candle {
+    pub start: DateTime<Utc>,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume_eth_wei: String,
+    pub volume_token_wei: String,
+    pub trades: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceObservation {
+    pub at: DateTime<Utc>,
+    pub token_price: U256,
+    pub eth_price: U256,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Twap {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub avg_token_price: String,
+    pub avg_eth_price: String,
+    // 有观测值覆盖的秒数；窗口起点早于首个观测时小于窗口长度
+    pub covered_secs: i64,
+    pub observations: usize,
+}
+
+// 成交价：ETH 数量 * 1e18 / 代币数量
+pub fn swap_price(eth_amount: U256, token_amount: U256) -> Option<U256> {
+    if token_amount.is_zero() {
+        return None;
+    }
+    Some(eth_amount.saturating_mul(U256::from(PRICE_SCALE)) / token_amount)
+}
+
+// obs 按时间升序，可包含窗口起点之前的最后一个观测（作为起始价格）
+// 每个观测值持续到下一个观测或窗口终点
+pub fn twap(obs: &[PriceObservation], from: DateTime<Utc>, to: DateTime<Utc>) -> Option<Twap> {
+    let (mut sum_token, mut sum_eth, mut covered) = (U256::ZERO, U256::ZERO, 0i64);
+    let mut used = 0;
+    for (i, o) in obs.iter().enumerate() {
+        let start = o.at.max(from);
+        let end = obs.get(i + 1).map(|n| n.at).unwrap_or(to).min(to);
+        let secs = (end - start).num_seconds();
+        if secs <= 0 {
+            continue;
+        }
+        let w = U256::from(secs as u64);
+        sum_token = sum_token.saturating_add(o.token_price.saturating_mul(w));
+        sum_eth = sum_eth.saturating_add(o.eth_price.saturating_mul(w));
+        covered += secs;
+        used += 1;
+    }
+    if covered == 0 {
+        return None;
+    }
+    let c = U256::from(covered as u64);
+    Some(Twap {
+        from,
+        to,
+        avg_token_price: (sum_token / c).to_string(),
+        avg_eth_price: (sum_eth / c).to_string(),
+        covered_secs: covered,
+        observations: used,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn obs(t: DateTime<Utc>, p: u64) -> PriceObservation {
+        PriceObservation { at: t, token_price: U256::from(p), eth_price: U256::from(p * 2) }
+    }
+
+    #[test]
+    fn time_weighted_average() {
+        let t0 = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        // 窗口前的观测 100 持续到 t0+30s，之后 200 持续到窗口结束
+        let v = [obs(t0 - Duration::seconds(60), 100), obs(t0 + Duration::seconds(30), 200)];
+        let r = twap(&v, t0, t0 + Duration::seconds(60)).unwrap();
+        assert_eq!(r.avg_token_price, "150");
+        assert_eq!(r.avg_eth_price, "300");
+        assert_eq!(r.covered_secs, 60);
+        assert_eq!(r.observations, 2);
+        // 起点前无观测时只统计覆盖部分
+        let r = twap(&v[1..], t0, t0 + Duration::seconds(60)).unwrap();
+        assert_eq!((r.avg_token_price.as_str(), r.covered_secs), ("200", 30));
+        assert!(twap(&[], t0, t0 + Duration::seconds(60)).is_none());
+    }
+
+    #[test]
+    fn swap_price_scale() {
+        let eth = U256::from(PRICE_SCALE / 2);
+        assert_eq!(swap_price(eth, U256::from(PRICE_SCALE)), Some(eth));
+        assert_eq!(swap_price(eth, U256::ZERO), None);
+        assert_eq!("1h".parse(), Ok(CandleInterval::Hour));
+    }
+}
@@ -0,0 +1,176 @@
+// 流动性头寸：LP 余额由 LP 代币 Transfer 汇总（铸造 from 零地址、销毁 to 零地址）
+// 手续费收益按成交当时的 swapFeeRate 估算，扣除协议费后归 LP；钱包份额按每笔成交前的持仓比例折算
+use super::{fee, FEE_DENOMINATOR};
+use alloy::primitives::U256;
+use serde::Serialize;
+
+pub const SECS_PER_YEAR: u64 = 365 * 86_400;
+pub const MAX_WINDOW_DAYS: i64 = 365;
+
+// 同一费率、同一输入币种、同一持仓状态的成交量汇总（wei）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeBucket {
+    pub eth_in: bool,
+    // 成交早于首条 FeeRatesUpdated 记录，费率取当前值
+    pub assumed_rate: bool,
+    pub swap_fee_rate: U256,
+    pub protocol_fee_rate: U256,
+    // 成交前钱包 LP 余额与 LP 总供应
+    pub lp_balance: U256,
+    pub lp_supply: U256,
+    pub volume: U256,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PoolFees {
+    pub eth: U256,
+    pub token: U256,
+}
+
+// 存取记录汇总（LiquidityAdded / LiquidityRemoved）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LpFlows {
+    pub deposited_eth: U256,
+    pub deposited_token: U256,
+    pub withdrawn_eth: U256,
+    pub withdrawn_token: U256,
+    pub adds: i64,
+    pub removes: i64,
+}
+
+// 金额均为 wei 十进制字符串
+#[derive(Debug, Serialize, Clone)]
+pub struct LpPosition {
+    pub wallet: String,
+    pub block: u64,
+    // 链上 balanceOf
+    pub lp_balance: String,
+    pub total_supply: String,
+    // 索引到的 LP 转账能否还原出当前余额；为 false 时手续费收益只覆盖索引起点之后的持仓，会偏低
+    pub history_complete: bool,
+    pub share_bps: u32,
+    // calculateRemoveLiquidity(lp_balance)
+    pub value_eth_wei: String,
+    pub value_token_wei: String,
+    pub deposited_eth_wei: String,
+    pub deposited_token_wei: String,
+    pub withdrawn_eth_wei: String,
+    pub withdrawn_token_wei: String,
+    pub window_days: i64,
+    // 窗口内归属该钱包的手续费估算，按每笔成交前的持仓份额累加
+    pub fees_earned_eth_wei: String,
+    pub fees_earned_token_wei: String,
+    // 窗口内存在早于首条 FeeRatesUpdated 的成交，这部分按当前费率估算
+    pub fee_rate_assumed: bool,
+    // 池子窗口内 LP 手续费折算为 ETH 后的年化收益率
+    pub apr_bps: u32,
+}
+
+// 单笔手续费中归 LP 的部分
+pub fn lp_fee(volume: U256, swap_fee_rate: U256, protocol_fee_rate: U256) -> U256 {
+    let f = fee(volume, swap_fee_rate);
+    f.saturating_sub(fee(f, protocol_fee_rate))
+}
+
+pub fn pool_fees(buckets: &[FeeBucket]) -> PoolFees {
+    buckets.iter().fold(PoolFees::default(), |mut acc, b| {
+        let f = lp_fee(b.volume, b.swap_fee_rate, b.protocol_fee_rate);
+        if b.eth_in {
+            acc.eth = acc.eth.saturating_add(f);
+        } else {
+            acc.token = acc.token.saturating_add(f);
+        }
+        acc
+    })
+}
+
+// 每个分组的 LP 手续费按成交前持仓份额折算后累加
+pub fn wallet_fees(buckets: &[FeeBucket]) -> PoolFees {
+    buckets.iter().fold(PoolFees::default(), |mut acc, b| {
+        let f = pro_rata(lp_fee(b.volume, b.swap_fee_rate, b.protocol_fee_rate), b.lp_balance, b.lp_supply);
+        if b.eth_in {
+            acc.eth = acc.eth.saturating_add(f);
+        } else {
+            acc.token = acc.token.saturating_add(f);
+        }
+        acc
+    })
+}
+
+pub fn share_bps(balance: U256, total_supply: U256) -> u32 {
+    if total_supply.is_zero() {
+        return 0;
+    }
+    (balance.saturating_mul(U256::from(FEE_DENOMINATOR)) / total_supply).saturating_to()
+}
+
+// 按份额折算：amount * balance / total_supply
+pub fn pro_rata(amount: U256, balance: U256, total_supply: U256) -> U256 {
+    if total_supply.is_zero() {
+        return U256::ZERO;
+    }
+    amount.saturating_mul(balance) / total_supply
+}
+
+// 以当前储备比例把代币数量折算为 ETH
+pub fn token_to_eth(amount: U256, reserve_eth: U256, reserve_token: U256) -> U256 {
+    if reserve_token.is_zero() {
+        return U256::ZERO;
+    }
+    amount.saturating_mul(reserve_eth) / reserve_token
+}
+
+// TVL 取 2 倍 ETH 储备；fees_eth 为窗口内 LP 手续费（ETH 计）
+pub fn apr_bps(fees_eth: U256, reserve_eth: U256, window_secs: u64) -> u32 {
+    let tvl = reserve_eth.saturating_mul(U256::from(2u64));
+    if tvl.is_zero() || window_secs == 0 {
+        return 0;
+    }
+    let annual = fees_eth.saturating_mul(U256::from(SECS_PER_YEAR)) / U256::from(window_secs);
+    (annual.saturating_mul(U256::from(FEE_DENOMINATOR)) / tvl).saturating_to()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u(v: u64) -> U256 {
+        U256::from(v)
+    }
+
+    fn bucket(eth_in: bool, swap_fee_rate: U256, protocol_fee_rate: U256, volume: U256, lp_balance: u64) -> FeeBucket {
+        FeeBucket { eth_in, assumed_rate: false, swap_fee_rate, protocol_fee_rate, lp_balance: u(lp_balance), lp_supply: u(100), volume }
+    }
+
+    #[test]
+    fn fees_split_by_input_side() {
+        // 0.3% 手续费，其中 10% 为协议费
+        assert_eq!(lp_fee(u(10_000), u(30), u(1_000)), u(27));
+        let buckets = [
+            bucket(true, u(30), u(1_000), u(10_000), 0),
+            bucket(true, u(50), u(0), u(10_000), 0),
+            bucket(false, u(30), u(0), u(100_000), 0),
+        ];
+        assert_eq!(pool_fees(&buckets), PoolFees { eth: u(77), token: u(300) });
+    }
+
+    #[test]
+    fn wallet_fees_follow_share_at_swap_time() {
+        // 前一半成交时持有 50%，之后全部转出
+        let buckets = [bucket(false, u(30), u(0), u(100_000), 50), bucket(false, u(30), u(0), u(100_000), 0)];
+        assert_eq!(pool_fees(&buckets).token, u(600));
+        assert_eq!(wallet_fees(&buckets), PoolFees { eth: U256::ZERO, token: u(150) });
+    }
+
+    #[test]
+    fn share_and_apr() {
+        assert_eq!(share_bps(u(25), u(100)), 2_500);
+        assert_eq!(share_bps(u(1), U256::ZERO), 0);
+        assert_eq!(pro_rata(u(300), u(25), u(100)), u(75));
+        assert_eq!(token_to_eth(u(1_000), u(1), u(100)), u(10));
+        // 一周 LP 手续费 1 ETH，TVL 2 * 52 ETH -> 约 50%
+        let eth = u(1_000_000_000_000_000_000);
+        assert_eq!(apr_bps(eth, eth * u(52), 7 * 86_400), 5_013);
+        assert_eq!(apr_bps(eth, U256::ZERO, 86_400), 0);
+    }
+}
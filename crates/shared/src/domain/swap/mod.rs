@@ -0,0 +1,187 @@
+// TokenSwap 恒定乘积池：本地报价（扣手续费后的输出量、价格冲击、滑点保护下的最少到手量）
+// 费率按万分比计：swapFeeRate = 30 即 0.3%；协议费从手续费中抽取，不影响兑换方到手数量
+pub mod flash;
+pub mod history;
+pub mod lp;
+
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+
+pub const FEE_DENOMINATOR: u64 = 10_000;
+pub const MAX_SLIPPAGE_BPS: u32 = 5_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    EthToToken,
+    TokenToEth,
+}
+
+impl Direction {
+    // in_token / out_token 取值 eth | token
+    pub fn from_pair(in_token: &str, out_token: &str) -> Option<Self> {
+        match (in_token, out_token) {
+            ("eth", "token") => Some(Self::EthToToken),
+            ("token", "eth") => Some(Self::TokenToEth),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EthToToken => "eth_to_token",
+            Self::TokenToEth => "token_to_eth",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolState {
+    pub reserve_eth: U256,
+    pub reserve_token: U256,
+    pub swap_fee_rate: U256,
+    pub protocol_fee_rate: U256,
+}
+
+impl PoolState {
+    pub fn reserves(&self, d: Direction) -> (U256, U256) {
+        match d {
+            Direction::EthToToken => (self.reserve_eth, self.reserve_token),
+            Direction::TokenToEth => (self.reserve_token, self.reserve_eth),
+        }
+    }
+}
+
+// 金额均为 wei 十进制字符串
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub direction: Direction,
+    pub block: u64,
+    pub amount_in_wei: String,
+    pub amount_out_wei: String,
+    pub fee_wei: String,
+    pub protocol_fee_wei: String,
+    pub price_impact_bps: u32,
+    pub slippage_bps: u32,
+    pub min_received_wei: String,
+    pub reserve_in_wei: String,
+    pub reserve_out_wei: String,
+    // 链上 get*AmountOut 结果；与本地计算不一致时以链上为准
+    pub onchain_amount_out_wei: Option<String>,
+    pub reconciled: bool,
+}
+
+fn denom() -> U256 {
+    U256::from(FEE_DENOMINATOR)
+}
+
+pub fn fee(amount_in: U256, fee_rate: U256) -> U256 {
+    amount_in.saturating_mul(fee_rate) / denom()
+}
+
+// out = in' * R_out / (R_in + in')，in' 为扣除手续费后的输入
+pub fn amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256, fee_rate: U256) -> U256 {
+    let net = amount_in.saturating_sub(fee(amount_in, fee_rate));
+    let den = reserve_in.saturating_add(net);
+    if den.is_zero() {
+        return U256::ZERO;
+    }
+    net.saturating_mul(reserve_out) / den
+}
+
+// 相对现价的冲击（不含手续费）：in' / (R_in + in')
+pub fn price_impact_bps(amount_in: U256, reserve_in: U256, fee_rate: U256) -> u32 {
+    let net = amount_in.saturating_sub(fee(amount_in, fee_rate));
+    let den = reserve_in.saturating_add(net);
+    if den.is_zero() {
+        return 0;
+    }
+    (net.saturating_mul(denom()) / den).saturating_to()
+}
+
+pub fn min_received(amount_out: U256, slippage_bps: u32) -> U256 {
+    amount_out.saturating_mul(U256::from(FEE_DENOMINATOR.saturating_sub(slippage_bps as u64))) / denom()
+}
+
+// 池子无流动性时无法报价
+pub fn quote(pool: &PoolState, d: Direction, amount_in: U256, slippage_bps: u32, block: u64) -> Option<SwapQuote> {
+    let (r_in, r_out) = pool.reserves(d);
+    if r_in.is_zero() || r_out.is_zero() {
+        return None;
+    }
+    let fee_wei = fee(amount_in, pool.swap_fee_rate);
+    let out = amount_out(amount_in, r_in, r_out, pool.swap_fee_rate);
+    Some(SwapQuote {
+        direction: d,
+        block,
+        amount_in_wei: amount_in.to_string(),
+        amount_out_wei: out.to_string(),
+        fee_wei: fee_wei.to_string(),
+        protocol_fee_wei: fee(fee_wei, pool.protocol_fee_rate).to_string(),
+        price_impact_bps: price_impact_bps(amount_in, r_in, pool.swap_fee_rate),
+        slippage_bps,
+        min_received_wei: min_received(out, slippage_bps).to_string(),
+        reserve_in_wei: r_in.to_string(),
+        reserve_out_wei: r_out.to_string(),
+        onchain_amount_out_wei: None,
+        reconciled: false,
+    })
+}
+
+impl SwapQuote {
+    // 以链上结果校正；不一致时输出量与最少到手量改用链上值
+    pub fn reconcile(&mut self, onchain_out: U256) {
+        let local: U256 = self.amount_out_wei.parse().unwrap_or_default();
+        self.reconciled = local == onchain_out;
+        if !self.reconciled {
+            self.amount_out_wei = onchain_out.to_string();
+            self.min_received_wei = min_received(onchain_out, self.slippage_bps).to_string();
+        }
+        self.onchain_amount_out_wei = Some(onchain_out.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> PoolState {
+        PoolState {
+            reserve_eth: U256::from(1_000u64),
+            reserve_token: U256::from(1_000_000u64),
+            swap_fee_rate: U256::from(30u64),
+            protocol_fee_rate: U256::from(1_000u64),
+        }
+    }
+
+    #[test]
+    fn constant_product_with_fee() {
+        let q = quote(&pool(), Direction::EthToToken, U256::from(100u64), 50, 1).unwrap();
+        // in' = 100 - 0 (费用向下取整为 0) -> 100 * 1e6 / 1100
+        assert_eq!(q.amount_out_wei, "90909");
+        assert_eq!(q.price_impact_bps, 909);
+        assert_eq!(q.min_received_wei, "90454");
+        let q = quote(&pool(), Direction::TokenToEth, U256::from(10_000u64), 0, 1).unwrap();
+        // fee = 30, in' = 9970 -> 9970 * 1000 / 1009970
+        assert_eq!(q.fee_wei, "30");
+        assert_eq!(q.protocol_fee_wei, "3");
+        assert_eq!(q.amount_out_wei, "9");
+    }
+
+    #[test]
+    fn reconcile_prefers_chain() {
+        let mut q = quote(&pool(), Direction::EthToToken, U256::from(100u64), 100, 1).unwrap();
+        q.reconcile(U256::from(90_909u64));
+        assert!(q.reconciled);
+        q.reconcile(U256::from(90_000u64));
+        assert!(!q.reconciled);
+        assert_eq!(q.min_received_wei, "89100");
+        assert!(quote(&PoolState { reserve_eth: U256::ZERO, ..pool() }, Direction::EthToToken, U256::from(1u64), 0, 1).is_none());
+    }
+
+    #[test]
+    fn direction_from_pair() {
+        assert_eq!(Direction::from_pair("eth", "token"), Some(Direction::EthToToken));
+        assert_eq!(Direction::from_pair("eth", "eth"), None);
+    }
+}
@@ -0,0 +1,110 @@
+// 门票 NFT 图像：按 tickets.status 动态渲染 SVG（已使用/已取消/已过期加水印）
+use super::TicketDetail;
+
+pub const WIDTH: u32 = 600;
+pub const HEIGHT: u32 = 300;
+
+// 文本写入 SVG 前转义，活动名称来自组织者输入
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// 过长文本截断，避免溢出票面
+fn clip(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let mut out: String = s.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+// (水印文字, 颜色)；有效票不加水印
+fn watermark(status: &str) -> Option<(&'static str, &'static str)> {
+    match status {
+        "used" => Some(("USED", "#c0392b")),
+        "cancelled" => Some(("CANCELLED", "#7f8c8d")),
+        "expired" => Some(("EXPIRED", "#7f8c8d")),
+        _ => None,
+    }
+}
+
+pub fn render_svg(t: &TicketDetail) -> String {
+    let title = t.event.title.clone().filter(|s| !s.is_empty()).unwrap_or_else(|| format!("Event #{}", t.event.id));
+    let date = t.event.start_time.format("%Y-%m-%d %H:%M UTC").to_string();
+    let venue = t.event.venue.clone().unwrap_or_default();
+    let seat = t.seat_label.clone().filter(|s| !s.is_empty()).unwrap_or_else(|| "General Admission".into());
+    let kind = t.ticket_type.name.clone().unwrap_or_default();
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="Helvetica, Arial, sans-serif">
+<rect width="{WIDTH}" height="{HEIGHT}" rx="24" fill="#1b1f3b"/>
+<rect x="16" y="16" width="{w}" height="{h}" rx="16" fill="none" stroke="#f5c542" stroke-width="2" stroke-dasharray="8 6"/>
+<text x="40" y="80" font-size="30" font-weight="bold" fill="#ffffff">{title}</text>
+<text x="40" y="120" font-size="18" fill="#c9cbe0">{date}</text>
+<text x="40" y="150" font-size="18" fill="#c9cbe0">{venue}</text>
+<text x="40" y="210" font-size="14" fill="#8a8fb8">SEAT</text>
+<text x="40" y="240" font-size="26" font-weight="bold" fill="#f5c542">{seat}</text>
+<text x="560" y="210" font-size="14" fill="#8a8fb8" text-anchor="end">{kind}</text>
+<text x="560" y="240" font-size="20" fill="#ffffff" text-anchor="end">#{token}</text>
+"##,
+        w = WIDTH - 32,
+        h = HEIGHT - 32,
+        title = escape(&clip(&title, 32)),
+        date = escape(&date),
+        venue = escape(&clip(&venue, 48)),
+        seat = escape(&clip(&seat, 24)),
+        kind = escape(&clip(&kind, 24)),
+        token = t.token_id,
+    );
+    if let Some((text, color)) = watermark(&t.status) {
+        svg.push_str(&format!(
+            r##"<rect width="{WIDTH}" height="{HEIGHT}" rx="24" fill="#000000" fill-opacity="0.45"/>
+<text x="{cx}" y="{cy}" font-size="72" font-weight="bold" fill="{color}" fill-opacity="0.85" text-anchor="middle" dominant-baseline="middle" transform="rotate(-18 {cx} {cy})">{text}</text>
+"##,
+            cx = WIDTH / 2,
+            cy = HEIGHT / 2,
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ticket::sample_ticket;
+
+    fn ticket(status: &str, title: &str) -> TicketDetail {
+        let mut t = sample_ticket();
+        t.status = status.into();
+        t.event.title = Some(title.into());
+        t
+    }
+
+    #[test]
+    fn watermark_follows_status() {
+        assert!(!render_svg(&ticket("valid", "Show")).contains("USED"));
+        assert!(render_svg(&ticket("used", "Show")).contains(">USED</text>"));
+        assert!(render_svg(&ticket("cancelled", "Show")).contains(">CANCELLED</text>"));
+    }
+
+    #[test]
+    fn escapes_untrusted_text() {
+        let svg = render_svg(&ticket("valid", "<script>&\"x\""));
+        assert!(!svg.contains("<script>"));
+        assert!(svg.contains("&lt;script&gt;&amp;&quot;x&quot;"));
+    }
+}
@@ -0,0 +1,92 @@
+// ERC-721 tokenURI 元数据（OpenSea 兼容格式），由数据库中的门票/活动信息生成
+use serde::Serialize;
+
+use super::TicketDetail;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    pub external_url: String,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Attribute {
+    pub trait_type: &'static str,
+    pub value: serde_json::Value,
+    // "date" 时 value 为 unix 秒，钱包按日期渲染
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<&'static str>,
+}
+
+impl Attribute {
+    fn text(trait_type: &'static str, value: impl Into<String>) -> Self {
+        Self { trait_type, value: serde_json::Value::String(value.into()), display_type: None }
+    }
+}
+
+impl TokenMetadata {
+    // base_url 为 API 对外地址，external_url 指向门票详情接口
+    pub fn from_ticket(t: &TicketDetail, image: Option<String>, base_url: &str) -> Self {
+        let title = t.event.title.clone().filter(|s| !s.is_empty()).unwrap_or_else(|| format!("Event #{}", t.event.id));
+        let seat = t.seat_label.clone().filter(|s| !s.is_empty());
+        let name = match &seat {
+            Some(s) => format!("{title} · {s}"),
+            None => format!("{title} · Ticket #{}", t.token_id),
+        };
+        let mut description = format!("Ticket #{} for {title}", t.token_id);
+        if let Some(v) = t.event.venue.as_deref().filter(|v| !v.is_empty()) {
+            description.push_str(&format!(" at {v}"));
+        }
+        description.push_str(&format!(", {}.", t.event.start_time.format("%Y-%m-%d %H:%M UTC")));
+
+        let mut attributes = vec![
+            Attribute::text("Event", title),
+            Attribute {
+                trait_type: "Date",
+                value: t.event.start_time.timestamp().into(),
+                display_type: Some("date"),
+            },
+        ];
+        if let Some(v) = t.event.venue.as_deref().filter(|v| !v.is_empty()) {
+            attributes.push(Attribute::text("Venue", v));
+        }
+        if let Some(n) = t.ticket_type.name.as_deref().filter(|n| !n.is_empty()) {
+            attributes.push(Attribute::text("Ticket Type", n));
+        }
+        if let Some(s) = seat {
+            attributes.push(Attribute::text("Seat", s));
+        }
+        attributes.push(Attribute::text("Status", t.status.clone()));
+
+        Self {
+            name,
+            description,
+            image,
+            external_url: format!("{}/tickets/{}", base_url.trim_end_matches('/'), t.token_id),
+            attributes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ticket::sample_ticket;
+
+    #[test]
+    fn builds_opensea_attributes() {
+        let m = TokenMetadata::from_ticket(&sample_ticket(), None, "https://api.example.com/");
+        assert_eq!(m.name, "Rust Conf · A-12");
+        assert_eq!(m.external_url, "https://api.example.com/tickets/7");
+        let v = serde_json::to_value(&m).unwrap();
+        assert!(v.get("image").is_none());
+        let attrs = v["attributes"].as_array().unwrap();
+        assert!(attrs.contains(&serde_json::json!({"trait_type": "Date", "value": 1748800800, "display_type": "date"})));
+        assert!(attrs.contains(&serde_json::json!({"trait_type": "Status", "value": "used"})));
+        assert!(attrs.contains(&serde_json::json!({"trait_type": "Seat", "value": "A-12"})));
+    }
+}
@@ -0,0 +1,121 @@
+pub mod artwork;
+pub mod metadata;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 与 TicketManager.TicketStatus 枚举顺序保持一致
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TicketStatus {
+    Valid,
+    Used,
+    Cancelled,
+    Expired,
+}
+
+impl TicketStatus {
+    pub fn from_chain(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Valid),
+            1 => Some(Self::Used),
+            2 => Some(Self::Cancelled),
+            3 => Some(Self::Expired),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Used => "used",
+            Self::Cancelled => "cancelled",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+// 门票详情（tickets 为业务投影，owner 以链上 ticket_tokens 为准）
+#[derive(Debug, Serialize, Clone)]
+pub struct TicketDetail {
+    pub token_id: i64,
+    pub owner: String,
+    pub seat_label: Option<String>,
+    pub status: String,
+    pub minted_block: Option<i64>,
+    pub event: TicketEvent,
+    pub ticket_type: TicketTypeRef,
+    pub listing: Option<ActiveListing>,
+    pub transfers: Vec<TicketTransfer>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TicketEvent {
+    pub id: i64,
+    pub chain_event_id: Option<i64>,
+    pub title: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub venue: Option<String>,
+    pub image_uri: Option<String>,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TicketTypeRef {
+    pub id: i64,
+    pub chain_type_id: Option<i64>,
+    pub name: Option<String>,
+    pub price_wei: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TicketTransfer {
+    pub block_number: i64,
+    pub block_time: Option<DateTime<Utc>>,
+    pub tx_hash: String,
+    pub log_index: i32,
+    pub from: String,
+    pub to: String,
+}
+
+// 当前挂单：优先取链上 marketplace_listings，其次链下 market_listings
+#[derive(Debug, Serialize, Clone)]
+pub struct ActiveListing {
+    pub source: &'static str,
+    pub listing_id: String,
+    pub seller: String,
+    pub price_wei: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TicketFilter {
+    pub event_id: Option<i64>,
+    pub status: Option<String>,
+}
+
+// 测试用样例门票，metadata / artwork 的单测按需改字段
+#[cfg(test)]
+pub(crate) fn sample_ticket() -> TicketDetail {
+    use chrono::TimeZone;
+    TicketDetail {
+        token_id: 7,
+        owner: "0xabc".into(),
+        seat_label: Some("A-12".into()),
+        status: "used".into(),
+        minted_block: Some(3),
+        event: TicketEvent {
+            id: 1,
+            chain_event_id: Some(1),
+            title: Some("Rust Conf".into()),
+            start_time: Utc.with_ymd_and_hms(2025, 6, 1, 18, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2025, 6, 1, 22, 0, 0).unwrap(),
+            venue: Some("Hall 1".into()),
+            image_uri: None,
+            status: "published".into(),
+        },
+        ticket_type: TicketTypeRef { id: 2, chain_type_id: Some(0), name: Some("VIP".into()), price_wei: "1".into() },
+        listing: None,
+        transfers: Vec::new(),
+    }
+}
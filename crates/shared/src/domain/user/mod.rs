@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// users.role 存逗号分隔的角色集合（如 "organizer,verifier"），无任何授权时为 "user"。
+// admin 只能手工授予；organizer/verifier/minter 由链上授权同步。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Organizer,
+    Verifier,
+    Minter,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Organizer => "organizer",
+            Role::Verifier => "verifier",
+            Role::Minter => "minter",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "organizer" => Role::Organizer,
+            "verifier" => Role::Verifier,
+            "minter" => Role::Minter,
+            "admin" => Role::Admin,
+            _ => Role::User,
+        }
+    }
+
+    // 解析 users.role；结果不含 User
+    pub fn parse_set(s: &str) -> BTreeSet<Role> {
+        s.split(',')
+            .map(|r| Role::parse(r.trim()))
+            .filter(|r| *r != Role::User)
+            .collect()
+    }
+
+    pub fn format_set(set: &BTreeSet<Role>) -> String {
+        if set.is_empty() {
+            return Role::User.as_str().to_string();
+        }
+        set.iter().map(Role::as_str).collect::<Vec<_>>().join(",")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct User {
+    pub wallet: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn role_set_roundtrip() {
+        let set = Role::parse_set("verifier, organizer,bogus");
+        assert_eq!(set, BTreeSet::from([Role::Organizer, Role::Verifier]));
+        assert_eq!(Role::format_set(&set), "organizer,verifier");
+        assert!(Role::parse_set("user").is_empty());
+        assert_eq!(Role::format_set(&BTreeSet::new()), "user");
+    }
+}
@@ -11,6 +11,8 @@ pub enum ApiError {
     #[error("bad request: {0}")] BadRequest(String),
     #[error("conflict: {0}")] Conflict(String),
     #[error("internal error")] Internal,
+    // 合约业务规则拒绝（回滚原因已解码），code 为稳定的 error_code
+    #[error("{message}")] Contract { code: &'static str, message: String },
 }
 
 #[derive(Serialize)]
@@ -25,6 +27,7 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(m) => (StatusCode::BAD_REQUEST, "BAD_REQUEST", m.clone()),
             ApiError::Conflict(m) => (StatusCode::CONFLICT, "CONFLICT", m.clone()),
             ApiError::Internal => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL", self.to_string()),
+            ApiError::Contract { code, message } => (StatusCode::UNPROCESSABLE_ENTITY, *code, message.clone()),
         };
         let body = axum::Json(ErrorBody { error_code: code, message: msg });
         (status, body).into_response()
@@ -3,9 +3,14 @@ pub mod db;
 pub mod error;
 pub mod domain {
     pub mod event;
+    pub mod market;
+    pub mod swap;
+    pub mod ticket;
+    pub mod user;
 }
 pub mod contracts;
 pub mod repo;
+pub mod security;
 pub mod seed;
 
 pub use config::AppConfig;
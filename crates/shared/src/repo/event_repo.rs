@@ -1,24 +1,214 @@
 use crate::db::pool::Db;
-use crate::domain::event::{Event, NewEvent};
+use crate::domain::event::draft::{EventDraft, STATUS_DRAFT, STATUS_PUBLISHED, STATUS_PUBLISHING};
+use crate::domain::event::{Event, EventCursor, EventFilter, EventSort, EventWithTypes, NewEvent, TicketType};
 use anyhow::Result;
-use sqlx::Row;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
 
 pub struct EventRepo<'a> { pub db: &'a Db }
 
+const EVENT_COLUMNS: &str = "id, organizer_wallet, chain_event_id, start_time, end_time, venue, status, meta, created_at";
+
+// 动态查询无法用 query_as! 校验，手工映射
+fn event_from_row(r: &sqlx::postgres::PgRow) -> Result<Event> {
+    Ok(Event {
+        id: r.try_get("id")?,
+        organizer_wallet: r.try_get("organizer_wallet")?,
+        chain_event_id: r.try_get("chain_event_id")?,
+        start_time: r.try_get("start_time")?,
+        end_time: r.try_get("end_time")?,
+        venue: r.try_get("venue")?,
+        status: r.try_get("status")?,
+        meta: r.try_get("meta")?,
+        created_at: r.try_get("created_at")?,
+    })
+}
+
 impl<'a> EventRepo<'a> {
     pub fn new(db: &'a Db) -> Self { Self { db } }
 
     pub async fn insert(&self, new: &NewEvent<'_>) -> Result<Event> {
-        let rec = sqlx::query!(r#"INSERT INTO events (organizer_wallet, start_time, end_time, venue, status)
-            VALUES ($1,$2,$3,$4,$5) RETURNING id, organizer_wallet, start_time, end_time, venue, status"#,
+        let rec = sqlx::query_as!(Event, r#"INSERT INTO events (organizer_wallet, start_time, end_time, venue, status)
+            VALUES ($1,$2,$3,$4,$5) RETURNING id, organizer_wallet, chain_event_id, start_time, end_time, venue, status, meta, created_at"#,
             new.organizer_wallet, new.start_time, new.end_time, new.venue, new.status)
             .fetch_one(&self.db.0).await?;
-        Ok(Event { id: rec.id, organizer_wallet: rec.organizer_wallet, start_time: rec.start_time, end_time: rec.end_time, venue: rec.venue, status: rec.status })
+        Ok(rec)
     }
 
     pub async fn find_by_id(&self, id: i64) -> Result<Option<Event>> {
-        let rec = sqlx::query!("SELECT id, organizer_wallet, start_time, end_time, venue, status FROM events WHERE id=$1", id)
+        let rec = sqlx::query_as!(Event, "SELECT id, organizer_wallet, chain_event_id, start_time, end_time, venue, status, meta, created_at FROM events WHERE id=$1", id)
+            .fetch_optional(&self.db.0).await?;
+        Ok(rec)
+    }
+
+    pub async fn find_id_by_chain_event(&self, chain_event_id: i64) -> Result<Option<i64>> {
+        let id = sqlx::query_scalar!("SELECT id FROM events WHERE chain_event_id=$1 ORDER BY id LIMIT 1", chain_event_id)
+            .fetch_optional(&self.db.0).await?;
+        Ok(id)
+    }
+
+    pub async fn find_with_types(&self, id: i64) -> Result<Option<EventWithTypes>> {
+        let Some(event) = self.find_by_id(id).await? else { return Ok(None) };
+        let ticket_types = self.ticket_types(&[id]).await?.remove(&id).unwrap_or_default();
+        Ok(Some(EventWithTypes { event, ticket_types }))
+    }
+
+    // 过滤条件动态拼接（QueryBuilder 绑定参数）+ keyset 分页；多取一条判断是否还有下一页
+    pub async fn list(&self, f: &EventFilter, sort: EventSort, after: Option<EventCursor>, limit: i64) -> Result<(Vec<EventWithTypes>, Option<EventCursor>)> {
+        let (key, desc) = match sort {
+            EventSort::StartAsc => ("start_time", false),
+            EventSort::StartDesc => ("start_time", true),
+            EventSort::CreatedDesc => ("created_at", true),
+        };
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(format!("SELECT {EVENT_COLUMNS} FROM events WHERE TRUE"));
+        if let Some(s) = &f.status {
+            qb.push(" AND status = ").push_bind(s.clone());
+        }
+        if f.exclude_drafts {
+            qb.push(" AND status NOT IN (").push_bind(STATUS_DRAFT).push(", ").push_bind(STATUS_PUBLISHING).push(")");
+        }
+        if let Some(o) = &f.organizer {
+            qb.push(" AND organizer_wallet = ").push_bind(o.to_lowercase());
+        }
+        if let Some(t) = f.starts_after {
+            qb.push(" AND start_time >= ").push_bind(t);
+        }
+        if let Some(t) = f.starts_before {
+            qb.push(" AND start_time < ").push_bind(t);
+        }
+        if let Some(v) = &f.venue {
+            let escaped = v.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+            qb.push(" AND venue ILIKE ").push_bind(format!("%{escaped}%"));
+        }
+        if let Some(c) = after {
+            qb.push(format!(" AND ({key}, id) {} (", if desc { "<" } else { ">" }))
+                .push_bind(c.key).push(", ").push_bind(c.id).push(")");
+        }
+        let dir = if desc { "DESC" } else { "ASC" };
+        qb.push(format!(" ORDER BY {key} {dir}, id {dir} LIMIT ")).push_bind(limit + 1);
+
+        let rows = qb.build().fetch_all(&self.db.0).await?;
+        let mut events = rows.iter().map(event_from_row).collect::<Result<Vec<_>>>()?;
+        let next = if events.len() as i64 > limit {
+            events.truncate(limit as usize);
+            events.last().map(|e| EventCursor {
+                key: if key == "created_at" { e.created_at } else { e.start_time },
+                id: e.id,
+            })
+        } else {
+            None
+        };
+
+        let ids: Vec<i64> = events.iter().map(|e| e.id).collect();
+        let mut types = self.ticket_types(&ids).await?;
+        let items = events.into_iter()
+            .map(|event| {
+                let ticket_types = types.remove(&event.id).unwrap_or_default();
+                EventWithTypes { event, ticket_types }
+            })
+            .collect();
+        Ok((items, next))
+    }
+
+    pub async fn ticket_types(&self, event_ids: &[i64]) -> Result<HashMap<i64, Vec<TicketType>>> {
+        let rows = sqlx::query_as!(TicketType, r#"SELECT id, event_id, chain_type_id, price_wei::TEXT AS "price_wei!",
+                supply_total, supply_sold, meta
+            FROM ticket_types WHERE event_id = ANY($1) ORDER BY event_id, chain_type_id NULLS LAST, id"#, event_ids)
+            .fetch_all(&self.db.0).await?;
+        let mut out: HashMap<i64, Vec<TicketType>> = HashMap::new();
+        for t in rows {
+            out.entry(t.event_id).or_default().push(t);
+        }
+        Ok(out)
+    }
+
+    async fn insert_draft_types(tx: &mut sqlx::Transaction<'_, Postgres>, event_id: i64, d: &EventDraft) -> Result<()> {
+        for t in d.ticket_types.iter() {
+            sqlx::query!("INSERT INTO ticket_types (event_id, price_wei, eth_price_wei, supply_total, meta) VALUES ($1,$2::TEXT::NUMERIC,$3::TEXT::NUMERIC,$4,$5)",
+                event_id, t.price_wei, t.eth_price_wei, t.supply, t.meta())
+                .execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn create_draft(&self, organizer: &str, d: &EventDraft) -> Result<i64> {
+        let mut tx = self.db.0.begin().await?;
+        let id = sqlx::query_scalar!(r#"INSERT INTO events (organizer_wallet, start_time, end_time, venue, status, meta)
+            VALUES ($1,$2,$3,$4,$5,$6) RETURNING id"#,
+            organizer, d.start_time, d.end_time, d.venue, STATUS_DRAFT, d.event_meta())
+            .fetch_one(&mut *tx).await?;
+        Self::insert_draft_types(&mut tx, id, d).await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    // 仅 draft 状态可编辑；票种整体替换
+    pub async fn replace_draft(&self, id: i64, organizer: &str, d: &EventDraft) -> Result<bool> {
+        let mut tx = self.db.0.begin().await?;
+        let updated = sqlx::query!(r#"UPDATE events SET start_time=$3, end_time=$4, venue=$5, meta=$6
+            WHERE id=$1 AND organizer_wallet=$2 AND status=$7"#,
+            id, organizer, d.start_time, d.end_time, d.venue, d.event_meta(), STATUS_DRAFT)
+            .execute(&mut *tx).await?.rows_affected();
+        if updated == 0 {
+            return Ok(false);
+        }
+        sqlx::query!("DELETE FROM ticket_types WHERE event_id=$1", id).execute(&mut *tx).await?;
+        Self::insert_draft_types(&mut tx, id, d).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn delete_draft(&self, id: i64, organizer: &str) -> Result<bool> {
+        let mut tx = self.db.0.begin().await?;
+        let found = sqlx::query_scalar!("SELECT id FROM events WHERE id=$1 AND organizer_wallet=$2 AND status=$3 FOR UPDATE",
+            id, organizer, STATUS_DRAFT)
+            .fetch_optional(&mut *tx).await?;
+        if found.is_none() {
+            return Ok(false);
+        }
+        sqlx::query!("DELETE FROM ticket_types WHERE event_id=$1", id).execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM events WHERE id=$1", id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn mark_publishing(&self, id: i64) -> Result<()> {
+        sqlx::query!("UPDATE events SET status=$2 WHERE id=$1 AND status=$3", id, STATUS_PUBLISHING, STATUS_DRAFT)
+            .execute(&self.db.0).await?;
+        Ok(())
+    }
+
+    // 索引器看到 EventCreated：按组织者 + 标题 + 起止时间匹配最早的待发布草稿
+    // 链上时间为秒级（calldata::unix 截断亚秒），草稿时间按秒截断后比较
+    pub async fn link_chain_event(&self, organizer: &str, title: &str, start: DateTime<Utc>, end: DateTime<Utc>, chain_event_id: i64) -> Result<Option<i64>> {
+        let id = sqlx::query_scalar!(r#"UPDATE events SET chain_event_id=$5, status=$6
+            WHERE id = (SELECT id FROM events WHERE organizer_wallet=$1 AND meta->>'title'=$2
+                AND date_trunc('second', start_time)=$3 AND date_trunc('second', end_time)=$4
+                AND status=$7 AND chain_event_id IS NULL ORDER BY id LIMIT 1)
+            RETURNING id"#,
+            organizer, title, start, end, chain_event_id, STATUS_PUBLISHED, STATUS_PUBLISHING)
+            .fetch_optional(&self.db.0).await?;
+        Ok(id)
+    }
+
+    // 索引器看到 TicketTypeAdded：按名称关联到同一活动下尚未上链的票种
+    pub async fn link_chain_ticket_type(&self, chain_event_id: i64, name: &str, chain_type_id: i64) -> Result<Option<i64>> {
+        let id = sqlx::query_scalar!(r#"UPDATE ticket_types SET chain_type_id=$3
+            WHERE id = (SELECT t.id FROM ticket_types t JOIN events e ON e.id=t.event_id
+                WHERE e.chain_event_id=$1 AND t.meta->>'name'=$2 AND t.chain_type_id IS NULL ORDER BY t.id LIMIT 1)
+            RETURNING id"#,
+            chain_event_id, name, chain_type_id)
             .fetch_optional(&self.db.0).await?;
-        Ok(rec.map(|r| Event { id: r.id, organizer_wallet: r.organizer_wallet, start_time: r.start_time, end_time: r.end_time, venue: r.venue, status: r.status }))
+        Ok(id)
+    }
+
+    // 以链上 getTicketTypeInfo 为准更新已关联票种的面值
+    pub async fn set_chain_type_prices(&self, chain_event_id: i64, chain_type_id: i64, price_wei: &str, eth_price_wei: &str) -> Result<bool> {
+        let res = sqlx::query!("UPDATE ticket_types t SET price_wei=$3::TEXT::NUMERIC, eth_price_wei=$4::TEXT::NUMERIC
+            FROM events e WHERE e.id = t.event_id AND e.chain_event_id = $1 AND t.chain_type_id = $2",
+            chain_event_id, chain_type_id, price_wei, eth_price_wei)
+            .execute(&self.db.0).await?;
+        Ok(res.rows_affected() > 0)
     }
 }
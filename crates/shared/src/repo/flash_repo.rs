@@ -0,0 +1,125 @@
+use crate::db::pool::Db;
+use crate::domain::swap::flash::{AlertKind, FlashLoan, FlashLoanAlert, Source, TracedCall};
+use alloy::primitives::U256;
+use anyhow::Result;
+
+pub struct FlashRepo<'a> { pub db: &'a Db }
+
+pub struct NewFlashLoan<'a> {
+    pub chain_id: i64,
+    pub tx_hash: &'a str,
+    pub block_number: i64,
+    pub tx_from: &'a str,
+    pub borrower: &'a str,
+    pub token_amount: Option<U256>,
+    pub eth_amount: Option<U256>,
+    pub source: Source,
+    pub calls: &'a [TracedCall],
+    pub max_price_move_bps: Option<u32>,
+}
+
+// 同区块的二级市场成交
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlockSale {
+    pub listing_id: String,
+    pub token_id: Option<String>,
+    pub buyer: Option<String>,
+    pub seller: Option<String>,
+    pub price_wei: Option<String>,
+    pub tx_hash: String,
+}
+
+impl<'a> FlashRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    // 重复处理同一交易（回放 / 重索引）时覆盖为最新结果；已确认的记录不被仅凭事件的推断覆盖
+    pub async fn record(&self, f: &NewFlashLoan<'_>) -> Result<()> {
+        sqlx::query!("INSERT INTO flash_loans(chain_id, tx_hash, block_number, tx_from, borrower, token_amount, eth_amount, source, calls, max_price_move_bps, status)
+            VALUES($1,$2,$3,$4,$5,$6::TEXT::NUMERIC,$7::TEXT::NUMERIC,$8,$9,$10,$11)
+            ON CONFLICT (chain_id, tx_hash) DO UPDATE SET borrower=EXCLUDED.borrower, token_amount=EXCLUDED.token_amount,
+                eth_amount=EXCLUDED.eth_amount, source=EXCLUDED.source, calls=EXCLUDED.calls, max_price_move_bps=EXCLUDED.max_price_move_bps,
+                status=EXCLUDED.status
+            WHERE NOT (flash_loans.status = 'confirmed' AND EXCLUDED.status = 'suspected')",
+            f.chain_id,
+            f.tx_hash,
+            f.block_number,
+            f.tx_from,
+            f.borrower,
+            f.token_amount.map(|v| v.to_string()),
+            f.eth_amount.map(|v| v.to_string()),
+            f.source.as_str(),
+            serde_json::to_value(f.calls)?,
+            f.max_price_move_bps.map(|v| v as i32),
+            f.source.status())
+            .execute(&self.db.0).await?;
+        Ok(())
+    }
+
+    // 交易首条日志之前的最后一次 PriceUpdated 代币价格
+    pub async fn price_before(&self, chain_id: i64, block: i64, log_index: i32) -> Result<Option<U256>> {
+        let r = sqlx::query!(r#"SELECT token_price::TEXT AS "token_price!" FROM token_price_observations
+            WHERE chain_id = $1 AND (block_number, log_index) < ($2, $3)
+            ORDER BY block_number DESC, log_index DESC LIMIT 1"#, chain_id, block, log_index)
+            .fetch_optional(&self.db.0).await?;
+        r.map(|r| Ok(r.token_price.parse()?)).transpose()
+    }
+
+    pub async fn sales_in_block(&self, chain_id: i64, block: i64) -> Result<Vec<BlockSale>> {
+        let rows = sqlx::query!(r#"SELECT listing_id::TEXT AS "listing_id!", token_id::TEXT AS "token_id?", buyer, seller, price::TEXT AS "price?", tx_hash
+            FROM marketplace_trades WHERE chain_id = $1 AND block_number = $2
+            ORDER BY tx_hash, listing_id"#, chain_id, block)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| BlockSale {
+            listing_id: r.listing_id,
+            token_id: r.token_id,
+            buyer: r.buyer,
+            seller: r.seller,
+            price_wei: r.price,
+            tx_hash: r.tx_hash,
+        }).collect())
+    }
+
+    pub async fn add_alert(&self, chain_id: i64, tx_hash: &str, kind: AlertKind, detail: serde_json::Value) -> Result<()> {
+        sqlx::query!("INSERT INTO flash_loan_alerts(chain_id, tx_hash, kind, detail) VALUES($1,$2,$3,$4)
+            ON CONFLICT (chain_id, tx_hash, kind) DO UPDATE SET detail=EXCLUDED.detail",
+            chain_id, tx_hash, kind.as_str(), detail)
+            .execute(&self.db.0).await?;
+        Ok(())
+    }
+
+    // 最新在前；alerted_only 只返回有告警的记录，status 为 confirmed / suspected 时按状态过滤
+    pub async fn list(&self, chain_id: i64, alerted_only: bool, status: Option<&str>, limit: i64) -> Result<Vec<FlashLoan>> {
+        let rows = sqlx::query!(r#"SELECT f.tx_hash, f.block_number, f.tx_from, f.borrower,
+                f.token_amount::TEXT AS "token_amount?", f.eth_amount::TEXT AS "eth_amount?",
+                f.source, f.status, f.calls, f.max_price_move_bps, f.detected_at
+            FROM flash_loans f
+            WHERE f.chain_id = $1
+              AND (NOT $2 OR EXISTS (SELECT 1 FROM flash_loan_alerts a WHERE a.chain_id = f.chain_id AND a.tx_hash = f.tx_hash))
+              AND ($3::TEXT IS NULL OR f.status = $3)
+            ORDER BY f.block_number DESC, f.tx_hash
+            LIMIT $4"#, chain_id, alerted_only, status, limit)
+            .fetch_all(&self.db.0).await?;
+        let hashes: Vec<String> = rows.iter().map(|r| r.tx_hash.clone()).collect();
+        let alerts = sqlx::query!("SELECT tx_hash, kind, detail, created_at FROM flash_loan_alerts
+            WHERE chain_id = $1 AND tx_hash = ANY($2) ORDER BY created_at, kind", chain_id, &hashes)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| FlashLoan {
+            alerts: alerts
+                .iter()
+                .filter(|a| a.tx_hash == r.tx_hash)
+                .map(|a| FlashLoanAlert { kind: a.kind.clone(), detail: a.detail.clone(), created_at: a.created_at })
+                .collect(),
+            tx_hash: r.tx_hash,
+            block_number: r.block_number,
+            tx_from: r.tx_from,
+            borrower: r.borrower,
+            token_amount_wei: r.token_amount,
+            eth_amount_wei: r.eth_amount,
+            source: r.source,
+            status: r.status,
+            calls: r.calls,
+            max_price_move_bps: r.max_price_move_bps,
+            detected_at: r.detected_at,
+        }).collect())
+    }
+}
@@ -0,0 +1,376 @@
+use crate::db::pool::Db;
+use crate::domain::market::auction::{Auction, AuctionBid, AuctionStatus};
+use crate::domain::market::policy::{ListingCheck, ListingViolation, ResalePolicy, ResalePolicyInput, Violation};
+use crate::domain::market::{
+    DepthBucket, FloorPrice, ListingCursor, ListingFilter, ListingSort, ListingStatus, MarketListing, Sale, SIDE_SECONDARY,
+};
+use alloy::primitives::U256;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, QueryBuilder, Row};
+
+pub struct MarketRepo<'a> { pub db: &'a Db }
+
+// 视图中链下挂单 chain_id 为空，按链过滤时一并保留
+const LISTING_SELECT: &str = "SELECT id, source, token_id, event_id, type_id, seller, price_wei::TEXT AS price_wei,
+        eth_price_wei::TEXT AS eth_price_wei, side, status, expires_at, created_at
+    FROM v_market_listings WHERE (chain_id = ";
+
+fn listing_from_row(r: &sqlx::postgres::PgRow) -> Result<MarketListing> {
+    Ok(MarketListing {
+        id: r.try_get("id")?,
+        source: r.try_get("source")?,
+        token_id: r.try_get("token_id")?,
+        event_id: r.try_get("event_id")?,
+        type_id: r.try_get("type_id")?,
+        seller: r.try_get("seller")?,
+        price_wei: r.try_get("price_wei")?,
+        eth_price_wei: r.try_get("eth_price_wei")?,
+        side: r.try_get("side")?,
+        status: r.try_get("status")?,
+        expires_at: r.try_get("expires_at")?,
+        created_at: r.try_get("created_at")?,
+    })
+}
+
+impl<'a> MarketRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    fn base(chain_id: i64) -> QueryBuilder<'static, Postgres> {
+        let mut qb = QueryBuilder::new(LISTING_SELECT);
+        qb.push_bind(chain_id).push(" OR chain_id IS NULL)");
+        qb
+    }
+
+    pub async fn find(&self, chain_id: i64, id: &str) -> Result<Option<MarketListing>> {
+        let mut qb = Self::base(chain_id);
+        qb.push(" AND id = ").push_bind(id.to_string());
+        let row = qb.build().fetch_optional(&self.db.0).await?;
+        row.as_ref().map(listing_from_row).transpose()
+    }
+
+    // 过滤条件动态拼接 + keyset 分页；多取一条判断是否还有下一页
+    pub async fn list(&self, chain_id: i64, f: &ListingFilter, sort: ListingSort, after: Option<ListingCursor>, limit: i64) -> Result<(Vec<MarketListing>, Option<ListingCursor>)> {
+        let mut qb = Self::base(chain_id);
+        if let Some(id) = f.event_id {
+            qb.push(" AND event_id = ").push_bind(id);
+        }
+        if let Some(id) = f.type_id {
+            qb.push(" AND type_id = ").push_bind(id);
+        }
+        if let Some(s) = &f.seller {
+            qb.push(" AND seller = ").push_bind(s.to_lowercase());
+        }
+        if let Some(p) = &f.min_price {
+            qb.push(" AND price_wei >= ").push_bind(p.clone()).push("::TEXT::NUMERIC");
+        }
+        if let Some(p) = &f.max_price {
+            qb.push(" AND price_wei <= ").push_bind(p.clone()).push("::TEXT::NUMERIC");
+        }
+        if let Some(s) = f.status {
+            qb.push(" AND status = ").push_bind(s.as_str());
+        }
+        if let Some(s) = &f.side {
+            qb.push(" AND side = ").push_bind(s.clone());
+        }
+        let (key, desc) = match sort {
+            ListingSort::PriceAsc => ("price_wei", false),
+            ListingSort::PriceDesc => ("price_wei", true),
+            ListingSort::CreatedDesc => ("created_at", true),
+        };
+        if let Some(c) = after {
+            let cmp = if desc { "<" } else { ">" };
+            if key == "created_at" {
+                // 游标为微秒时间戳，按整数微秒比较，避免浮点换算丢精度
+                qb.push(format!(" AND ((EXTRACT(EPOCH FROM created_at) * 1000000)::BIGINT, id) {cmp} ("))
+                    .push_bind(c.key).push("::TEXT::BIGINT, ");
+            } else {
+                qb.push(format!(" AND (price_wei, id) {cmp} ("))
+                    .push_bind(c.key).push("::TEXT::NUMERIC, ");
+            }
+            qb.push_bind(c.id).push(")");
+        }
+        let dir = if desc { "DESC" } else { "ASC" };
+        qb.push(format!(" ORDER BY {key} {dir}, id {dir} LIMIT ")).push_bind(limit + 1);
+
+        let rows = qb.build().fetch_all(&self.db.0).await?;
+        let mut items = rows.iter().map(listing_from_row).collect::<Result<Vec<_>>>()?;
+        let next = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(|l| ListingCursor {
+                key: if key == "created_at" { l.created_at.timestamp_micros().to_string() } else { l.price_wei.clone() },
+                id: l.id.clone(),
+            })
+        } else {
+            None
+        };
+        Ok((items, next))
+    }
+
+    pub async fn floor_prices(&self, chain_id: i64, event_id: Option<i64>) -> Result<Vec<FloorPrice>> {
+        let rows = sqlx::query!(r#"SELECT event_id AS "event_id!", type_id AS "type_id!",
+                MIN(price_wei)::TEXT AS "floor_price_wei!", COUNT(*) AS "active_listings!"
+            FROM v_market_listings
+            WHERE (chain_id = $1 OR chain_id IS NULL) AND status = 'active' AND side = $3
+              AND event_id IS NOT NULL AND type_id IS NOT NULL
+              AND ($2::BIGINT IS NULL OR event_id = $2)
+            GROUP BY event_id, type_id
+            ORDER BY event_id, type_id"#, chain_id, event_id, SIDE_SECONDARY)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| FloorPrice {
+            event_id: r.event_id,
+            type_id: r.type_id,
+            floor_price_wei: r.floor_price_wei,
+            active_listings: r.active_listings,
+        }).collect())
+    }
+
+    // 按 bucket_wei 宽度分桶统计 active 二级市场卖单数量，价格升序
+    pub async fn depth(&self, chain_id: i64, type_id: i64, bucket_wei: &str) -> Result<Vec<DepthBucket>> {
+        let rows = sqlx::query!(r#"SELECT (FLOOR(price_wei / $3::TEXT::NUMERIC) * $3::TEXT::NUMERIC)::TEXT AS "price_from!",
+                ((FLOOR(price_wei / $3::TEXT::NUMERIC) + 1) * $3::TEXT::NUMERIC)::TEXT AS "price_to!",
+                COUNT(*) AS "listings!"
+            FROM v_market_listings
+            WHERE (chain_id = $1 OR chain_id IS NULL) AND status = 'active' AND side = $4 AND type_id = $2
+            GROUP BY FLOOR(price_wei / $3::TEXT::NUMERIC)
+            ORDER BY FLOOR(price_wei / $3::TEXT::NUMERIC)"#, chain_id, type_id, bucket_wei, SIDE_SECONDARY)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| DepthBucket {
+            price_from_wei: r.price_from,
+            price_to_wei: r.price_to,
+            listings: r.listings,
+        }).collect())
+    }
+
+    pub async fn recent_sales(&self, chain_id: i64, event_id: Option<i64>, type_id: Option<i64>, limit: i64) -> Result<Vec<Sale>> {
+        let rows = sqlx::query!(r#"SELECT s.listing_id::TEXT AS "listing_id!", COALESCE(s.token_id, l.ticket_id)::BIGINT AS token_id,
+                t.event_id AS "event_id?", t.type_id AS "type_id?", s.buyer, COALESCE(s.seller, l.seller) AS seller,
+                s.price::TEXT AS price, s.paid_with_eth, s.tx_hash, s.block_number, b.block_time AS "block_time?"
+            FROM marketplace_trades s
+            LEFT JOIN marketplace_listings l ON l.chain_id = s.chain_id AND l.listing_id = s.listing_id
+            LEFT JOIN tickets t ON t.token_id = COALESCE(s.token_id, l.ticket_id)
+            LEFT JOIN chain_blocks b ON b.chain_id = s.chain_id AND b.block_number = s.block_number
+            WHERE s.chain_id = $1
+              AND ($2::BIGINT IS NULL OR t.event_id = $2)
+              AND ($3::BIGINT IS NULL OR t.type_id = $3)
+            ORDER BY s.block_number DESC NULLS LAST, s.tx_hash
+            LIMIT $4"#, chain_id, event_id, type_id, limit)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| Sale {
+            listing_id: r.listing_id,
+            token_id: r.token_id,
+            event_id: r.event_id,
+            type_id: r.type_id,
+            buyer: r.buyer,
+            seller: r.seller,
+            price_wei: r.price,
+            paid_with_eth: r.paid_with_eth,
+            tx_hash: r.tx_hash,
+            block_number: r.block_number,
+            block_time: r.block_time,
+        }).collect())
+    }
+
+    // 进行中的拍卖，按结束时间升序；ends_before 用于“即将结束”
+    // end_time 由索引器在出价后按链上 auctions(id).endTime 延长，防狙击延时内的拍卖不会提前消失
+    pub async fn live_auctions(&self, chain_id: i64, event_id: Option<i64>, ends_before: Option<DateTime<Utc>>, limit: i64) -> Result<Vec<Auction>> {
+        let rows = sqlx::query!(r#"SELECT a.auction_id::TEXT AS "auction_id!", a.token_id::TEXT AS "token_id!", t.event_id AS "event_id?", t.type_id AS "type_id?",
+                a.seller, a.starting_price::TEXT AS "starting_price!", a.eth_starting_price::TEXT AS "eth_starting_price!",
+                a.reserve_price::TEXT AS "reserve_price!", a.end_time, a.status, a.current_bid::TEXT AS current_bid, a.current_bidder,
+                a.current_bid_is_eth, a.bid_count, a.winner, a.winning_bid::TEXT AS winning_bid
+            FROM marketplace_auctions a
+            LEFT JOIN tickets t ON t.token_id = a.token_id
+            WHERE a.chain_id = $1 AND a.status = $2 AND a.end_time > NOW()
+              AND ($3::BIGINT IS NULL OR t.event_id = $3)
+              AND ($4::TIMESTAMPTZ IS NULL OR a.end_time <= $4)
+            ORDER BY a.end_time, a.auction_id
+            LIMIT $5"#, chain_id, AuctionStatus::Active.to_chain(), event_id, ends_before, limit)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| Auction {
+            auction_id: r.auction_id,
+            token_id: r.token_id,
+            event_id: r.event_id,
+            type_id: r.type_id,
+            seller: r.seller,
+            starting_price_wei: r.starting_price,
+            eth_starting_price_wei: r.eth_starting_price,
+            reserve_price_wei: r.reserve_price,
+            end_time: r.end_time,
+            status: status_str(r.status),
+            current_bid_wei: r.current_bid,
+            current_bidder: r.current_bidder,
+            current_bid_is_eth: r.current_bid_is_eth,
+            bid_count: r.bid_count,
+            winner: r.winner,
+            winning_bid_wei: r.winning_bid,
+        }).collect())
+    }
+
+    pub async fn find_auction(&self, chain_id: i64, auction_id: &str) -> Result<Option<Auction>> {
+        let rec = sqlx::query!(r#"SELECT a.auction_id::TEXT AS "auction_id!", a.token_id::TEXT AS "token_id!", t.event_id AS "event_id?", t.type_id AS "type_id?",
+                a.seller, a.starting_price::TEXT AS "starting_price!", a.eth_starting_price::TEXT AS "eth_starting_price!",
+                a.reserve_price::TEXT AS "reserve_price!", a.end_time, a.status, a.current_bid::TEXT AS current_bid, a.current_bidder,
+                a.current_bid_is_eth, a.bid_count, a.winner, a.winning_bid::TEXT AS winning_bid
+            FROM marketplace_auctions a
+            LEFT JOIN tickets t ON t.token_id = a.token_id
+            WHERE a.chain_id = $1 AND a.auction_id = $2::TEXT::NUMERIC"#, chain_id, auction_id)
+            .fetch_optional(&self.db.0).await?;
+        Ok(rec.map(|r| Auction {
+            auction_id: r.auction_id,
+            token_id: r.token_id,
+            event_id: r.event_id,
+            type_id: r.type_id,
+            seller: r.seller,
+            starting_price_wei: r.starting_price,
+            eth_starting_price_wei: r.eth_starting_price,
+            reserve_price_wei: r.reserve_price,
+            end_time: r.end_time,
+            status: status_str(r.status),
+            current_bid_wei: r.current_bid,
+            current_bidder: r.current_bidder,
+            current_bid_is_eth: r.current_bid_is_eth,
+            bid_count: r.bid_count,
+            winner: r.winner,
+            winning_bid_wei: r.winning_bid,
+        }))
+    }
+
+    // 出价历史，最新在前
+    pub async fn auction_bids(&self, chain_id: i64, auction_id: &str) -> Result<Vec<AuctionBid>> {
+        let rows = sqlx::query!(r#"SELECT x.bidder, x.amount::TEXT AS "amount!", x.is_eth, x.block_number, b.block_time AS "block_time?", x.tx_hash
+            FROM marketplace_bids x
+            LEFT JOIN chain_blocks b ON b.chain_id = x.chain_id AND b.block_number = x.block_number
+            WHERE x.chain_id = $1 AND x.auction_id = $2::TEXT::NUMERIC
+            ORDER BY x.block_number DESC, x.log_index DESC"#, chain_id, auction_id)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| AuctionBid {
+            bidder: r.bidder,
+            amount_wei: r.amount,
+            is_eth: r.is_eth,
+            block_number: r.block_number,
+            block_time: r.block_time,
+            tx_hash: r.tx_hash,
+        }).collect())
+    }
+
+    pub async fn resale_policy(&self, event_id: i64) -> Result<Option<ResalePolicy>> {
+        let rec = sqlx::query_as!(ResalePolicy, "SELECT event_id, max_price_bps, max_listings_per_wallet, updated_by, updated_at
+            FROM event_resale_policies WHERE event_id = $1", event_id)
+            .fetch_optional(&self.db.0).await?;
+        Ok(rec)
+    }
+
+    pub async fn upsert_resale_policy(&self, event_id: i64, p: &ResalePolicyInput, updated_by: &str) -> Result<ResalePolicy> {
+        let rec = sqlx::query_as!(ResalePolicy, "INSERT INTO event_resale_policies(event_id, max_price_bps, max_listings_per_wallet, updated_by)
+            VALUES($1,$2,$3,$4)
+            ON CONFLICT (event_id) DO UPDATE SET max_price_bps=EXCLUDED.max_price_bps,
+                max_listings_per_wallet=EXCLUDED.max_listings_per_wallet, updated_by=EXCLUDED.updated_by, updated_at=NOW()
+            RETURNING event_id, max_price_bps, max_listings_per_wallet, updated_by, updated_at",
+            event_id, p.max_price_bps, p.max_listings_per_wallet, updated_by)
+            .fetch_one(&self.db.0).await?;
+        Ok(rec)
+    }
+
+    pub async fn delete_resale_policy(&self, event_id: i64) -> Result<bool> {
+        let res = sqlx::query!("DELETE FROM event_resale_policies WHERE event_id = $1", event_id)
+            .execute(&self.db.0).await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // 按票所属活动的转售政策校验挂单；活动取 tickets 投影，没有时按 ticket_tokens 的链上 eventId 关联
+    // 票与活动都无法定位或活动未配置政策时返回 None；票种未知时面值为 None，由政策按未知面值拒绝
+    // exclude_listing 为视图 id，更新挂单时不把自身计入卖家的进行中挂单
+    pub async fn check_listing(&self, chain_id: i64, token_id: &str, seller: &str, price: U256, eth_price: U256, exclude_listing: Option<&str>) -> Result<Option<(i64, Vec<Violation>)>> {
+        let Some(r) = sqlx::query!(r#"SELECT p.event_id, p.max_price_bps, p.max_listings_per_wallet, p.updated_by, p.updated_at,
+                tt.price_wei::TEXT AS "face_value?", tt.eth_price_wei::TEXT AS "eth_face_value?"
+            FROM (SELECT $2::TEXT::NUMERIC AS token_id) k
+            LEFT JOIN ticket_tokens tk ON tk.chain_id = $1 AND tk.token_id = k.token_id
+            LEFT JOIN tickets t ON t.token_id = k.token_id
+            JOIN events e ON e.id = COALESCE(t.event_id, (SELECT id FROM events WHERE chain_event_id = tk.event_id ORDER BY id LIMIT 1))
+            JOIN event_resale_policies p ON p.event_id = e.id
+            LEFT JOIN ticket_types tt ON tt.id = t.type_id"#, chain_id, token_id)
+            .fetch_optional(&self.db.0).await? else {
+            return Ok(None);
+        };
+        let others = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "n!" FROM v_market_listings
+            WHERE (chain_id = $1 OR chain_id IS NULL) AND event_id = $2 AND seller = $3 AND status = 'active'
+              AND ($4::TEXT IS NULL OR id <> $4)"#, chain_id, r.event_id, seller.to_lowercase(), exclude_listing)
+            .fetch_one(&self.db.0).await?;
+        let policy = ResalePolicy {
+            event_id: r.event_id,
+            max_price_bps: r.max_price_bps,
+            max_listings_per_wallet: r.max_listings_per_wallet,
+            updated_by: r.updated_by,
+            updated_at: r.updated_at,
+        };
+        let check = ListingCheck {
+            face_value: r.face_value.map(|v| v.parse()).transpose()?,
+            eth_face_value: r.eth_face_value.map(|v| v.parse()).transpose()?,
+            price,
+            eth_price,
+            other_active_listings: others,
+        };
+        Ok(Some((policy.event_id, policy.check(&check))))
+    }
+
+    // 索引器投影链上挂单后重新评估并整体替换其违规标记；非进行中的挂单只清除标记
+    pub async fn flag_listing(&self, chain_id: i64, listing_id: &str, block: i64) -> Result<Vec<Violation>> {
+        let listing = sqlx::query!(r#"SELECT ticket_id::TEXT AS "ticket_id!", seller AS "seller!", price::TEXT AS "price!",
+                COALESCE(eth_price, 0)::TEXT AS "eth_price!", status
+            FROM marketplace_listings
+            WHERE chain_id = $1 AND listing_id = $2::TEXT::NUMERIC AND ticket_id IS NOT NULL AND seller IS NOT NULL AND price IS NOT NULL"#, chain_id, listing_id)
+            .fetch_optional(&self.db.0).await?;
+        let checked = match listing {
+            Some(l) if l.status == Some(ListingStatus::Active.to_chain()) => {
+                self.check_listing(chain_id, &l.ticket_id, &l.seller, l.price.parse()?, l.eth_price.parse()?, Some(listing_id)).await?
+            }
+            _ => None,
+        };
+        let mut tx = self.db.0.begin().await?;
+        sqlx::query!("DELETE FROM listing_policy_violations WHERE chain_id = $1 AND listing_id = $2::TEXT::NUMERIC", chain_id, listing_id)
+            .execute(&mut *tx).await?;
+        let Some((event_id, violations)) = checked else {
+            tx.commit().await?;
+            return Ok(Vec::new());
+        };
+        for v in &violations {
+            sqlx::query!("INSERT INTO listing_policy_violations(chain_id, listing_id, event_id, rule, detail, detected_block)
+                VALUES($1,$2::TEXT::NUMERIC,$3,$4,$5,$6) ON CONFLICT DO NOTHING",
+                chain_id, listing_id, event_id, v.rule, v.message, block)
+                .execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(violations)
+    }
+
+    // 仅列出仍处于进行中的违规挂单
+    pub async fn policy_violations(&self, chain_id: i64, event_id: i64, limit: i64) -> Result<Vec<ListingViolation>> {
+        let rows = sqlx::query!(r#"SELECT v.listing_id::TEXT AS "listing_id!", ml.ticket_id::BIGINT AS token_id, ml.seller AS "seller?",
+                ml.price::TEXT AS price, v.rule, v.detail, v.detected_block, v.created_at
+            FROM listing_policy_violations v
+            JOIN marketplace_listings ml ON ml.chain_id = v.chain_id AND ml.listing_id = v.listing_id
+            WHERE v.chain_id = $1 AND v.event_id = $2 AND ml.status = $3
+            ORDER BY v.detected_block DESC, v.listing_id DESC
+            LIMIT $4"#, chain_id, event_id, ListingStatus::Active.to_chain(), limit)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| ListingViolation {
+            listing_id: r.listing_id,
+            token_id: r.token_id,
+            seller: r.seller,
+            price_wei: r.price,
+            rule: r.rule,
+            detail: r.detail,
+            detected_block: r.detected_block,
+            created_at: r.created_at,
+        }).collect())
+    }
+}
+
+fn status_str(v: i16) -> String {
+    u8::try_from(v)
+        .ok()
+        .and_then(AuctionStatus::from_chain)
+        .map(|s| s.as_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
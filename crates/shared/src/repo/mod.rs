@@ -1 +1,8 @@
 pub mod event_repo;
+pub mod flash_repo;
+pub mod market_repo;
+pub mod purchase_repo;
+pub mod swap_repo;
+pub mod ticket_repo;
+pub mod user_repo;
+pub mod whitelist_repo;
@@ -0,0 +1,85 @@
+use crate::db::pool::Db;
+use anyhow::Result;
+use std::collections::HashMap;
+
+pub struct PurchaseRepo<'a> { pub db: &'a Db }
+
+// 按链上票种 id 汇总的购买数量
+#[derive(Debug, Default, Clone)]
+pub struct PurchaseCounts {
+    pub by_wallet: HashMap<i64, i64>,
+    pub total: HashMap<i64, i64>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TypeLimit {
+    pub confirmed: Option<i64>,
+    pub pending: Option<i64>,
+}
+
+impl<'a> PurchaseRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    pub async fn counts(&self, chain_id: i64, chain_event_id: i64, wallet: &str) -> Result<PurchaseCounts> {
+        let rows = sqlx::query!(r#"SELECT chain_type_id,
+                COALESCE(SUM(quantity) FILTER (WHERE buyer = $3), 0)::BIGINT AS "by_wallet!",
+                SUM(quantity)::BIGINT AS "total!"
+            FROM ticket_purchases
+            WHERE chain_id = $1 AND chain_event_id = $2
+            GROUP BY chain_type_id"#, chain_id, chain_event_id, wallet)
+            .fetch_all(&self.db.0).await?;
+        let mut out = PurchaseCounts::default();
+        for r in rows {
+            out.by_wallet.insert(r.chain_type_id, r.by_wallet);
+            out.total.insert(r.chain_type_id, r.total);
+        }
+        Ok(out)
+    }
+
+    // 只有已确认的限额参与限购计算；pending 为已下发但尚未确认上链的新值
+    pub async fn type_limits(&self, chain_id: i64, chain_event_id: i64, wallet: &str) -> Result<HashMap<i64, TypeLimit>> {
+        let rows = sqlx::query!("SELECT chain_type_id, purchase_limit, pending_limit FROM ticket_type_purchase_limits
+            WHERE chain_id = $1 AND chain_event_id = $2 AND wallet = $3", chain_id, chain_event_id, wallet)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.chain_type_id, TypeLimit { confirmed: r.purchase_limit, pending: r.pending_limit }))
+            .collect())
+    }
+
+    pub async fn request_type_limit(&self, chain_id: i64, chain_event_id: i64, chain_type_id: i64, wallet: &str, limit: i64, set_by: &str) -> Result<()> {
+        sqlx::query!("INSERT INTO ticket_type_purchase_limits(chain_id, chain_event_id, chain_type_id, wallet, pending_limit, set_by)
+            VALUES($1,$2,$3,$4,$5,$6)
+            ON CONFLICT (chain_id, chain_event_id, chain_type_id, wallet) DO UPDATE SET pending_limit=EXCLUDED.pending_limit,
+                set_by=EXCLUDED.set_by, set_at=NOW()",
+            chain_id, chain_event_id, chain_type_id, wallet, limit, set_by)
+            .execute(&self.db.0).await?;
+        Ok(())
+    }
+
+    // 按已成功上链的 setTicketTypePurchaseLimit 交易写入生效限额；早于已确认区块的交易不覆盖
+    // 待确认值与上链值一致时清除；返回是否生效
+    #[allow(clippy::too_many_arguments)]
+    pub async fn confirm_type_limit(
+        &self,
+        chain_id: i64,
+        chain_event_id: i64,
+        chain_type_id: i64,
+        wallet: &str,
+        limit: i64,
+        set_by: &str,
+        block: i64,
+        tx_hash: &str,
+    ) -> Result<bool> {
+        let r = sqlx::query!(r#"INSERT INTO ticket_type_purchase_limits AS t(chain_id, chain_event_id, chain_type_id, wallet, purchase_limit, set_by, confirmed_block, confirmed_tx)
+            VALUES($1,$2,$3,$4,$5,$6,$7,$8)
+            ON CONFLICT (chain_id, chain_event_id, chain_type_id, wallet) DO UPDATE SET purchase_limit=EXCLUDED.purchase_limit,
+                confirmed_block=EXCLUDED.confirmed_block, confirmed_tx=EXCLUDED.confirmed_tx,
+                pending_limit=CASE WHEN t.pending_limit = EXCLUDED.purchase_limit THEN NULL ELSE t.pending_limit END
+            WHERE t.confirmed_block IS NULL OR t.confirmed_block <= EXCLUDED.confirmed_block
+            RETURNING 1 AS "one!""#,
+            chain_id, chain_event_id, chain_type_id, wallet, limit, set_by, block, tx_hash)
+            .fetch_optional(&self.db.0).await?;
+        Ok(r.is_some())
+    }
+}
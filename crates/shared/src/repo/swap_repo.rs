@@ -0,0 +1,168 @@
+use crate::db::pool::Db;
+use crate::domain::swap::history::{Candle, CandleInterval, PriceObservation};
+use crate::domain::swap::lp::{FeeBucket, LpFlows};
+use alloy::primitives::U256;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+pub struct SwapRepo<'a> { pub db: &'a Db }
+
+impl<'a> SwapRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    // 窗口内的观测值，外加窗口起点前的最后一个（作为起始价格），按时间升序
+    pub async fn observations(&self, chain_id: i64, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PriceObservation>> {
+        let rows = sqlx::query!(r#"SELECT observed_at AS "observed_at!", token_price::TEXT AS "token_price!", eth_price::TEXT AS "eth_price!" FROM (
+                (SELECT observed_at, token_price, eth_price, block_number, log_index FROM token_price_observations
+                 WHERE chain_id = $1 AND observed_at <= $2
+                 ORDER BY observed_at DESC, block_number DESC, log_index DESC LIMIT 1)
+                UNION ALL
+                (SELECT observed_at, token_price, eth_price, block_number, log_index FROM token_price_observations
+                 WHERE chain_id = $1 AND observed_at > $2 AND observed_at < $3)
+            ) o
+            ORDER BY observed_at, block_number, log_index"#, chain_id, from, to)
+            .fetch_all(&self.db.0).await?;
+        rows.into_iter()
+            .map(|r| Ok(PriceObservation {
+                at: r.observed_at,
+                token_price: r.token_price.parse()?,
+                eth_price: r.eth_price.parse()?,
+            }))
+            .collect()
+    }
+
+    // 按区块时间分桶；无成交的区间不返回
+    pub async fn candles(&self, chain_id: i64, interval: CandleInterval, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Candle>> {
+        let rows = sqlx::query!(r#"SELECT date_trunc($2, b.block_time, 'UTC') AS "start!",
+                ((array_agg(s.price ORDER BY s.block_number, s.log_index))[1])::TEXT AS "open!",
+                MAX(s.price)::TEXT AS "high!",
+                MIN(s.price)::TEXT AS "low!",
+                ((array_agg(s.price ORDER BY s.block_number DESC, s.log_index DESC))[1])::TEXT AS "close!",
+                COALESCE(SUM(s.eth_amount), 0)::TEXT AS "volume_eth!",
+                COALESCE(SUM(s.token_amount), 0)::TEXT AS "volume_token!",
+                COUNT(*) AS "trades!"
+            FROM token_swaps s
+            JOIN chain_blocks b ON b.chain_id = s.chain_id AND b.block_number = s.block_number
+            WHERE s.chain_id = $1 AND s.price IS NOT NULL
+              AND b.block_time >= $3 AND b.block_time < $4
+            GROUP BY 1
+            ORDER BY 1"#, chain_id, interval.trunc_unit(), from, to)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| Candle {
+            start: r.start,
+            open: r.open,
+            high: r.high,
+            low: r.low,
+            close: r.close,
+            volume_eth_wei: r.volume_eth,
+            volume_token_wei: r.volume_token,
+            trades: r.trades,
+        }).collect())
+    }
+
+    // 转入减转出；铸造来自零地址、销毁转入零地址，均已计入
+    // 索引到的 LP 转账汇总出的余额；索引起点之前已有持仓时会出现转出多于转入，此时返回 None
+    pub async fn lp_balance(&self, chain_id: i64, wallet: &str) -> Result<Option<U256>> {
+        let r = sqlx::query!(r#"SELECT (COALESCE(SUM(value) FILTER (WHERE to_addr = $2), 0)
+                - COALESCE(SUM(value) FILTER (WHERE from_addr = $2), 0))::TEXT AS "balance!"
+            FROM lp_transfers
+            WHERE chain_id = $1 AND (from_addr = $2 OR to_addr = $2)"#, chain_id, wallet)
+            .fetch_one(&self.db.0).await?;
+        if r.balance.starts_with('-') {
+            return Ok(None);
+        }
+        Ok(Some(r.balance.parse()?))
+    }
+
+    pub async fn lp_flows(&self, chain_id: i64, wallet: &str) -> Result<LpFlows> {
+        let r = sqlx::query!(r#"SELECT
+                COALESCE(SUM(eth_amount) FILTER (WHERE kind = 'add'), 0)::TEXT AS "deposited_eth!",
+                COALESCE(SUM(token_amount) FILTER (WHERE kind = 'add'), 0)::TEXT AS "deposited_token!",
+                COALESCE(SUM(eth_amount) FILTER (WHERE kind = 'remove'), 0)::TEXT AS "withdrawn_eth!",
+                COALESCE(SUM(token_amount) FILTER (WHERE kind = 'remove'), 0)::TEXT AS "withdrawn_token!",
+                COUNT(*) FILTER (WHERE kind = 'add') AS "adds!",
+                COUNT(*) FILTER (WHERE kind = 'remove') AS "removes!"
+            FROM lp_events
+            WHERE chain_id = $1 AND provider = $2"#, chain_id, wallet)
+            .fetch_one(&self.db.0).await?;
+        Ok(LpFlows {
+            deposited_eth: r.deposited_eth.parse()?,
+            deposited_token: r.deposited_token.parse()?,
+            withdrawn_eth: r.withdrawn_eth.parse()?,
+            withdrawn_token: r.withdrawn_token.parse()?,
+            adds: r.adds,
+            removes: r.removes,
+        })
+    }
+
+    // since 之后的成交量按 (输入币种, 成交时生效费率, 成交前钱包 LP 余额与总供应) 汇总
+    // 成交前无 FeeRatesUpdated 记录时使用传入的当前费率并标记 assumed_rate；
+    // LP 转账（钱包相关及铸造 / 销毁）、费率更新与成交按 (block_number, log_index) 合并成一条时间线，
+    // 余额与总供应取窗口累计值，费率取之前最近一次更新；索引不完整导致的负值按 0 计
+    pub async fn fee_buckets(
+        &self,
+        chain_id: i64,
+        wallet: &str,
+        since: DateTime<Utc>,
+        swap_fee_rate: U256,
+        protocol_fee_rate: U256,
+    ) -> Result<Vec<FeeBucket>> {
+        let rows = sqlx::query!(r#"WITH timeline AS (
+                SELECT t.block_number, t.log_index, FALSE AS is_swap, NULL::BOOLEAN AS eth_in, NULL::NUMERIC AS amount_in,
+                    (CASE WHEN t.to_addr = $2 THEN t.value ELSE 0 END) - (CASE WHEN t.from_addr = $2 THEN t.value ELSE 0 END) AS d_balance,
+                    (CASE WHEN t.from_addr = '0x0000000000000000000000000000000000000000' THEN t.value ELSE 0 END)
+                        - (CASE WHEN t.to_addr = '0x0000000000000000000000000000000000000000' THEN t.value ELSE 0 END) AS d_supply,
+                    NULL::NUMERIC AS swap_fee_rate, NULL::NUMERIC AS protocol_fee_rate
+                FROM lp_transfers t
+                WHERE t.chain_id = $1
+                  AND (t.from_addr = $2 OR t.to_addr = $2
+                       OR t.from_addr = '0x0000000000000000000000000000000000000000' OR t.to_addr = '0x0000000000000000000000000000000000000000')
+                UNION ALL
+                SELECT f.block_number, f.log_index, FALSE, NULL, NULL, 0, 0, f.swap_fee_rate, f.protocol_fee_rate
+                FROM swap_fee_rates f
+                WHERE f.chain_id = $1
+                UNION ALL
+                SELECT s.block_number, s.log_index, TRUE, s.token_in = '0x0000000000000000000000000000000000000000', s.amount_in, 0, 0, NULL, NULL
+                FROM token_swaps s
+                JOIN chain_blocks b ON b.chain_id = s.chain_id AND b.block_number = s.block_number
+                WHERE s.chain_id = $1 AND b.block_time >= $3
+            ), running AS (
+                -- 成交行自身增量为 0，累计值即成交之前的余额 / 供应；rate_grp 为截至当前的费率更新次数
+                SELECT block_number, log_index, is_swap, eth_in, amount_in, swap_fee_rate, protocol_fee_rate,
+                    SUM(d_balance) OVER w AS balance,
+                    SUM(d_supply) OVER w AS supply,
+                    COUNT(swap_fee_rate) OVER w AS rate_grp
+                FROM timeline
+                WINDOW w AS (ORDER BY block_number, log_index ROWS UNBOUNDED PRECEDING)
+            ), rated AS (
+                -- 每组首行即该次费率更新；第 0 组之前没有更新，费率为 NULL
+                SELECT is_swap, eth_in, amount_in, balance, supply,
+                    FIRST_VALUE(swap_fee_rate) OVER g AS swap_fee_rate,
+                    FIRST_VALUE(protocol_fee_rate) OVER g AS protocol_fee_rate
+                FROM running
+                WINDOW g AS (PARTITION BY rate_grp ORDER BY block_number, log_index)
+            )
+            SELECT COALESCE(eth_in, FALSE) AS "eth_in!",
+                (swap_fee_rate IS NULL) AS "assumed_rate!",
+                COALESCE(swap_fee_rate, $4::TEXT::NUMERIC)::TEXT AS "swap_fee_rate!",
+                COALESCE(protocol_fee_rate, $5::TEXT::NUMERIC)::TEXT AS "protocol_fee_rate!",
+                GREATEST(balance, 0)::TEXT AS "lp_balance!",
+                GREATEST(supply, 0)::TEXT AS "lp_supply!",
+                SUM(amount_in)::TEXT AS "volume!"
+            FROM rated
+            WHERE is_swap
+            GROUP BY 1, 2, 3, 4, 5, 6"#, chain_id, wallet, since, swap_fee_rate.to_string(), protocol_fee_rate.to_string())
+            .fetch_all(&self.db.0).await?;
+        rows.into_iter()
+            .map(|r| Ok(FeeBucket {
+                eth_in: r.eth_in,
+                assumed_rate: r.assumed_rate,
+                swap_fee_rate: r.swap_fee_rate.parse()?,
+                protocol_fee_rate: r.protocol_fee_rate.parse()?,
+                lp_balance: r.lp_balance.parse()?,
+                lp_supply: r.lp_supply.parse()?,
+                volume: r.volume.parse()?,
+            }))
+            .collect()
+    }
+}
@@ -0,0 +1,175 @@
+use crate::db::pool::Db;
+use crate::domain::market::ListingStatus;
+use crate::domain::ticket::{ActiveListing, TicketDetail, TicketEvent, TicketFilter, TicketTransfer, TicketTypeRef};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
+
+pub struct TicketRepo<'a> { pub db: &'a Db }
+
+// owner 取链上投影（ticket_tokens），尚未投影时回退 tickets.owner_wallet
+const TICKET_SELECT: &str = r#"SELECT t.token_id, COALESCE(tk.owner, t.owner_wallet) AS owner, t.seat_label, t.status, t.minted_block,
+        e.id AS event_id, e.chain_event_id, e.meta->>'title' AS title, e.start_time, e.end_time, e.venue,
+        e.meta->>'image_uri' AS image_uri, e.status AS event_status,
+        tt.id AS type_id, tt.chain_type_id, tt.meta->>'name' AS type_name, tt.price_wei::TEXT AS price_wei
+    FROM tickets t
+    JOIN events e ON e.id = t.event_id
+    JOIN ticket_types tt ON tt.id = t.type_id
+    LEFT JOIN ticket_tokens tk ON tk.chain_id = "#;
+
+fn ticket_from_row(r: &sqlx::postgres::PgRow) -> Result<TicketDetail> {
+    Ok(TicketDetail {
+        token_id: r.try_get("token_id")?,
+        owner: r.try_get("owner")?,
+        seat_label: r.try_get("seat_label")?,
+        status: r.try_get("status")?,
+        minted_block: r.try_get("minted_block")?,
+        event: TicketEvent {
+            id: r.try_get("event_id")?,
+            chain_event_id: r.try_get("chain_event_id")?,
+            title: r.try_get("title")?,
+            start_time: r.try_get("start_time")?,
+            end_time: r.try_get("end_time")?,
+            venue: r.try_get("venue")?,
+            image_uri: r.try_get("image_uri")?,
+            status: r.try_get("event_status")?,
+        },
+        ticket_type: TicketTypeRef {
+            id: r.try_get("type_id")?,
+            chain_type_id: r.try_get("chain_type_id")?,
+            name: r.try_get("type_name")?,
+            price_wei: r.try_get("price_wei")?,
+        },
+        listing: None,
+        transfers: Vec::new(),
+    })
+}
+
+impl<'a> TicketRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    pub async fn find(&self, chain_id: i64, token_id: i64) -> Result<Option<TicketDetail>> {
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(TICKET_SELECT);
+        qb.push_bind(chain_id).push(" AND tk.token_id = t.token_id WHERE t.token_id = ").push_bind(token_id);
+        let Some(row) = qb.build().fetch_optional(&self.db.0).await? else { return Ok(None) };
+        let mut items = vec![ticket_from_row(&row)?];
+        self.attach(chain_id, &mut items).await?;
+        Ok(items.pop())
+    }
+
+    // 钱包持仓：按 token_id keyset 分页，多取一条判断是否还有下一页
+    pub async fn by_owner(&self, chain_id: i64, wallet: &str, f: &TicketFilter, after: Option<i64>, limit: i64) -> Result<(Vec<TicketDetail>, Option<i64>)> {
+        let wallet = wallet.to_lowercase();
+        let mut qb: QueryBuilder<Postgres> = QueryBuilder::new(TICKET_SELECT);
+        qb.push_bind(chain_id).push(" AND tk.token_id = t.token_id");
+        // OR 分支分别命中 tickets(owner_wallet) 与 ticket_tokens(chain_id, owner) 索引，COALESCE 排除已转出的旧记录
+        qb.push(" WHERE (t.owner_wallet = ").push_bind(wallet.clone())
+            .push(" OR tk.owner = ").push_bind(wallet.clone())
+            .push(") AND COALESCE(tk.owner, t.owner_wallet) = ").push_bind(wallet);
+        if let Some(id) = f.event_id {
+            qb.push(" AND t.event_id = ").push_bind(id);
+        }
+        if let Some(s) = &f.status {
+            qb.push(" AND t.status = ").push_bind(s.clone());
+        }
+        if let Some(a) = after {
+            qb.push(" AND t.token_id > ").push_bind(a);
+        }
+        qb.push(" ORDER BY t.token_id LIMIT ").push_bind(limit + 1);
+
+        let rows = qb.build().fetch_all(&self.db.0).await?;
+        let mut items = rows.iter().map(ticket_from_row).collect::<Result<Vec<_>>>()?;
+        let next = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items.last().map(|t| t.token_id)
+        } else {
+            None
+        };
+        self.attach(chain_id, &mut items).await?;
+        Ok((items, next))
+    }
+
+    // 元数据缓存版本（无链上投影时为 0）
+    pub async fn metadata_version(&self, chain_id: i64, token_id: i64) -> Result<(i64, Option<DateTime<Utc>>)> {
+        let rec = sqlx::query!("SELECT metadata_version, metadata_updated_at FROM ticket_tokens WHERE chain_id=$1 AND token_id=$2::BIGINT::NUMERIC",
+            chain_id, token_id)
+            .fetch_optional(&self.db.0).await?;
+        Ok(rec.map(|r| (r.metadata_version, r.metadata_updated_at)).unwrap_or((0, None)))
+    }
+
+    // MetadataUpdate / BatchMetadataUpdate：区间两端为十进制文本（uint256 可能超出 BIGINT）
+    pub async fn bump_metadata(&self, chain_id: i64, from_token: &str, to_token: &str) -> Result<u64> {
+        let res = sqlx::query!(r#"UPDATE ticket_tokens SET metadata_version = metadata_version + 1, metadata_updated_at = NOW()
+            WHERE chain_id=$1 AND token_id BETWEEN $2::TEXT::NUMERIC AND $3::TEXT::NUMERIC"#,
+            chain_id, from_token, to_token)
+            .execute(&self.db.0).await?;
+        Ok(res.rows_affected())
+    }
+
+    // 批量补齐转移历史与当前挂单
+    async fn attach(&self, chain_id: i64, items: &mut [TicketDetail]) -> Result<()> {
+        let ids: Vec<i64> = items.iter().map(|t| t.token_id).collect();
+        let mut transfers = self.transfers(chain_id, &ids).await?;
+        let mut listings = self.active_listings(chain_id, &ids).await?;
+        for t in items.iter_mut() {
+            t.transfers = transfers.remove(&t.token_id).unwrap_or_default();
+            t.listing = listings.remove(&t.token_id);
+        }
+        Ok(())
+    }
+
+    pub async fn transfers(&self, chain_id: i64, token_ids: &[i64]) -> Result<HashMap<i64, Vec<TicketTransfer>>> {
+        let rows = sqlx::query!(r#"SELECT x.token_id::BIGINT AS "token_id!", x.block_number, b.block_time AS "block_time?",
+                x.tx_hash, x.log_index, x.from_addr, x.to_addr
+            FROM ticket_transfers x
+            LEFT JOIN chain_blocks b ON b.chain_id = x.chain_id AND b.block_number = x.block_number
+            WHERE x.chain_id = $1 AND x.token_id = ANY($2::BIGINT[]::NUMERIC[])
+            ORDER BY x.token_id, x.block_number, x.log_index"#, chain_id, token_ids)
+            .fetch_all(&self.db.0).await?;
+        let mut out: HashMap<i64, Vec<TicketTransfer>> = HashMap::new();
+        for r in rows {
+            out.entry(r.token_id).or_default().push(TicketTransfer {
+                block_number: r.block_number,
+                block_time: r.block_time,
+                tx_hash: r.tx_hash,
+                log_index: r.log_index,
+                from: r.from_addr,
+                to: r.to_addr,
+            });
+        }
+        Ok(out)
+    }
+
+    pub async fn active_listings(&self, chain_id: i64, token_ids: &[i64]) -> Result<HashMap<i64, ActiveListing>> {
+        let mut out = HashMap::new();
+        let offchain = sqlx::query!(r#"SELECT id, ticket_id, seller_wallet, price_wei::TEXT AS "price_wei!"
+            FROM market_listings WHERE status = 'active' AND ticket_id = ANY($1)"#, token_ids)
+            .fetch_all(&self.db.0).await?;
+        for r in offchain {
+            out.insert(r.ticket_id, ActiveListing {
+                source: "offchain",
+                listing_id: r.id.to_string(),
+                seller: r.seller_wallet,
+                price_wei: r.price_wei,
+            });
+        }
+        // 链上挂单覆盖链下记录
+        let onchain = sqlx::query!(r#"SELECT listing_id::TEXT AS "listing_id!", ticket_id::BIGINT AS "ticket_id!",
+                seller AS "seller!", price::TEXT AS "price!"
+            FROM marketplace_listings
+            WHERE chain_id = $1 AND status = $2 AND ticket_id = ANY($3::BIGINT[]::NUMERIC[])
+              AND seller IS NOT NULL AND price IS NOT NULL
+            ORDER BY created_block"#, chain_id, ListingStatus::Active.to_chain(), token_ids)
+            .fetch_all(&self.db.0).await?;
+        for r in onchain {
+            out.insert(r.ticket_id, ActiveListing {
+                source: "chain",
+                listing_id: r.listing_id,
+                seller: r.seller,
+                price_wei: r.price,
+            });
+        }
+        Ok(out)
+    }
+}
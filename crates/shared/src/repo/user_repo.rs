@@ -0,0 +1,94 @@
+use crate::db::pool::Db;
+use crate::domain::user::{Role, User};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+pub struct UserRepo<'a> { pub db: &'a Db }
+
+impl<'a> UserRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    pub async fn find(&self, wallet: &str) -> Result<Option<User>> {
+        let rec = sqlx::query_as!(User, "SELECT wallet, role, created_at FROM users WHERE wallet=$1", wallet)
+            .fetch_optional(&self.db.0).await?;
+        Ok(rec)
+    }
+
+    // SIWE 挑战：按 nonce 值存储，新挑战不影响同一钱包尚未使用的挑战；顺带清理已过期的
+    // 每个钱包最多保留 max_outstanding 个未使用挑战，超出时淘汰最早签发的
+    pub async fn put_login_nonce(&self, wallet: &str, nonce: &str, expires_at: DateTime<Utc>, max_outstanding: i64) -> Result<()> {
+        let mut tx = self.db.0.begin().await?;
+        sqlx::query!("DELETE FROM siwe_nonces WHERE expires_at <= NOW()")
+            .execute(&mut *tx).await?;
+        sqlx::query!("INSERT INTO siwe_nonces (wallet, nonce, expires_at) VALUES ($1,$2,$3)",
+            wallet, nonce, expires_at)
+            .execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM siwe_nonces WHERE wallet=$1 AND nonce NOT IN (
+                SELECT nonce FROM siwe_nonces WHERE wallet=$1 ORDER BY created_at DESC, nonce DESC LIMIT $2)",
+            wallet, max_outstanding)
+            .execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // 原子地消费 nonce：仅删除钱包与 nonce 均匹配且未过期的挑战，消费后不可重放
+    pub async fn consume_login_nonce(&self, wallet: &str, nonce: &str) -> Result<bool> {
+        let rec = sqlx::query!("DELETE FROM siwe_nonces WHERE wallet=$1 AND nonce=$2 AND expires_at > NOW() RETURNING 1 AS one",
+            wallet, nonce)
+            .fetch_optional(&self.db.0).await?;
+        Ok(rec.is_some())
+    }
+
+    // 首次登录建档；已有用户只轮换 users.nonce 并刷新登录时间，角色保持不变
+    pub async fn upsert_on_login(&self, wallet: &str, nonce: &str) -> Result<User> {
+        let rec = sqlx::query_as!(User, r#"INSERT INTO users (wallet, nonce, last_login_at) VALUES ($1,$2,NOW())
+            ON CONFLICT (wallet) DO UPDATE SET nonce=EXCLUDED.nonce, last_login_at=EXCLUDED.last_login_at
+            RETURNING wallet, role, created_at"#, wallet, nonce)
+            .fetch_one(&self.db.0).await?;
+        Ok(rec)
+    }
+
+    // 链上授权变更：仅当来源位置比已记录的更新时才覆盖（重放/乱序安全）
+    pub async fn apply_chain_role(&self, chain_id: i64, wallet: &str, role: Role, authorized: bool, block: i64, log_index: i32) -> Result<()> {
+        sqlx::query!(r#"INSERT INTO chain_roles (chain_id, wallet, role, authorized, block_number, log_index) VALUES ($1,$2,$3,$4,$5,$6)
+            ON CONFLICT (chain_id, wallet, role) DO UPDATE SET authorized=EXCLUDED.authorized, block_number=EXCLUDED.block_number,
+                log_index=EXCLUDED.log_index, updated_at=NOW()
+            WHERE (chain_roles.block_number, chain_roles.log_index) < (EXCLUDED.block_number, EXCLUDED.log_index)"#,
+            chain_id, wallet, role.as_str(), authorized, block, log_index)
+            .execute(&self.db.0).await?;
+        Ok(())
+    }
+
+    // 按 chain_roles 重算 users.role（保留手工授予的 admin）；用户不存在时建档
+    pub async fn sync_role(&self, wallet: &str) -> Result<String> {
+        let granted = sqlx::query_scalar!("SELECT role FROM chain_roles WHERE wallet=$1 AND authorized", wallet)
+            .fetch_all(&self.db.0).await?;
+        let mut set: std::collections::BTreeSet<Role> = self.find(wallet).await?
+            .map(|u| Role::parse_set(&u.role))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| *r == Role::Admin)
+            .collect();
+        set.extend(granted.iter().map(|r| Role::parse(r)).filter(|r| *r != Role::User));
+        let role = Role::format_set(&set);
+        sqlx::query!(r#"INSERT INTO users (wallet, role, nonce) VALUES ($1,$2,$3)
+            ON CONFLICT (wallet) DO UPDATE SET role=EXCLUDED.role"#,
+            wallet, role, uuid::Uuid::new_v4().simple().to_string())
+            .execute(&self.db.0).await?;
+        Ok(role)
+    }
+
+    // 需要与链上核对的钱包：有链上授权记录的钱包，及 since 之后登录过的用户（覆盖索引起点之前已获授权者）
+    // 按钱包地址键集分页，after 为上一页最后一个钱包
+    pub async fn role_sync_candidates(&self, chain_id: i64, since: DateTime<Utc>, after: &str, limit: i64) -> Result<Vec<String>> {
+        let rows = sqlx::query_scalar!(r#"SELECT wallet AS "wallet!" FROM (
+                SELECT wallet FROM chain_roles WHERE chain_id=$1
+                UNION SELECT wallet FROM users WHERE last_login_at >= $2
+            ) w
+            WHERE wallet > $3
+            ORDER BY wallet
+            LIMIT $4"#, chain_id, since, after, limit)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows)
+    }
+}
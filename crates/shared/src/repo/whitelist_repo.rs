@@ -0,0 +1,106 @@
+use crate::db::pool::Db;
+use crate::domain::event::whitelist::{Eligibility, WhitelistEntry, STATUS_CONFIRMED, STATUS_EXPIRED, STATUS_PENDING};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+pub struct WhitelistRepo<'a> { pub db: &'a Db }
+
+// 待确认条目及其链上 id，供索引器调用 isInPresaleWhitelist
+#[derive(Debug, Clone)]
+pub struct PendingEntry {
+    pub type_id: i64,
+    pub chain_event_id: i64,
+    pub chain_type_id: i64,
+    pub wallet: String,
+}
+
+impl<'a> WhitelistRepo<'a> {
+    pub fn new(db: &'a Db) -> Self { Self { db } }
+
+    // 给定钱包在该票种下的已有状态（仅返回存在的条目）
+    pub async fn statuses(&self, chain_id: i64, type_id: i64, wallets: &[String]) -> Result<HashMap<String, String>> {
+        let rows = sqlx::query!("SELECT wallet, status FROM presale_whitelist WHERE chain_id = $1 AND type_id = $2 AND wallet = ANY($3)",
+            chain_id, type_id, wallets)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| (r.wallet, r.status)).collect())
+    }
+
+    // 已确认的条目保持不变；重复导入的 pending / expired 条目恢复 pending 并刷新请求人与时间
+    pub async fn mark_pending(&self, chain_id: i64, type_id: i64, wallets: &[String], requested_by: &str) -> Result<u64> {
+        let res = sqlx::query!("INSERT INTO presale_whitelist(chain_id, type_id, wallet, status, requested_by)
+            SELECT $1, $2, w, $4, $5 FROM UNNEST($3::TEXT[]) AS w
+            ON CONFLICT (chain_id, type_id, wallet) DO UPDATE SET status = $4, requested_by = EXCLUDED.requested_by, requested_at = NOW(), checked_at = NULL
+            WHERE presale_whitelist.status <> $6",
+            chain_id, type_id, wallets, STATUS_PENDING, requested_by, STATUS_CONFIRMED)
+            .execute(&self.db.0).await?;
+        Ok(res.rows_affected())
+    }
+
+    // 按钱包地址 keyset 分页
+    pub async fn list(&self, chain_id: i64, type_id: i64, status: Option<&str>, after: Option<&str>, limit: i64) -> Result<Vec<WhitelistEntry>> {
+        let rows = sqlx::query_as!(WhitelistEntry, "SELECT wallet, status, requested_by, requested_at, confirmed_block
+            FROM presale_whitelist
+            WHERE chain_id = $1 AND type_id = $2
+              AND ($3::TEXT IS NULL OR status = $3)
+              AND ($4::TEXT IS NULL OR wallet > $4)
+            ORDER BY wallet
+            LIMIT $5", chain_id, type_id, status, after, limit)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows)
+    }
+
+    // 仅已上链的票种；草稿活动返回空
+    pub async fn eligibility(&self, chain_id: i64, event_id: i64, wallet: &str) -> Result<Vec<Eligibility>> {
+        let rows = sqlx::query!(r#"SELECT tt.id, tt.chain_type_id, w.status AS "status?"
+            FROM ticket_types tt
+            LEFT JOIN presale_whitelist w ON w.type_id = tt.id AND w.chain_id = $1 AND w.wallet = $3
+            WHERE tt.event_id = $2 AND tt.chain_type_id IS NOT NULL
+            ORDER BY tt.chain_type_id"#, chain_id, event_id, wallet)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| Eligibility {
+            type_id: r.id,
+            chain_type_id: r.chain_type_id,
+            eligible: r.status.as_deref() == Some(STATUS_CONFIRMED),
+            status: r.status,
+        }).collect())
+    }
+
+    // requested_at 早于 before 仍未上链的 pending 条目置为 expired，不再轮询
+    pub async fn expire_pending(&self, chain_id: i64, before: DateTime<Utc>) -> Result<u64> {
+        let res = sqlx::query!("UPDATE presale_whitelist SET status = $3
+            WHERE chain_id = $1 AND status = $4 AND requested_at < $2",
+            chain_id, before, STATUS_EXPIRED, STATUS_PENDING)
+            .execute(&self.db.0).await?;
+        Ok(res.rows_affected())
+    }
+
+    // 最久未检查的优先
+    pub async fn pending(&self, chain_id: i64, limit: i64) -> Result<Vec<PendingEntry>> {
+        let rows = sqlx::query!(r#"SELECT w.type_id, e.chain_event_id AS "chain_event_id!", tt.chain_type_id AS "chain_type_id!", w.wallet
+            FROM presale_whitelist w
+            JOIN ticket_types tt ON tt.id = w.type_id
+            JOIN events e ON e.id = tt.event_id
+            WHERE w.chain_id = $1 AND w.status = $2
+              AND e.chain_event_id IS NOT NULL AND tt.chain_type_id IS NOT NULL
+            ORDER BY w.checked_at NULLS FIRST, w.requested_at
+            LIMIT $3"#, chain_id, STATUS_PENDING, limit)
+            .fetch_all(&self.db.0).await?;
+        Ok(rows.into_iter().map(|r| PendingEntry {
+            type_id: r.type_id,
+            chain_event_id: r.chain_event_id,
+            chain_type_id: r.chain_type_id,
+            wallet: r.wallet,
+        }).collect())
+    }
+
+    pub async fn mark_checked(&self, chain_id: i64, type_id: i64, wallet: &str, confirmed_block: Option<i64>) -> Result<()> {
+        sqlx::query!("UPDATE presale_whitelist SET checked_at = NOW(),
+                status = CASE WHEN $4::BIGINT IS NULL THEN status ELSE $5 END,
+                confirmed_block = COALESCE($4, confirmed_block)
+            WHERE chain_id = $1 AND type_id = $2 AND wallet = $3",
+            chain_id, type_id, wallet, confirmed_block, STATUS_CONFIRMED)
+            .execute(&self.db.0).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+// 访问令牌（HS256 JWT）：sub 为小写钱包地址，role 取自 users.role，sid 为刷新令牌会话族 ID
+use anyhow::Result;
+use chrono::Utc;
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Claims {
+    pub sub: String,
+    pub role: String,
+    pub sid: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+pub fn issue(secret: &str, wallet: &str, role: &str, sid: &str, ttl_secs: i64) -> Result<(String, Claims)> {
+    let iat = Utc::now().timestamp();
+    let claims = Claims {
+        sub: wallet.to_string(),
+        role: role.to_string(),
+        sid: sid.to_string(),
+        iat,
+        exp: iat + ttl_secs,
+    };
+    let token = jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )?;
+    Ok((token, claims))
+}
+
+pub fn decode(secret: &str, token: &str) -> Result<Claims> {
+    let data = jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )?;
+    Ok(data.claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_decode_roundtrip() {
+        let (token, claims) = issue("s3cret", "0xabc", "user", "f1", 900).unwrap();
+        assert_eq!(claims.exp - claims.iat, 900);
+        assert_eq!(decode("s3cret", &token).unwrap(), claims);
+        assert!(decode("other", &token).is_err());
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        // 超出默认 60 秒 leeway
+        let (token, _) = issue("s3cret", "0xabc", "user", "f1", -120).unwrap();
+        assert!(decode("s3cret", &token).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod jwt;
+pub mod session;
+pub mod siwe;
@@ -0,0 +1,153 @@
+// 刷新令牌会话（Redis）
+// 令牌格式 `<family>.<secret>`；每次刷新都轮换出同族新令牌，旧令牌保留到过期用于重用检测。
+// 已用过的令牌再次出现视为泄露，整个会话族作废。
+//   rf:{family}  -> wallet（会话族存活标记，滑动过期）
+//   rt:{hash}    -> hash{family, wallet, uses}
+//   rw:{wallet}  -> set{family}（按钱包批量吊销）
+
+use alloy::primitives::keccak256;
+use anyhow::Result;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub family: String,
+    pub wallet: String,
+}
+
+#[derive(Debug)]
+pub enum Rotation {
+    Rotated(Session, String),
+    // 检测到重用，会话族已被吊销
+    Reused(Session),
+    Invalid,
+}
+
+#[derive(Clone)]
+pub struct SessionStore {
+    conn: ConnectionManager,
+    ttl_secs: i64,
+}
+
+fn family_key(family: &str) -> String {
+    format!("rf:{family}")
+}
+
+fn wallet_key(wallet: &str) -> String {
+    format!("rw:{wallet}")
+}
+
+// 仅存令牌哈希，Redis 泄露不会直接暴露可用令牌
+fn token_key(token: &str) -> String {
+    format!("rt:{:x}", keccak256(token.as_bytes()))
+}
+
+fn new_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+impl SessionStore {
+    pub fn new(conn: ConnectionManager, ttl_secs: i64) -> Self {
+        Self { conn, ttl_secs }
+    }
+
+    pub fn ttl_secs(&self) -> i64 {
+        self.ttl_secs
+    }
+
+    async fn mint(&self, s: &Session) -> Result<String> {
+        let token = format!("{}.{}", s.family, new_id());
+        let key = token_key(&token);
+        let ttl = self.ttl_secs;
+        redis::pipe()
+            .atomic()
+            .hset_multiple(&key, &[("family", s.family.as_str()), ("wallet", s.wallet.as_str())])
+            .ignore()
+            .expire(&key, ttl)
+            .ignore()
+            .set_ex(family_key(&s.family), &s.wallet, ttl as u64)
+            .ignore()
+            .sadd(wallet_key(&s.wallet), &s.family)
+            .ignore()
+            .expire(wallet_key(&s.wallet), ttl)
+            .ignore()
+            .query_async::<_, ()>(&mut self.conn.clone())
+            .await?;
+        Ok(token)
+    }
+
+    // 登录：开启新的会话族
+    pub async fn create(&self, wallet: &str) -> Result<(Session, String)> {
+        let s = Session {
+            family: new_id(),
+            wallet: wallet.to_string(),
+        };
+        let token = self.mint(&s).await?;
+        Ok((s, token))
+    }
+
+    pub async fn is_active(&self, family: &str) -> Result<bool> {
+        Ok(self.conn.clone().exists(family_key(family)).await?)
+    }
+
+    async fn lookup(&self, token: &str) -> Result<Option<Session>> {
+        let (family, wallet): (Option<String>, Option<String>) = redis::pipe()
+            .hget(token_key(token), "family")
+            .hget(token_key(token), "wallet")
+            .query_async(&mut self.conn.clone())
+            .await?;
+        Ok(family.zip(wallet).map(|(family, wallet)| Session { family, wallet }))
+    }
+
+    // 刷新：uses 计数原子自增，首次使用才轮换
+    pub async fn rotate(&self, token: &str) -> Result<Rotation> {
+        let Some(s) = self.lookup(token).await? else {
+            return Ok(Rotation::Invalid);
+        };
+        if !self.is_active(&s.family).await? {
+            return Ok(Rotation::Invalid);
+        }
+        let uses: i64 = self.conn.clone().hincr(token_key(token), "uses", 1).await?;
+        if uses > 1 {
+            self.revoke_family(&s).await?;
+            return Ok(Rotation::Reused(s));
+        }
+        let next = self.mint(&s).await?;
+        Ok(Rotation::Rotated(s, next))
+    }
+
+    pub async fn revoke_family(&self, s: &Session) -> Result<()> {
+        redis::pipe()
+            .atomic()
+            .del(family_key(&s.family))
+            .ignore()
+            .srem(wallet_key(&s.wallet), &s.family)
+            .ignore()
+            .query_async::<_, ()>(&mut self.conn.clone())
+            .await?;
+        Ok(())
+    }
+
+    // 登出：吊销令牌所属会话族；未知令牌视为已登出
+    pub async fn logout(&self, token: &str) -> Result<()> {
+        if let Some(s) = self.lookup(token).await? {
+            self.revoke_family(&s).await?;
+        }
+        Ok(())
+    }
+
+    // 吊销钱包的全部会话，返回吊销的会话族数量
+    pub async fn revoke_wallet(&self, wallet: &str) -> Result<usize> {
+        let mut conn = self.conn.clone();
+        let families: Vec<String> = conn.smembers(wallet_key(wallet)).await?;
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for f in families.iter() {
+            pipe.del(family_key(f)).ignore();
+        }
+        pipe.del(wallet_key(wallet)).ignore();
+        pipe.query_async::<_, ()>(&mut conn).await?;
+        Ok(families.len())
+    }
+}
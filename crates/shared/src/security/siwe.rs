@@ -0,0 +1,282 @@
+// EIP-4361 (Sign-In with Ethereum) 消息构造、解析、校验与签名验证
+// 签名验证先按 EOA (EIP-191 personal_sign) 恢复地址，失败且地址为合约时走 EIP-1271 isValidSignature。
+
+use crate::contracts::bindings::IERC1271;
+use crate::contracts::provider::SharedProvider;
+use alloy::primitives::{eip191_hash_message, fixed_bytes, Address, FixedBytes, Signature};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt;
+use thiserror::Error;
+
+const PREAMBLE: &str = " wants you to sign in with your Ethereum account:";
+// EIP-1271 isValidSignature 成功时返回的 magic value
+const EIP1271_MAGIC: FixedBytes<4> = fixed_bytes!("1626ba7e");
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SiweError {
+    #[error("malformed message: {0}")]
+    Malformed(&'static str),
+    #[error("domain mismatch")]
+    Domain,
+    #[error("chain id mismatch")]
+    ChainId,
+    #[error("message expired")]
+    Expired,
+    #[error("message not yet valid")]
+    NotYetValid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweMessage {
+    pub domain: String,
+    pub address: Address,
+    pub statement: Option<String>,
+    pub uri: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub nonce: String,
+    pub issued_at: DateTime<Utc>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+// 随机 nonce：EIP-4361 要求至少 8 位字母数字
+pub fn generate_nonce() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn ts(t: &DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+impl fmt::Display for SiweMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}{PREAMBLE}", self.domain)?;
+        writeln!(f, "{}", self.address.to_checksum(None))?;
+        writeln!(f)?;
+        // 无 statement 时仍保留空行（ABNF: address LF LF [statement LF] LF）
+        if let Some(s) = &self.statement {
+            writeln!(f, "{s}")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "URI: {}", self.uri)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Chain ID: {}", self.chain_id)?;
+        writeln!(f, "Nonce: {}", self.nonce)?;
+        write!(f, "Issued At: {}", ts(&self.issued_at))?;
+        if let Some(t) = &self.expiration_time {
+            write!(f, "\nExpiration Time: {}", ts(t))?;
+        }
+        if let Some(t) = &self.not_before {
+            write!(f, "\nNot Before: {}", ts(t))?;
+        }
+        if let Some(r) = &self.request_id {
+            write!(f, "\nRequest ID: {r}")?;
+        }
+        if !self.resources.is_empty() {
+            write!(f, "\nResources:")?;
+            for r in self.resources.iter() {
+                write!(f, "\n- {r}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SiweMessage {
+    pub fn parse(s: &str) -> Result<Self, SiweError> {
+        let mut lines = s.split('\n');
+        let domain = lines
+            .next()
+            .and_then(|l| l.strip_suffix(PREAMBLE))
+            .ok_or(SiweError::Malformed("preamble"))?
+            .to_string();
+        let address: Address = lines
+            .next()
+            .and_then(|l| l.parse().ok())
+            .ok_or(SiweError::Malformed("address"))?;
+        if lines.next() != Some("") {
+            return Err(SiweError::Malformed("blank line after address"));
+        }
+        let statement = match lines.next() {
+            Some("") => None,
+            Some(l) => {
+                if lines.next() != Some("") {
+                    return Err(SiweError::Malformed("blank line after statement"));
+                }
+                Some(l.to_string())
+            }
+            None => return Err(SiweError::Malformed("truncated")),
+        };
+
+        let mut field = |tag: &'static str| -> Result<String, SiweError> {
+            lines
+                .next()
+                .and_then(|l| l.strip_prefix(tag))
+                .map(str::to_string)
+                .ok_or(SiweError::Malformed(tag))
+        };
+        let uri = field("URI: ")?;
+        let version = field("Version: ")?;
+        if version != "1" {
+            return Err(SiweError::Malformed("version"));
+        }
+        let chain_id = field("Chain ID: ")?
+            .parse()
+            .map_err(|_| SiweError::Malformed("chain id"))?;
+        let nonce = field("Nonce: ")?;
+        if nonce.len() < 8 || !nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(SiweError::Malformed("nonce"));
+        }
+        let issued_at = parse_time(&field("Issued At: ")?)?;
+
+        let mut msg = SiweMessage {
+            domain,
+            address,
+            statement,
+            uri,
+            version,
+            chain_id,
+            nonce,
+            issued_at,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: vec![],
+        };
+        let mut in_resources = false;
+        for l in lines {
+            if in_resources {
+                let r = l.strip_prefix("- ").ok_or(SiweError::Malformed("resource"))?;
+                msg.resources.push(r.to_string());
+            } else if let Some(v) = l.strip_prefix("Expiration Time: ") {
+                msg.expiration_time = Some(parse_time(v)?);
+            } else if let Some(v) = l.strip_prefix("Not Before: ") {
+                msg.not_before = Some(parse_time(v)?);
+            } else if let Some(v) = l.strip_prefix("Request ID: ") {
+                msg.request_id = Some(v.to_string());
+            } else if l == "Resources:" {
+                in_resources = true;
+            } else {
+                return Err(SiweError::Malformed("unexpected line"));
+            }
+        }
+        Ok(msg)
+    }
+
+    // 校验与签名无关的字段：域名、链 ID、有效期
+    pub fn validate(&self, domain: &str, chain_id: u64, now: DateTime<Utc>) -> Result<(), SiweError> {
+        if self.domain != domain {
+            return Err(SiweError::Domain);
+        }
+        if self.chain_id != chain_id {
+            return Err(SiweError::ChainId);
+        }
+        if self.expiration_time.is_some_and(|t| now >= t) {
+            return Err(SiweError::Expired);
+        }
+        if self.not_before.is_some_and(|t| now < t) {
+            return Err(SiweError::NotYetValid);
+        }
+        Ok(())
+    }
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>, SiweError> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| SiweError::Malformed("timestamp"))
+}
+
+// `raw` 必须是用户实际签名的原文（逐字节），不能用重新格式化后的消息
+pub async fn verify_signature(
+    provider: &SharedProvider,
+    address: Address,
+    raw: &str,
+    signature: &[u8],
+) -> anyhow::Result<bool> {
+    let hash = eip191_hash_message(raw.as_bytes());
+    if signature.len() == 65 {
+        if let Ok(sig) = Signature::try_from(signature) {
+            if sig.recover_address_from_prehash(&hash).ok() == Some(address) {
+                return Ok(true);
+            }
+        }
+    }
+    // 合约钱包：仅当地址上有代码时才尝试 EIP-1271
+    let code = provider.get_code_at(address).await?;
+    if code.is_empty() {
+        return Ok(false);
+    }
+    let wallet = IERC1271::new(address, provider.clone());
+    match wallet
+        .isValidSignature(hash, signature.to_vec().into())
+        .call()
+        .await
+    {
+        Ok(r) => Ok(r.magicValue == EIP1271_MAGIC),
+        Err(e) => {
+            tracing::debug!(?e, %address, "isValidSignature reverted");
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+    use chrono::{Duration, TimeZone};
+
+    fn sample() -> SiweMessage {
+        let issued = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        SiweMessage {
+            domain: "app.onlineticket.xyz".into(),
+            address: address!("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"),
+            statement: Some("Sign in to OnlineTicket".into()),
+            uri: "https://app.onlineticket.xyz".into(),
+            version: "1".into(),
+            chain_id: 11155111,
+            nonce: "a1b2c3d4e5f6a7b8".into(),
+            issued_at: issued,
+            expiration_time: Some(issued + Duration::minutes(5)),
+            not_before: None,
+            request_id: None,
+            resources: vec!["https://app.onlineticket.xyz/terms".into()],
+        }
+    }
+
+    #[test]
+    fn display_parse_roundtrip() {
+        let m = sample();
+        let text = m.to_string();
+        assert!(text.starts_with("app.onlineticket.xyz wants you to sign in"));
+        assert_eq!(SiweMessage::parse(&text).unwrap(), m);
+
+        let mut bare = sample();
+        bare.statement = None;
+        bare.resources.clear();
+        assert_eq!(SiweMessage::parse(&bare.to_string()).unwrap(), bare);
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        let text = sample().to_string();
+        assert!(SiweMessage::parse(&text.replace("Nonce: a1b2c3d4e5f6a7b8", "Nonce: short")).is_err());
+        assert!(SiweMessage::parse(&text.replace("Version: 1", "Version: 2")).is_err());
+        assert!(SiweMessage::parse(&text.replace(" wants you", " would like you")).is_err());
+    }
+
+    #[test]
+    fn validate_checks_domain_chain_and_time() {
+        let m = sample();
+        let now = m.issued_at + Duration::minutes(1);
+        assert_eq!(m.validate("app.onlineticket.xyz", 11155111, now), Ok(()));
+        assert_eq!(m.validate("evil.xyz", 11155111, now), Err(SiweError::Domain));
+        assert_eq!(m.validate("app.onlineticket.xyz", 1, now), Err(SiweError::ChainId));
+        let late = m.issued_at + Duration::minutes(10);
+        assert_eq!(m.validate("app.onlineticket.xyz", 11155111, late), Err(SiweError::Expired));
+    }
+}